@@ -0,0 +1,46 @@
+use rust_stdf::diff::{diff_files, AlignBy, DiffOptions};
+use std::env;
+use std::time::Instant;
+
+fn main() {
+    // usage: stdf_diff <left> <right> [--by-id] [--tol <relative tolerance>]
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        println!("usage: stdf_diff <left> <right> [--by-id] [--tol <relative tolerance>]\n");
+        return;
+    }
+    let (left, right) = (&args[0], &args[1]);
+    let mut options = DiffOptions::default();
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--by-id" => options.align = AlignBy::PartId,
+            "--tol" => match flags.next().and_then(|t| t.parse().ok()) {
+                Some(tol) => options.rel_tol = tol,
+                None => {
+                    println!("--tol requires a number");
+                    return;
+                }
+            },
+            _ => {
+                println!("unknown option: {}", flag);
+                return;
+            }
+        }
+    }
+    println!("Left path: {}", left);
+    println!("Right path: {}", right);
+
+    let start_time = Instant::now();
+    match diff_files(left, right, options) {
+        Ok(diffs) => {
+            for d in diffs.iter() {
+                println!("{}", d);
+            }
+            println!("\n{} differences", diffs.len());
+        }
+        Err(e) => println!("{}", e),
+    }
+    let elapsed = start_time.elapsed().as_millis();
+    println!("elapsed time {} ms", elapsed);
+}
@@ -0,0 +1,28 @@
+use rust_stdf::merge::{merge_files, MergeOptions};
+use std::env;
+use std::time::Instant;
+
+fn main() {
+    // usage: stdf_merge <output> <input1> <input2> ...
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        println!("usage: stdf_merge <output> <input> [<input> ...]\n");
+        return;
+    }
+    let (dst, srcs) = (&args[0], &args[1..]);
+    println!("Output path: {}", dst);
+    for src in srcs {
+        println!("Input path: {}", src);
+    }
+
+    let start_time = Instant::now();
+    match merge_files(srcs, dst, MergeOptions::default()) {
+        Ok(report) => println!(
+            "\n{} files merged, {} parts, {} records",
+            report.file_count, report.part_count, report.record_count
+        ),
+        Err(e) => println!("{}", e),
+    }
+    let elapsed = start_time.elapsed().as_millis();
+    println!("elapsed time {} ms", elapsed);
+}
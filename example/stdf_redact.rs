@@ -0,0 +1,41 @@
+use rust_stdf::redact::{redact_file, RedactMode, RedactOptions};
+use std::env;
+use std::time::Instant;
+
+fn main() {
+    // usage: stdf_redact <input> <output> [--hash <salt>] [--test-names]
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        println!("usage: stdf_redact <input> <output> [--hash <salt>] [--test-names]\n");
+        return;
+    }
+    let (src, dst) = (&args[0], &args[1]);
+    let mut options = RedactOptions::default();
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--hash" => {
+                options.mode = RedactMode::Hash;
+                options.salt = flags.next().cloned().unwrap_or_default();
+            }
+            "--test-names" => options.test_names = true,
+            _ => {
+                println!("unknown option: {}", flag);
+                return;
+            }
+        }
+    }
+    println!("Input path: {}", src);
+    println!("Output path: {}", dst);
+
+    let start_time = Instant::now();
+    match redact_file(src, dst, options) {
+        Ok(report) => println!(
+            "\n{} records read, {} records written",
+            report.read_count, report.write_count
+        ),
+        Err(e) => println!("{}", e),
+    }
+    let elapsed = start_time.elapsed().as_millis();
+    println!("elapsed time {} ms", elapsed);
+}
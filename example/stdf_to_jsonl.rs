@@ -0,0 +1,58 @@
+use rust_stdf::jsonl_export::{to_jsonl, to_jsonl_stream, FlagFormat, JsonlOptions};
+use rust_stdf::stdf_file::StdfReader;
+use rust_stdf::stdf_record_type::get_code_from_rec_name;
+use std::env;
+use std::io;
+use std::time::Instant;
+
+fn main() {
+    // usage: stdf_to_jsonl <input> [<output>] [--hex] [--rec <PTR,PRR,...>]
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        println!("usage: stdf_to_jsonl <input> [<output>] [--hex] [--rec <PTR,PRR,...>]\n");
+        println!("records are written to stdout if <output> is omitted");
+        return;
+    }
+    let src = &args[0];
+    let mut dst = None;
+    let mut options = JsonlOptions::default();
+    let mut flags = args[1..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--hex" => options.flag_format = FlagFormat::Hex,
+            "--rec" => match flags.next() {
+                Some(names) => {
+                    options.rec_types = names
+                        .split(',')
+                        .map(|n| get_code_from_rec_name(n.trim()))
+                        .fold(0, |acc, code| acc | code)
+                }
+                None => {
+                    eprintln!("--rec requires a list of record names");
+                    return;
+                }
+            },
+            _ if dst.is_none() && !flag.starts_with("--") => dst = Some(flag),
+            _ => {
+                eprintln!("unknown option: {}", flag);
+                return;
+            }
+        }
+    }
+
+    let start_time = Instant::now();
+    let result = match dst {
+        Some(dst) => to_jsonl(src, dst, options),
+        None => match StdfReader::new(src) {
+            Ok(mut reader) => to_jsonl_stream(&mut reader, io::stdout().lock(), options),
+            Err(e) => Err(e),
+        },
+    };
+    // report to stderr, stdout may be the output
+    match result {
+        Ok(count) => eprintln!("{} records written", count),
+        Err(e) => eprintln!("{}", e),
+    }
+    let elapsed = start_time.elapsed().as_millis();
+    eprintln!("elapsed time {} ms", elapsed);
+}
@@ -0,0 +1,421 @@
+//
+// arrow_export.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_types::*;
+use crate::table_rows::{RowNormalizer, TableRow};
+use arrow::array::{
+    ArrayRef, BooleanBuilder, Float32Builder, Int16Builder, Int8Builder, StringBuilder,
+    UInt16Builder, UInt32Builder, UInt64Builder, UInt8Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use smart_default::SmartDefault;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Tables converted from STDF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StdfTable {
+    /// long format test results, one row per PTR,
+    /// FTR, or pin of MPR, linked to `Parts` by `part_index`
+    Results,
+    /// one row per PRR
+    Parts,
+    /// one row per test number and test type,
+    /// from the first PTR/MPR/FTR of the test
+    Tests,
+    /// one row per HBR and SBR
+    Bins,
+    /// one row per WRR
+    Wafers,
+}
+
+/// Options of `ArrowBatchBuilder`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct ArrowOptions {
+    /// max row count of a record batch,
+    /// a batch is emitted once it's full
+    #[default = 65536]
+    pub batch_size: usize,
+}
+
+/// define a builder of a table, the columns are named
+/// after the fields, `$nullable` must be true if
+/// `append_option` is used on the column
+macro_rules! table_builder {
+    ($name:ident { $($col:ident: $builder:ident => $dtype:expr, $nullable:expr;)+ }) => {
+        #[derive(Debug, Default)]
+        struct $name {
+            len: usize,
+            $($col: $builder,)+
+        }
+
+        impl $name {
+            fn schema() -> Schema {
+                Schema::new(vec![
+                    $(Field::new(stringify!($col), $dtype, $nullable),)+
+                ])
+            }
+
+            fn finish(&mut self) -> Result<RecordBatch, ArrowError> {
+                self.len = 0;
+                RecordBatch::try_new(
+                    Arc::new(Self::schema()),
+                    vec![$(Arc::new(self.$col.finish()) as ArrayRef,)+],
+                )
+            }
+        }
+    };
+}
+
+table_builder!(ResultsTable {
+    part_index: UInt64Builder => DataType::UInt64, false;
+    head_num: UInt8Builder => DataType::UInt8, false;
+    site_num: UInt8Builder => DataType::UInt8, false;
+    test_num: UInt32Builder => DataType::UInt32, false;
+    test_typ: StringBuilder => DataType::Utf8, false;
+    pmr_indx: UInt16Builder => DataType::UInt16, true;
+    result: Float32Builder => DataType::Float32, true;
+    passed: BooleanBuilder => DataType::Boolean, true;
+    test_flg: UInt8Builder => DataType::UInt8, false;
+});
+
+table_builder!(PartsTable {
+    part_index: UInt64Builder => DataType::UInt64, false;
+    lot_id: StringBuilder => DataType::Utf8, false;
+    wafer_id: StringBuilder => DataType::Utf8, true;
+    head_num: UInt8Builder => DataType::UInt8, false;
+    site_num: UInt8Builder => DataType::UInt8, false;
+    part_id: StringBuilder => DataType::Utf8, false;
+    x_coord: Int16Builder => DataType::Int16, true;
+    y_coord: Int16Builder => DataType::Int16, true;
+    hard_bin: UInt16Builder => DataType::UInt16, false;
+    soft_bin: UInt16Builder => DataType::UInt16, true;
+    num_test: UInt16Builder => DataType::UInt16, false;
+    test_t: UInt32Builder => DataType::UInt32, false;
+    passed: BooleanBuilder => DataType::Boolean, true;
+});
+
+table_builder!(TestsTable {
+    test_num: UInt32Builder => DataType::UInt32, false;
+    test_typ: StringBuilder => DataType::Utf8, false;
+    test_txt: StringBuilder => DataType::Utf8, false;
+    units: StringBuilder => DataType::Utf8, false;
+    res_scal: Int8Builder => DataType::Int8, false;
+    lo_limit: Float32Builder => DataType::Float32, true;
+    hi_limit: Float32Builder => DataType::Float32, true;
+});
+
+table_builder!(BinsTable {
+    bin_typ: StringBuilder => DataType::Utf8, false;
+    head_num: UInt8Builder => DataType::UInt8, false;
+    site_num: UInt8Builder => DataType::UInt8, false;
+    bin_num: UInt16Builder => DataType::UInt16, false;
+    bin_cnt: UInt32Builder => DataType::UInt32, false;
+    bin_pf: StringBuilder => DataType::Utf8, true;
+    bin_nam: StringBuilder => DataType::Utf8, false;
+});
+
+table_builder!(WafersTable {
+    head_num: UInt8Builder => DataType::UInt8, false;
+    wafer_id: StringBuilder => DataType::Utf8, false;
+    start_t: UInt32Builder => DataType::UInt32, true;
+    finish_t: UInt32Builder => DataType::UInt32, false;
+    part_cnt: UInt32Builder => DataType::UInt32, false;
+    rtst_cnt: UInt32Builder => DataType::UInt32, true;
+    abrt_cnt: UInt32Builder => DataType::UInt32, true;
+    good_cnt: UInt32Builder => DataType::UInt32, true;
+    func_cnt: UInt32Builder => DataType::UInt32, true;
+});
+
+impl StdfTable {
+    pub const ALL: [StdfTable; 5] = [
+        StdfTable::Results,
+        StdfTable::Parts,
+        StdfTable::Tests,
+        StdfTable::Bins,
+        StdfTable::Wafers,
+    ];
+
+    /// name of the table, e.g. "results"
+    pub fn name(&self) -> &'static str {
+        match self {
+            StdfTable::Results => "results",
+            StdfTable::Parts => "parts",
+            StdfTable::Tests => "tests",
+            StdfTable::Bins => "bins",
+            StdfTable::Wafers => "wafers",
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        Arc::new(match self {
+            StdfTable::Results => ResultsTable::schema(),
+            StdfTable::Parts => PartsTable::schema(),
+            StdfTable::Tests => TestsTable::schema(),
+            StdfTable::Bins => BinsTable::schema(),
+            StdfTable::Wafers => WafersTable::schema(),
+        })
+    }
+}
+
+/// Convert STDF records to Arrow `RecordBatch`es of
+/// the tables in `StdfTable`.
+///
+/// Rows of a table are emitted as a batch once `batch_size`
+/// is reached, caller should drain them by `take_batches`
+/// regularly to keep the memory usage bounded, the remaining
+/// rows are emitted by `finish`.
+///
+/// Rows are normalized by `RowNormalizer`, see it for how
+/// parts are numbered, missing values are written as null.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{arrow_export::*, PIR, PRR, PTR, StdfRecord};
+///
+/// let mut builder = ArrowBatchBuilder::new();
+/// for rec in [
+///     StdfRecord::PIR(PIR::default()),
+///     StdfRecord::PTR(PTR {
+///         test_num: 100,
+///         result: 1.5,
+///         ..Default::default()
+///     }),
+///     StdfRecord::PRR(PRR::default()),
+/// ] {
+///     builder.feed(&rec).unwrap();
+/// }
+/// for (table, batch) in builder.finish().unwrap() {
+///     match table {
+///         StdfTable::Results | StdfTable::Parts | StdfTable::Tests => {
+///             assert_eq!(batch.num_rows(), 1)
+///         }
+///         _ => assert_eq!(batch.num_rows(), 0),
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ArrowBatchBuilder {
+    options: ArrowOptions,
+    normalizer: RowNormalizer,
+    rows: Vec<TableRow>,
+    results: ResultsTable,
+    parts: PartsTable,
+    tests: TestsTable,
+    bins: BinsTable,
+    wafers: WafersTable,
+    ready: Vec<(StdfTable, RecordBatch)>,
+}
+
+impl ArrowBatchBuilder {
+    pub fn new() -> Self {
+        ArrowBatchBuilder::default()
+    }
+
+    pub fn with_options(options: ArrowOptions) -> Self {
+        ArrowBatchBuilder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// collect MIR, WIR, WRR, PIR, PRR, PTR, MPR,
+    /// FTR, HBR and SBR, other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) -> Result<(), StdfError> {
+        let mut rows = std::mem::take(&mut self.rows);
+        self.normalizer.feed(rec, &mut rows);
+        let result = rows.drain(..).try_for_each(|row| self.add_row(row));
+        self.rows = rows;
+        result
+    }
+
+    /// take the full batches emitted so far
+    #[inline(always)]
+    pub fn take_batches(&mut self) -> Vec<(StdfTable, RecordBatch)> {
+        std::mem::take(&mut self.ready)
+    }
+
+    /// emit the remaining rows of all tables, every table
+    /// has a batch even if it's empty, so that the schema
+    /// is always available
+    pub fn finish(mut self) -> Result<Vec<(StdfTable, RecordBatch)>, StdfError> {
+        let mut batches = std::mem::take(&mut self.ready);
+        batches.push((StdfTable::Results, self.results.finish()?));
+        batches.push((StdfTable::Parts, self.parts.finish()?));
+        batches.push((StdfTable::Tests, self.tests.finish()?));
+        batches.push((StdfTable::Bins, self.bins.finish()?));
+        batches.push((StdfTable::Wafers, self.wafers.finish()?));
+        Ok(batches)
+    }
+
+    fn add_row(&mut self, row: TableRow) -> Result<(), StdfError> {
+        let batch_size = self.options.batch_size;
+        let full = match row {
+            TableRow::Result(r) => {
+                let t = &mut self.results;
+                t.part_index.append_value(r.part_index);
+                t.head_num.append_value(r.head_num);
+                t.site_num.append_value(r.site_num);
+                t.test_num.append_value(r.test_num);
+                t.test_typ.append_value(r.test_typ.to_string());
+                t.pmr_indx.append_option(r.pmr_indx);
+                t.result.append_option(r.result);
+                t.passed.append_option(r.passed);
+                t.test_flg.append_value(r.test_flg);
+                t.len += 1;
+                (t.len >= batch_size).then_some(StdfTable::Results)
+            }
+            TableRow::Part(r) => {
+                let t = &mut self.parts;
+                t.part_index.append_value(r.part_index);
+                t.lot_id.append_value(&r.lot_id);
+                t.wafer_id.append_option(r.wafer_id);
+                t.head_num.append_value(r.head_num);
+                t.site_num.append_value(r.site_num);
+                t.part_id.append_value(&r.part_id);
+                t.x_coord.append_option(r.x_coord);
+                t.y_coord.append_option(r.y_coord);
+                t.hard_bin.append_value(r.hard_bin);
+                t.soft_bin.append_option(r.soft_bin);
+                t.num_test.append_value(r.num_test);
+                t.test_t.append_value(r.test_t);
+                t.passed.append_option(r.passed);
+                t.len += 1;
+                (t.len >= batch_size).then_some(StdfTable::Parts)
+            }
+            TableRow::Test(r) => {
+                let t = &mut self.tests;
+                t.test_num.append_value(r.test_num);
+                t.test_typ.append_value(r.test_typ.to_string());
+                t.test_txt.append_value(&r.test_txt);
+                t.units.append_value(&r.units);
+                t.res_scal.append_value(r.res_scal);
+                t.lo_limit.append_option(r.lo_limit);
+                t.hi_limit.append_option(r.hi_limit);
+                t.len += 1;
+                (t.len >= batch_size).then_some(StdfTable::Tests)
+            }
+            TableRow::Bin(r) => {
+                let t = &mut self.bins;
+                t.bin_typ.append_value(r.bin_typ.to_string());
+                t.head_num.append_value(r.head_num);
+                t.site_num.append_value(r.site_num);
+                t.bin_num.append_value(r.bin_num);
+                t.bin_cnt.append_value(r.bin_cnt);
+                t.bin_pf.append_option(r.bin_pf.map(|pf| pf.to_string()));
+                t.bin_nam.append_value(&r.bin_nam);
+                t.len += 1;
+                (t.len >= batch_size).then_some(StdfTable::Bins)
+            }
+            TableRow::Wafer(r) => {
+                let t = &mut self.wafers;
+                t.head_num.append_value(r.head_num);
+                t.wafer_id.append_value(&r.wafer_id);
+                t.start_t.append_option(r.start_t);
+                t.finish_t.append_value(r.finish_t);
+                t.part_cnt.append_value(r.part_cnt);
+                t.rtst_cnt.append_option(r.rtst_cnt);
+                t.abrt_cnt.append_option(r.abrt_cnt);
+                t.good_cnt.append_option(r.good_cnt);
+                t.func_cnt.append_option(r.func_cnt);
+                t.len += 1;
+                (t.len >= batch_size).then_some(StdfTable::Wafers)
+            }
+        };
+        if let Some(table) = full {
+            let batch = match table {
+                StdfTable::Results => self.results.finish()?,
+                StdfTable::Parts => self.parts.finish()?,
+                StdfTable::Tests => self.tests.finish()?,
+                StdfTable::Bins => self.bins.finish()?,
+                StdfTable::Wafers => self.wafers.finish()?,
+            };
+            self.ready.push((table, batch));
+        }
+        Ok(())
+    }
+}
+
+/// Convert the STDF file at `src` to Parquet files in
+/// the directory `dst_dir`, one file per table, named
+/// after `StdfTable::name`, e.g. `results.parquet`.
+/// Files are compressed by snappy.
+///
+/// Return the row count of every table.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::arrow_export::{write_parquet, ArrowOptions};
+///
+/// if let Ok(row_counts) = write_parquet("input.stdf", "lake/lot1", ArrowOptions::default()) {
+///     for (table, rows) in row_counts {
+///         println!("{}: {} rows", table.name(), rows);
+///     }
+/// }
+/// ```
+pub fn write_parquet<P, Q>(
+    src: P,
+    dst_dir: Q,
+    options: ArrowOptions,
+) -> Result<BTreeMap<StdfTable, u64>, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(src)?;
+    std::fs::create_dir_all(&dst_dir)?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writers = BTreeMap::new();
+    for table in StdfTable::ALL {
+        let path = dst_dir.as_ref().join(format!("{}.parquet", table.name()));
+        let writer =
+            ArrowWriter::try_new(File::create(path)?, table.schema(), Some(props.clone()))?;
+        writers.insert(table, writer);
+    }
+
+    let mut row_counts: BTreeMap<StdfTable, u64> = StdfTable::ALL.iter().map(|&t| (t, 0)).collect();
+    let mut write_batches = |batches: Vec<(StdfTable, RecordBatch)>| -> Result<(), StdfError> {
+        for (table, batch) in batches {
+            if let Some(writer) = writers.get_mut(&table) {
+                writer.write(&batch)?;
+            }
+            *row_counts.entry(table).or_insert(0) += batch.num_rows() as u64;
+        }
+        Ok(())
+    };
+
+    let mut builder = ArrowBatchBuilder::with_options(options);
+    for raw_element in reader.get_rawdata_iter() {
+        let raw_element = raw_element?;
+        if raw_element.is_type(RowNormalizer::REC_TYPES) {
+            builder.feed(&StdfRecord::from(&raw_element))?;
+            write_batches(builder.take_batches())?;
+        }
+    }
+    write_batches(builder.finish()?)?;
+    for (_, writer) in writers {
+        writer.close()?;
+    }
+    Ok(row_counts)
+}
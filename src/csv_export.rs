@@ -0,0 +1,441 @@
+//
+// csv_export.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::flags::PtrTestFlags;
+use crate::pinmap::PinMap;
+use crate::stats::LimitTracker;
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use smart_default::SmartDefault;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Columns that identify a part, written
+/// before the test columns of every row
+pub const PART_COLUMNS: [&str; 10] = [
+    "LOT_ID", "WAFER_ID", "PART_ID", "HEAD_NUM", "SITE_NUM", "X_COORD", "Y_COORD", "HARD_BIN",
+    "SOFT_BIN", "TEST_T",
+];
+
+/// Options of the CSV exporter
+#[derive(SmartDefault, Debug, Clone)]
+pub struct CsvOptions {
+    /// `,` for CSV, `\t` for TSV
+    #[default = ',']
+    pub delimiter: char,
+    /// export every pin of MPR as a column,
+    /// MPRs are ignored if false
+    #[default = true]
+    pub mpr_pins: bool,
+}
+
+/// A test column of the table, one for every
+/// PTR test number, or every pin of a MPR
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestColumn {
+    pub test_num: U4,
+    /// 'P' for PTR, 'M' for MPR
+    pub test_typ: C1,
+    pub test_txt: Cn,
+    /// position of the pin in `RTN_RSLT` of MPR,
+    /// `None` for PTR
+    pub pin_pos: Option<usize>,
+    /// logical name of the pin from PMR, or the PMR index
+    /// if the PMR is not found, empty for PTR
+    pub pin_nam: Cn,
+    pub units: Cn,
+    pub lo_limit: Option<R4>,
+    pub hi_limit: Option<R4>,
+}
+
+type ColumnKey = (U4, Option<usize>);
+
+/// Collect the test columns from PTR and MPR,
+/// in the order of their first appearance.
+///
+/// Names, units and limits of a column are taken
+/// from the first record of the test number.
+#[derive(Debug, Default)]
+pub struct TestColumnCollector {
+    options: CsvOptions,
+    limits: LimitTracker,
+    pins: PinMap,
+    index: HashMap<ColumnKey, usize>,
+    columns: Vec<TestColumn>,
+}
+
+/// Write the parts as a wide table, one row per part
+/// and one column per test, in CSV or TSV.
+///
+/// The table starts with 6 header rows of the test columns:
+/// `TEST_NUM`, `TEST_TXT`, `PIN`, `UNITS`, `LO_LIMIT` and
+/// `HI_LIMIT`, the label is in the first column. Then a row of
+/// the column names, i.e. `PART_COLUMNS` and test numbers,
+/// followed by the parts. Pin columns of MPR are named
+/// `{test_num}.{pin_nam}`, the position of the pin is used
+/// if the name is empty, or appended if it is not unique.
+///
+/// A row is written as soon as the PRR is fed, so the memory
+/// usage does not grow with the part count. Results that are
+/// not valid, or tests not executed on the part are empty.
+///
+/// The columns must be known in advance, which are
+/// collected by `TestColumnCollector` in the first pass.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{csv_export::*, PIR, PRR, PTR, StdfRecord};
+///
+/// let recs = [
+///     StdfRecord::PIR(PIR::default()),
+///     StdfRecord::PTR(PTR {
+///         test_num: 100,
+///         test_txt: "VDD".to_string(),
+///         result: 1.5,
+///         ..Default::default()
+///     }),
+///     StdfRecord::PRR(PRR {
+///         hard_bin: 1,
+///         ..Default::default()
+///     }),
+/// ];
+/// let mut collector = TestColumnCollector::new();
+/// recs.iter().for_each(|r| collector.feed(r));
+///
+/// let columns = collector.finish();
+/// let mut exporter = CsvExporter::new(Vec::new(), columns, CsvOptions::default()).unwrap();
+/// for rec in recs.iter() {
+///     exporter.feed(rec).unwrap();
+/// }
+/// let csv = String::from_utf8(exporter.finish().unwrap()).unwrap();
+/// let lines: Vec<&str> = csv.lines().collect();
+/// assert_eq!(lines[0], "TEST_NUM,,,,,,,,,,100");
+/// assert_eq!(lines[7], ",,,0,0,-32768,-32768,1,65535,0,1.5");
+/// ```
+pub struct CsvExporter<W: Write> {
+    writer: W,
+    options: CsvOptions,
+    index: HashMap<ColumnKey, usize>,
+    column_count: usize,
+    lot_id: Cn,
+    open_wafers: HashMap<U1, Cn>,
+    open_parts: HashMap<(U1, U1), Vec<Option<R4>>>,
+    row_count: u64,
+}
+
+impl TestColumnCollector {
+    pub fn new() -> Self {
+        TestColumnCollector::default()
+    }
+
+    pub fn with_options(options: CsvOptions) -> Self {
+        TestColumnCollector {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// collect PMR, PTR and MPR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::PMR(_) => self.pins.feed(rec),
+            StdfRecord::PTR(ptr_rec) => {
+                let limits = self.limits.resolve_ptr(ptr_rec);
+                if self.index.contains_key(&(ptr_rec.test_num, None)) {
+                    return;
+                }
+                self.add_column(TestColumn {
+                    test_num: ptr_rec.test_num,
+                    test_typ: 'P',
+                    test_txt: ptr_rec.test_txt.clone(),
+                    pin_pos: None,
+                    pin_nam: Cn::new(),
+                    units: limits.units,
+                    lo_limit: limits.lo_limit,
+                    hi_limit: limits.hi_limit,
+                });
+            }
+            StdfRecord::MPR(mpr_rec) if self.options.mpr_pins => {
+                let limits = self.limits.resolve_mpr(mpr_rec);
+                for pos in 0..mpr_rec.rtn_rslt.len() {
+                    if self.index.contains_key(&(mpr_rec.test_num, Some(pos))) {
+                        continue;
+                    }
+                    let pin_nam = match mpr_rec.rtn_indx.as_ref().and_then(|i| i.get(pos)) {
+                        Some(&pmr_indx) => self
+                            .pins
+                            .get_pin(mpr_rec.head_num, mpr_rec.site_num, pmr_indx)
+                            .map(|p| p.log_nam.clone())
+                            .unwrap_or_else(|| pmr_indx.to_string()),
+                        None => Cn::new(),
+                    };
+                    self.add_column(TestColumn {
+                        test_num: mpr_rec.test_num,
+                        test_typ: 'M',
+                        test_txt: mpr_rec.test_txt.clone(),
+                        pin_pos: Some(pos),
+                        pin_nam,
+                        units: limits.units.clone(),
+                        lo_limit: limits.lo_limit,
+                        hi_limit: limits.hi_limit,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn finish(self) -> Vec<TestColumn> {
+        self.columns
+    }
+
+    fn add_column(&mut self, column: TestColumn) {
+        self.index
+            .insert((column.test_num, column.pin_pos), self.columns.len());
+        self.columns.push(column);
+    }
+}
+
+impl<W: Write> CsvExporter<W> {
+    /// create an exporter of the columns
+    /// and write the header rows
+    pub fn new(
+        writer: W,
+        columns: Vec<TestColumn>,
+        options: CsvOptions,
+    ) -> Result<Self, StdfError> {
+        let index = columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| ((c.test_num, c.pin_pos), i))
+            .collect();
+        let mut exporter = CsvExporter {
+            writer,
+            options,
+            index,
+            column_count: columns.len(),
+            lot_id: Cn::new(),
+            open_wafers: HashMap::new(),
+            open_parts: HashMap::new(),
+            row_count: 0,
+        };
+        exporter.write_header(&columns)?;
+        Ok(exporter)
+    }
+
+    /// collect MIR, WIR, WRR, PIR, PTR and MPR,
+    /// a row is written for every PRR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) -> Result<(), StdfError> {
+        match rec {
+            StdfRecord::MIR(mir_rec) => self.lot_id = mir_rec.lot_id.clone(),
+            StdfRecord::WIR(wir_rec) => {
+                self.open_wafers
+                    .insert(wir_rec.head_num, wir_rec.wafer_id.clone());
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                self.open_wafers.remove(&wrr_rec.head_num);
+            }
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts.insert(
+                    (pir_rec.head_num, pir_rec.site_num),
+                    vec![None; self.column_count],
+                );
+            }
+            StdfRecord::PTR(ptr_rec) if PtrTestFlags::from(ptr_rec.test_flg).is_valid() => {
+                self.set_result(
+                    (ptr_rec.head_num, ptr_rec.site_num),
+                    (ptr_rec.test_num, None),
+                    ptr_rec.result,
+                );
+            }
+            StdfRecord::MPR(mpr_rec)
+                if self.options.mpr_pins && PtrTestFlags::from(mpr_rec.test_flg).is_valid() =>
+            {
+                for (pos, &result) in mpr_rec.rtn_rslt.iter().enumerate() {
+                    self.set_result(
+                        (mpr_rec.head_num, mpr_rec.site_num),
+                        (mpr_rec.test_num, Some(pos)),
+                        result,
+                    );
+                }
+            }
+            StdfRecord::PRR(prr_rec) => self.write_part(prr_rec)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// count of the part rows written
+    #[inline(always)]
+    pub fn get_row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    /// flush and return the inner writer
+    pub fn finish(mut self) -> Result<W, StdfError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn set_result(&mut self, site: (U1, U1), key: ColumnKey, result: R4) {
+        let column_count = self.column_count;
+        if let Some(&i) = self.index.get(&key) {
+            // results without PIR are still exported
+            self.open_parts
+                .entry(site)
+                .or_insert_with(|| vec![None; column_count])[i] = Some(result);
+        }
+    }
+
+    fn write_header(&mut self, columns: &[TestColumn]) -> Result<(), StdfError> {
+        let padding = vec![String::new(); PART_COLUMNS.len() - 1];
+        let labels = [
+            "TEST_NUM", "TEST_TXT", "PIN", "UNITS", "LO_LIMIT", "HI_LIMIT",
+        ];
+        for (i, label) in labels.iter().enumerate() {
+            let mut row = vec![label.to_string()];
+            row.extend(padding.iter().cloned());
+            row.extend(columns.iter().map(|c| match i {
+                0 => c.test_num.to_string(),
+                1 => c.test_txt.clone(),
+                2 => c.pin_nam.clone(),
+                3 => c.units.clone(),
+                4 => format_value(c.lo_limit),
+                _ => format_value(c.hi_limit),
+            }));
+            self.write_row(&row)?;
+        }
+        // pins of the same name in a MPR are named by position
+        let mut pin_counts: HashMap<(U4, &str), usize> = HashMap::new();
+        for c in columns.iter().filter(|c| c.pin_pos.is_some()) {
+            *pin_counts.entry((c.test_num, &c.pin_nam)).or_insert(0) += 1;
+        }
+        let mut row: Vec<String> = PART_COLUMNS.iter().map(|s| s.to_string()).collect();
+        row.extend(columns.iter().map(|c| match c.pin_pos {
+            None => c.test_num.to_string(),
+            Some(pos) if c.pin_nam.is_empty() => format!("{}.{}", c.test_num, pos),
+            Some(pos) if pin_counts[&(c.test_num, c.pin_nam.as_str())] > 1 => {
+                format!("{}.{}_{}", c.test_num, c.pin_nam, pos)
+            }
+            Some(_) => format!("{}.{}", c.test_num, c.pin_nam),
+        }));
+        self.write_row(&row)
+    }
+
+    fn write_part(&mut self, prr: &PRR) -> Result<(), StdfError> {
+        let results = self
+            .open_parts
+            .remove(&(prr.head_num, prr.site_num))
+            .unwrap_or_else(|| vec![None; self.column_count]);
+        let wafer_id = self
+            .open_wafers
+            .get(&prr.head_num)
+            .cloned()
+            .unwrap_or_default();
+        let mut row = vec![
+            self.lot_id.clone(),
+            wafer_id,
+            prr.part_id.clone(),
+            prr.head_num.to_string(),
+            prr.site_num.to_string(),
+            prr.x_coord.to_string(),
+            prr.y_coord.to_string(),
+            prr.hard_bin.to_string(),
+            prr.soft_bin.to_string(),
+            prr.test_t.to_string(),
+        ];
+        row.extend(results.into_iter().map(format_value));
+        self.write_row(&row)?;
+        self.row_count += 1;
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[String]) -> Result<(), StdfError> {
+        let delimiter = self.options.delimiter;
+        let line = row
+            .iter()
+            .map(|cell| escape_cell(cell, delimiter))
+            .collect::<Vec<String>>()
+            .join(&delimiter.to_string());
+        writeln!(self.writer, "{}", line)?;
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn format_value(value: Option<R4>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// quote the cell if it contains the delimiter,
+/// quotes or line breaks
+fn escape_cell(cell: &str, delimiter: char) -> String {
+    if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Export the STDF file at `src` to a CSV or TSV
+/// table at `dst`, return the count of part rows.
+///
+/// The STDF file is read twice, the first pass collects
+/// the test columns, the second pass writes the parts.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::csv_export::{export_csv_file, CsvOptions};
+///
+/// let options = CsvOptions {
+///     delimiter: '\t',
+///     ..Default::default()
+/// };
+/// if let Ok(row_count) = export_csv_file("input.stdf", "output.tsv", options) {
+///     println!("{} parts exported", row_count);
+/// }
+/// ```
+pub fn export_csv_file<P, Q>(src: P, dst: Q, options: CsvOptions) -> Result<u64, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut collector = TestColumnCollector::with_options(options.clone());
+    let mut reader = StdfReader::new(&src)?;
+    for raw_element in reader.get_rawdata_iter() {
+        let raw_element = raw_element?;
+        if raw_element.is_type(REC_PMR | REC_PTR | REC_MPR) {
+            collector.feed(&StdfRecord::from(&raw_element));
+        }
+    }
+
+    let writer = BufWriter::new(File::create(dst)?);
+    let mut exporter = CsvExporter::new(writer, collector.finish(), options)?;
+    let mut reader = StdfReader::new(&src)?;
+    for raw_element in reader.get_rawdata_iter() {
+        let raw_element = raw_element?;
+        if raw_element.is_type(REC_MIR | REC_WIR | REC_WRR | REC_PIR | REC_PRR | REC_PTR | REC_MPR)
+        {
+            exporter.feed(&StdfRecord::from(&raw_element))?;
+        }
+    }
+    let row_count = exporter.get_row_count();
+    exporter.finish()?;
+    Ok(row_count)
+}
@@ -0,0 +1,697 @@
+//
+// diff.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::flags::PtrTestFlags;
+use crate::stats::{LimitTracker, TestLimits};
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_types::*;
+use smart_default::SmartDefault;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+/// How the parts of two files are paired
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignBy {
+    /// the n-th PRR of the left file is paired with
+    /// the n-th PRR of the right file
+    #[default]
+    PartOrder,
+    /// parts of the same `PART_ID` are paired, the
+    /// last PRR of a `PART_ID` is used if it is retested.
+    /// parts with empty `PART_ID` are paired by their
+    /// position in the file
+    PartId,
+}
+
+/// Options of `DiffBuilder`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct DiffOptions {
+    pub align: AlignBy,
+    /// results are regarded as equal if
+    /// `|left - right| <= abs_tol + rel_tol * |left|`
+    #[default = 0.0]
+    pub abs_tol: f64,
+    #[default = 0.0]
+    pub rel_tol: f64,
+    /// compare MIR and SDR, timestamps
+    /// of MIR are never compared
+    #[default = true]
+    pub header: bool,
+}
+
+/// Category of a difference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// a field of MIR or SDR is changed,
+    /// or the record is present in one file only
+    HeaderChanged,
+    /// a test number is present in one file only
+    TestMissing,
+    /// `TEST_TXT` of the first record of a test is changed
+    TestNameChanged,
+    /// limits, specs or scales of a test are changed
+    LimitChanged,
+    /// units of a test are changed
+    UnitsChanged,
+    /// a part is present in one file only
+    PartMissing,
+    /// hard bin or soft bin of a part is changed
+    BinChanged,
+    /// a test is executed on the part in one file only
+    ResultMissing,
+    /// results of a test differ beyond the tolerance
+    ResultChanged,
+    /// pass/fail of a test is changed
+    PassFailChanged,
+}
+
+/// A difference between the left file and the right file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub kind: DiffKind,
+    /// label of the part, `#n` (1-based PRR position of
+    /// the left file, or the right file if the part is missing
+    /// in the left) if aligned by part order, `PART_ID` otherwise.
+    /// `None` if the difference is not related to a part
+    pub part: Option<String>,
+    /// `None` if the difference is not related to a test
+    pub test_num: Option<U4>,
+    /// name of the field that differs, e.g. `LOT_ID`, `HI_LIMIT`
+    pub field: String,
+    /// value in the left file, `None` if absent
+    pub left: Option<String>,
+    /// value in the right file, `None` if absent
+    pub right: Option<String>,
+}
+
+/// Compare two datalogs semantically, for tester-to-tester
+/// correlation or validating a new test program release.
+///
+/// Parts are paired by `DiffOptions::align`, tests are
+/// paired by test number. Effective limits of PTR and MPR
+/// are taken from the first record of every test number,
+/// results that are not valid are not compared.
+///
+/// Differences are sorted as: header, test definitions
+/// by test number, then parts in the order of the left file.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{diff::*, PIR, PRR, PTR, StdfRecord};
+///
+/// let mut builder = DiffBuilder::new();
+/// for (result, hard_bin) in [(1.0, 1), (1.5, 2)] {
+///     let recs = [
+///         StdfRecord::PIR(PIR::default()),
+///         StdfRecord::PTR(PTR {
+///             test_num: 100,
+///             result,
+///             ..Default::default()
+///         }),
+///         StdfRecord::PRR(PRR {
+///             hard_bin,
+///             ..Default::default()
+///         }),
+///     ];
+///     for rec in recs.iter() {
+///         if hard_bin == 1 {
+///             builder.feed_left(rec);
+///         } else {
+///             builder.feed_right(rec);
+///         }
+///     }
+/// }
+/// let diffs = builder.finish();
+/// assert_eq!(diffs[0].kind, DiffKind::BinChanged);
+/// assert_eq!(diffs[1].kind, DiffKind::ResultChanged);
+/// assert_eq!(diffs[1].test_num, Some(100));
+/// ```
+#[derive(Debug, Default)]
+pub struct DiffBuilder {
+    options: DiffOptions,
+    left: Datalog,
+    right: Datalog,
+}
+
+#[derive(Debug, Default)]
+struct Datalog {
+    mir: Option<MIR>,
+    sdrs: BTreeMap<(U1, U1), SDR>,
+    limits: LimitTracker,
+    tests: BTreeMap<U4, TestDef>,
+    open_parts: HashMap<(U1, U1), PartData>,
+    parts: Vec<PartData>,
+}
+
+#[derive(Debug)]
+struct TestDef {
+    test_txt: Cn,
+    limits: TestLimits,
+}
+
+#[derive(Debug, Default)]
+struct PartData {
+    part_id: Cn,
+    hard_bin: U2,
+    soft_bin: U2,
+    results: BTreeMap<U4, TestResult>,
+}
+
+#[derive(Debug)]
+struct TestResult {
+    /// empty if the results are not valid
+    values: Vec<R4>,
+    passed: Option<bool>,
+}
+
+/// push a `HeaderChanged` for every field that differs
+macro_rules! diff_fields {
+    ($diffs:expr, $left:expr, $right:expr, [$($field:ident),+]) => {{
+        $(
+            if $left.$field != $right.$field {
+                $diffs.push(Difference {
+                    kind: DiffKind::HeaderChanged,
+                    part: None,
+                    test_num: None,
+                    field: stringify!($field).to_uppercase(),
+                    left: Some($left.$field.to_string()),
+                    right: Some($right.$field.to_string()),
+                });
+            }
+        )+
+    }};
+}
+
+impl DiffBuilder {
+    pub fn new() -> Self {
+        DiffBuilder::default()
+    }
+
+    pub fn with_options(options: DiffOptions) -> Self {
+        DiffBuilder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// collect MIR, SDR, PIR, PRR, PTR, MPR and FTR
+    /// of the left file, other records are ignored.
+    #[inline(always)]
+    pub fn feed_left(&mut self, rec: &StdfRecord) {
+        self.left.feed(rec);
+    }
+
+    /// collect MIR, SDR, PIR, PRR, PTR, MPR and FTR
+    /// of the right file, other records are ignored.
+    #[inline(always)]
+    pub fn feed_right(&mut self, rec: &StdfRecord) {
+        self.right.feed(rec);
+    }
+
+    /// compare the collected data, parts
+    /// without PRR are not compared
+    pub fn finish(self) -> Vec<Difference> {
+        let mut diffs = vec![];
+        if self.options.header {
+            self.diff_header(&mut diffs);
+        }
+        self.diff_tests(&mut diffs);
+        self.diff_parts(&mut diffs);
+        diffs
+    }
+
+    fn diff_header(&self, diffs: &mut Vec<Difference>) {
+        match (&self.left.mir, &self.right.mir) {
+            (Some(l), Some(r)) => diff_fields!(
+                diffs,
+                l,
+                r,
+                [
+                    stat_num, mode_cod, rtst_cod, prot_cod, burn_tim, cmod_cod, lot_id, part_typ,
+                    node_nam, tstr_typ, job_nam, job_rev, sblot_id, oper_nam, exec_typ, exec_ver,
+                    test_cod, tst_temp, user_txt, aux_file, pkg_typ, famly_id, date_cod, facil_id,
+                    floor_id, proc_id, oper_frq, spec_nam, spec_ver, flow_id, setup_id, dsgn_rev,
+                    eng_id, rom_cod, serl_num, supr_nam
+                ]
+            ),
+            (None, None) => {}
+            (l, r) => diffs.push(Difference {
+                kind: DiffKind::HeaderChanged,
+                part: None,
+                test_num: None,
+                field: "MIR".to_string(),
+                left: l.as_ref().map(|_| "MIR".to_string()),
+                right: r.as_ref().map(|_| "MIR".to_string()),
+            }),
+        }
+
+        let keys: BTreeSet<&(U1, U1)> = self
+            .left
+            .sdrs
+            .keys()
+            .chain(self.right.sdrs.keys())
+            .collect();
+        for key in keys {
+            match (self.left.sdrs.get(key), self.right.sdrs.get(key)) {
+                (Some(l), Some(r)) => diff_fields!(
+                    diffs,
+                    l,
+                    r,
+                    [
+                        site_cnt, hand_typ, hand_id, card_typ, card_id, load_typ, load_id, dib_typ,
+                        dib_id, cabl_typ, cabl_id, cont_typ, cont_id, lasr_typ, lasr_id, extr_typ,
+                        extr_id
+                    ]
+                ),
+                (l, r) => {
+                    let label = format!("SDR head {} site group {}", key.0, key.1);
+                    diffs.push(Difference {
+                        kind: DiffKind::HeaderChanged,
+                        part: None,
+                        test_num: None,
+                        field: "SDR".to_string(),
+                        left: l.map(|_| label.clone()),
+                        right: r.map(|_| label.clone()),
+                    })
+                }
+            }
+        }
+    }
+
+    fn diff_tests(&self, diffs: &mut Vec<Difference>) {
+        let test_nums: BTreeSet<&U4> = self
+            .left
+            .tests
+            .keys()
+            .chain(self.right.tests.keys())
+            .collect();
+        for &test_num in test_nums {
+            let (l, r) = match (
+                self.left.tests.get(&test_num),
+                self.right.tests.get(&test_num),
+            ) {
+                (Some(l), Some(r)) => (l, r),
+                (l, r) => {
+                    diffs.push(Difference {
+                        kind: DiffKind::TestMissing,
+                        part: None,
+                        test_num: Some(test_num),
+                        field: "TEST_NUM".to_string(),
+                        left: l.map(|t| t.test_txt.clone()),
+                        right: r.map(|t| t.test_txt.clone()),
+                    });
+                    continue;
+                }
+            };
+            let mut push = |kind, field: &str, left: Option<String>, right: Option<String>| {
+                if left != right {
+                    diffs.push(Difference {
+                        kind,
+                        part: None,
+                        test_num: Some(test_num),
+                        field: field.to_string(),
+                        left,
+                        right,
+                    });
+                }
+            };
+            push(
+                DiffKind::TestNameChanged,
+                "TEST_TXT",
+                Some(l.test_txt.clone()),
+                Some(r.test_txt.clone()),
+            );
+            let (ll, rl) = (&l.limits, &r.limits);
+            for (field, left, right) in [
+                ("LO_LIMIT", ll.lo_limit, rl.lo_limit),
+                ("HI_LIMIT", ll.hi_limit, rl.hi_limit),
+                ("LO_SPEC", ll.lo_spec, rl.lo_spec),
+                ("HI_SPEC", ll.hi_spec, rl.hi_spec),
+            ] {
+                push(
+                    DiffKind::LimitChanged,
+                    field,
+                    left.map(|v| v.to_string()),
+                    right.map(|v| v.to_string()),
+                );
+            }
+            for (field, left, right) in [
+                ("RES_SCAL", ll.res_scal, rl.res_scal),
+                ("LLM_SCAL", ll.llm_scal, rl.llm_scal),
+                ("HLM_SCAL", ll.hlm_scal, rl.hlm_scal),
+            ] {
+                push(
+                    DiffKind::LimitChanged,
+                    field,
+                    Some(left.to_string()),
+                    Some(right.to_string()),
+                );
+            }
+            push(
+                DiffKind::UnitsChanged,
+                "UNITS",
+                Some(ll.units.clone()),
+                Some(rl.units.clone()),
+            );
+        }
+    }
+
+    fn diff_parts(&self, diffs: &mut Vec<Difference>) {
+        let (left_keys, right_keys) = (
+            self.left.part_keys(self.options.align),
+            self.right.part_keys(self.options.align),
+        );
+        let right_index: HashMap<&String, usize> = right_keys
+            .iter()
+            .map(|(key, index)| (key, *index))
+            .collect();
+        let left_index: HashMap<&String, usize> =
+            left_keys.iter().map(|(key, index)| (key, *index)).collect();
+
+        for (key, l_index) in left_keys.iter() {
+            let l = &self.left.parts[*l_index];
+            match right_index.get(key) {
+                Some(&r_index) => self.diff_part(diffs, key, l, &self.right.parts[r_index]),
+                None => diffs.push(Difference {
+                    kind: DiffKind::PartMissing,
+                    part: Some(key.clone()),
+                    test_num: None,
+                    field: "PART_ID".to_string(),
+                    left: Some(l.part_id.clone()),
+                    right: None,
+                }),
+            }
+        }
+        for (key, r_index) in right_keys.iter() {
+            if !left_index.contains_key(key) {
+                diffs.push(Difference {
+                    kind: DiffKind::PartMissing,
+                    part: Some(key.clone()),
+                    test_num: None,
+                    field: "PART_ID".to_string(),
+                    left: None,
+                    right: Some(self.right.parts[*r_index].part_id.clone()),
+                });
+            }
+        }
+    }
+
+    fn diff_part(&self, diffs: &mut Vec<Difference>, key: &str, l: &PartData, r: &PartData) {
+        let mut push =
+            |kind, test_num, field: &str, left: Option<String>, right: Option<String>| {
+                diffs.push(Difference {
+                    kind,
+                    part: Some(key.to_string()),
+                    test_num,
+                    field: field.to_string(),
+                    left,
+                    right,
+                });
+            };
+        for (field, left, right) in [
+            ("HARD_BIN", l.hard_bin, r.hard_bin),
+            ("SOFT_BIN", l.soft_bin, r.soft_bin),
+        ] {
+            if left != right {
+                push(
+                    DiffKind::BinChanged,
+                    None,
+                    field,
+                    Some(left.to_string()),
+                    Some(right.to_string()),
+                );
+            }
+        }
+
+        let test_nums: BTreeSet<&U4> = l.results.keys().chain(r.results.keys()).collect();
+        for &test_num in test_nums {
+            let (lr, rr) = match (l.results.get(&test_num), r.results.get(&test_num)) {
+                (Some(lr), Some(rr)) => (lr, rr),
+                (lr, rr) => {
+                    push(
+                        DiffKind::ResultMissing,
+                        Some(test_num),
+                        "RESULT",
+                        lr.map(|t| format_values(&t.values)),
+                        rr.map(|t| format_values(&t.values)),
+                    );
+                    continue;
+                }
+            };
+            if !self.values_close(&lr.values, &rr.values) {
+                push(
+                    DiffKind::ResultChanged,
+                    Some(test_num),
+                    "RESULT",
+                    Some(format_values(&lr.values)),
+                    Some(format_values(&rr.values)),
+                );
+            }
+            if lr.passed != rr.passed {
+                push(
+                    DiffKind::PassFailChanged,
+                    Some(test_num),
+                    "TEST_FLG",
+                    lr.passed.map(format_passed),
+                    rr.passed.map(format_passed),
+                );
+            }
+        }
+    }
+
+    fn values_close(&self, left: &[R4], right: &[R4]) -> bool {
+        left.len() == right.len()
+            && left.iter().zip(right.iter()).all(|(&l, &r)| {
+                let (l, r) = (l as f64, r as f64);
+                l == r
+                    || (l.is_nan() && r.is_nan())
+                    || (l - r).abs() <= self.options.abs_tol + self.options.rel_tol * l.abs()
+            })
+    }
+}
+
+impl Datalog {
+    fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::MIR(mir_rec) => self.mir = Some(mir_rec.clone()),
+            StdfRecord::SDR(sdr_rec) => {
+                self.sdrs
+                    .insert((sdr_rec.head_num, sdr_rec.site_grp), sdr_rec.clone());
+            }
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts
+                    .insert((pir_rec.head_num, pir_rec.site_num), PartData::default());
+            }
+            StdfRecord::PRR(prr_rec) => {
+                let mut part = self
+                    .open_parts
+                    .remove(&(prr_rec.head_num, prr_rec.site_num))
+                    .unwrap_or_default();
+                part.part_id = prr_rec.part_id.clone();
+                part.hard_bin = prr_rec.hard_bin;
+                part.soft_bin = prr_rec.soft_bin;
+                self.parts.push(part);
+            }
+            StdfRecord::PTR(ptr_rec) => {
+                let limits = self.limits.resolve_ptr(ptr_rec);
+                self.add_result(
+                    (ptr_rec.head_num, ptr_rec.site_num, ptr_rec.test_num),
+                    &ptr_rec.test_txt,
+                    limits,
+                    ptr_rec.test_flg,
+                    &[ptr_rec.result],
+                );
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                let limits = self.limits.resolve_mpr(mpr_rec);
+                self.add_result(
+                    (mpr_rec.head_num, mpr_rec.site_num, mpr_rec.test_num),
+                    &mpr_rec.test_txt,
+                    limits,
+                    mpr_rec.test_flg,
+                    &mpr_rec.rtn_rslt,
+                );
+            }
+            StdfRecord::FTR(ftr_rec) => {
+                self.add_result(
+                    (ftr_rec.head_num, ftr_rec.site_num, ftr_rec.test_num),
+                    &ftr_rec.test_txt,
+                    TestLimits::default(),
+                    ftr_rec.test_flg,
+                    &[],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn add_result(
+        &mut self,
+        key: (U1, U1, U4),
+        test_txt: &str,
+        limits: TestLimits,
+        test_flg: B1,
+        values: &[R4],
+    ) {
+        let test_def = self.tests.entry(key.2).or_insert_with(|| TestDef {
+            test_txt: test_txt.to_string(),
+            limits,
+        });
+        if test_def.test_txt.is_empty() {
+            test_def.test_txt = test_txt.to_string();
+        }
+
+        let flags = PtrTestFlags::from(test_flg);
+        if flags.not_executed() {
+            return;
+        }
+        let values = if flags.is_valid() {
+            values.to_vec()
+        } else {
+            vec![]
+        };
+        self.open_parts
+            .entry((key.0, key.1))
+            .or_default()
+            .results
+            .insert(
+                key.2,
+                TestResult {
+                    values,
+                    passed: flags.passed(),
+                },
+            );
+    }
+
+    /// (alignment key, index of part)
+    fn part_keys(&self, align: AlignBy) -> Vec<(String, usize)> {
+        match align {
+            AlignBy::PartOrder => (0..self.parts.len())
+                .map(|i| (format!("#{}", i + 1), i))
+                .collect(),
+            AlignBy::PartId => {
+                // keep the position of the first PRR,
+                // but the index of the last one
+                let mut keys: Vec<(String, usize)> = vec![];
+                let mut positions: HashMap<String, usize> = HashMap::new();
+                for (i, part) in self.parts.iter().enumerate() {
+                    let key = if part.part_id.is_empty() {
+                        format!("#{}", i + 1)
+                    } else {
+                        part.part_id.clone()
+                    };
+                    match positions.get(&key) {
+                        Some(&pos) => keys[pos].1 = i,
+                        None => {
+                            positions.insert(key.clone(), keys.len());
+                            keys.push((key, i));
+                        }
+                    }
+                }
+                keys
+            }
+        }
+    }
+}
+
+fn format_values(values: &[R4]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn format_passed(passed: bool) -> String {
+    if passed { "pass" } else { "fail" }.to_string()
+}
+
+impl fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DiffKind::HeaderChanged => "header changed",
+            DiffKind::TestMissing => "test missing",
+            DiffKind::TestNameChanged => "test name changed",
+            DiffKind::LimitChanged => "limit changed",
+            DiffKind::UnitsChanged => "units changed",
+            DiffKind::PartMissing => "part missing",
+            DiffKind::BinChanged => "bin changed",
+            DiffKind::ResultMissing => "result missing",
+            DiffKind::ResultChanged => "result changed",
+            DiffKind::PassFailChanged => "pass/fail changed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(part) = &self.part {
+            write!(f, ", part {}", part)?;
+        }
+        if let Some(test_num) = self.test_num {
+            write!(f, ", test {}", test_num)?;
+        }
+        let absent = "<absent>".to_string();
+        write!(
+            f,
+            ": {} {} -> {}",
+            self.field,
+            self.left.as_ref().unwrap_or(&absent),
+            self.right.as_ref().unwrap_or(&absent)
+        )
+    }
+}
+
+/// Compare the STDF files at `left` and `right`,
+/// error is returned if any file cannot be read
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::diff::{diff_files, AlignBy, DiffOptions};
+///
+/// let options = DiffOptions {
+///     align: AlignBy::PartId,
+///     abs_tol: 1e-6,
+///     rel_tol: 0.01,
+///     ..Default::default()
+/// };
+/// if let Ok(diffs) = diff_files("tester_a.stdf", "tester_b.stdf", options) {
+///     for d in diffs {
+///         println!("{}", d);
+///     }
+/// }
+/// ```
+pub fn diff_files<P, Q>(
+    left: P,
+    right: Q,
+    options: DiffOptions,
+) -> Result<Vec<Difference>, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut builder = DiffBuilder::with_options(options);
+    let mut reader = StdfReader::new(left)?;
+    for rec in reader.get_record_iter() {
+        builder.feed_left(&rec?);
+    }
+    let mut reader = StdfReader::new(right)?;
+    for rec in reader.get_record_iter() {
+        builder.feed_right(&rec?);
+    }
+    Ok(builder.finish())
+}
@@ -0,0 +1,427 @@
+//
+// faildata.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_types::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// A fail logged in STR, fields are `None`
+/// if the corresponding array is absent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StrFail {
+    /// `CYC_BASE` + `CYC_OFST`
+    pub cycle: Option<U8>,
+    /// PMR index of the failing pin
+    pub pmr_indx: Option<U2>,
+    pub expected: Option<U1>,
+    pub captured: Option<U1>,
+    pub new_data: Option<U1>,
+    pub pattern: Option<U8>,
+    pub chain: Option<U8>,
+    /// `BIT_BASE` + `BIT_POS`
+    pub bit_pos: Option<U8>,
+}
+
+/// Fails of a test, collected from an STR
+/// and its continuation records
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StrFails {
+    pub test_num: U4,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub fails: Vec<StrFail>,
+}
+
+/// A declared count of STR that does not match the data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrCountMismatch {
+    pub test_num: U4,
+    pub head_num: U1,
+    pub site_num: U1,
+    /// `TOTL_CNT`, or `REC_LEN` if the array counts exceed
+    /// the record, `declared` and `actual` are the record
+    /// lengths in bytes in this case
+    pub field: &'static str,
+    pub declared: U4,
+    pub actual: U4,
+}
+
+/// An adapter over `RawDataIter` that merges an STR and
+/// its continuation records (`CONT_FLG` != 0) of the same
+/// test/head/site into one STR, other records are passed
+/// through after parsing.
+///
+/// The merged STR is yielded at the position of the last
+/// record of the sequence, with the arrays concatenated and
+/// the counts and data sizes updated (counts are clamped to
+/// 65535, the arrays keep all elements). Offsets of the
+/// continuation records are rebased to `CYC_BASE` and
+/// `BIT_BASE` of the first record. Sequences that are not
+/// ended at the end of the stream are yielded with
+/// `CONT_FLG` != 0.
+///
+/// The array counts of every record are checked against the
+/// record length, and `TOTL_CNT` of the merged STR against
+/// the logged fails, see `get_mismatches`.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{faildata::StrMergeIter, stdf_file::*, StdfRecord};
+///
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     let mut merged_iter = StrMergeIter::new(reader.get_rawdata_iter());
+///     for rec in merged_iter.by_ref().flatten() {
+///         if let StdfRecord::STR(str_rec) = rec {
+///             println!("{} fails", str_rec.fails().len());
+///         }
+///     }
+///     for mismatch in merged_iter.get_mismatches() {
+///         println!("{:?}", mismatch);
+///     }
+/// }
+/// ```
+pub struct StrMergeIter<I> {
+    inner: I,
+    open_tests: BTreeMap<(U4, U1, U1), STR>,
+    mismatches: Vec<StrCountMismatch>,
+}
+
+/// Collect the fails of STR records, the fails of
+/// continuation records (`CONT_FLG` != 0) are merged
+/// into the STR that ends the sequence.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{faildata::StrFailDecoder, stdf_file::*, StdfRecord};
+///
+/// let mut decoder = StrFailDecoder::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         if let StdfRecord::STR(str_rec) = &rec {
+///             if let Some(str_fails) = decoder.feed(str_rec) {
+///                 for fail in str_fails.fails {
+///                     println!("{:?} {:?}", fail.cycle, fail.pmr_indx);
+///                 }
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct StrFailDecoder {
+    open_tests: HashMap<(U4, U1, U1), Vec<StrFail>>,
+}
+
+impl StrFailDecoder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        StrFailDecoder::default()
+    }
+
+    /// decode the fails of an STR, returns all the fails of
+    /// the test if it is the last record of the sequence,
+    /// otherwise the fails are kept until the last one is fed.
+    pub fn feed(&mut self, str_rec: &STR) -> Option<StrFails> {
+        let key = (str_rec.test_num, str_rec.head_num, str_rec.site_num);
+        let mut fails = self.open_tests.remove(&key).unwrap_or_default();
+        fails.extend(str_rec.fails());
+        if str_rec.cont_flg[0] != 0 {
+            self.open_tests.insert(key, fails);
+            None
+        } else {
+            Some(StrFails {
+                test_num: key.0,
+                head_num: key.1,
+                site_num: key.2,
+                fails,
+            })
+        }
+    }
+
+    /// return the fails of the sequences that are not
+    /// ended, e.g. the file is truncated
+    pub fn finish(self) -> Vec<StrFails> {
+        let mut pending: Vec<StrFails> = self
+            .open_tests
+            .into_iter()
+            .map(|((test_num, head_num, site_num), fails)| StrFails {
+                test_num,
+                head_num,
+                site_num,
+                fails,
+            })
+            .collect();
+        pending.sort_by_key(|f| (f.test_num, f.head_num, f.site_num));
+        pending
+    }
+}
+
+/// return the indexes of the set bits in `Dn`,
+/// bit 0 of the first byte is index 0
+pub fn decode_bitmap(bitmap: &Dn) -> Vec<U2> {
+    let mut indexes = vec![];
+    for (i, &byte) in bitmap.iter().enumerate() {
+        for bit in 0..8 {
+            if byte & (1 << bit) != 0 {
+                let indx = i * 8 + bit;
+                // Dn holds 65535 bits at most
+                if indx > U2::MAX as usize {
+                    return indexes;
+                }
+                indexes.push(indx as U2);
+            }
+        }
+    }
+    indexes
+}
+
+impl FTR {
+    /// PMR indexes of the failing pins in `FAIL_PIN`
+    #[inline(always)]
+    pub fn fail_pins(&self) -> Vec<U2> {
+        decode_bitmap(&self.fail_pin)
+    }
+
+    /// PMR indexes of the enabled comparators in `SPIN_MAP`
+    #[inline(always)]
+    pub fn enabled_pins(&self) -> Vec<U2> {
+        decode_bitmap(&self.spin_map)
+    }
+}
+
+impl STR {
+    /// PMR indexes of the globally masked pins in `MASK_MAP`
+    #[inline(always)]
+    pub fn masked_pins(&self) -> Vec<U2> {
+        decode_bitmap(&self.mask_map)
+    }
+
+    /// PMR indexes of the pins failed after
+    /// the buffer is full, in `FAL_MAP`
+    #[inline(always)]
+    pub fn fal_pins(&self) -> Vec<U2> {
+        decode_bitmap(&self.fal_map)
+    }
+
+    /// decode the parallel arrays of logged fails in this
+    /// record, the continuation records are not merged,
+    /// see `StrFailDecoder` for merging.
+    pub fn fails(&self) -> Vec<StrFail> {
+        let count = [
+            self.cyc_ofst.len(),
+            self.pmr_indx.len(),
+            self.chn_num.len(),
+            self.exp_data.len(),
+            self.cap_data.len(),
+            self.new_data.len(),
+            self.pat_num.len(),
+            self.bit_pos.len(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+        (0..count)
+            .map(|i| StrFail {
+                cycle: self
+                    .cyc_ofst
+                    .get(i)
+                    .map(|ofst| self.cyc_base.saturating_add(ofst)),
+                pmr_indx: self
+                    .pmr_indx
+                    .get(i)
+                    .and_then(|indx| U2::try_from(indx).ok()),
+                expected: self.exp_data.get(i).copied(),
+                captured: self.cap_data.get(i).copied(),
+                new_data: self.new_data.get(i).copied(),
+                pattern: self.pat_num.get(i),
+                chain: self.chn_num.get(i),
+                bit_pos: self
+                    .bit_pos
+                    .get(i)
+                    .map(|pos| (self.bit_base as U8).saturating_add(pos)),
+            })
+            .collect()
+    }
+}
+
+impl<I> StrMergeIter<I>
+where
+    I: Iterator<Item = Result<RawDataElement, StdfError>>,
+{
+    pub fn new(inner: I) -> Self {
+        StrMergeIter {
+            inner,
+            open_tests: BTreeMap::new(),
+            mismatches: vec![],
+        }
+    }
+
+    /// counts that do not match the data,
+    /// of the STRs that have been yielded
+    #[inline(always)]
+    pub fn get_mismatches(&self) -> &[StrCountMismatch] {
+        &self.mismatches
+    }
+
+    fn check_count(&mut self, str_rec: &STR, field: &'static str, declared: U4, actual: usize) {
+        if declared as usize != actual {
+            self.mismatches.push(StrCountMismatch {
+                test_num: str_rec.test_num,
+                head_num: str_rec.head_num,
+                site_num: str_rec.site_num,
+                field,
+                declared,
+                actual: actual as U4,
+            });
+        }
+    }
+
+    fn close(&mut self, merged: STR) -> STR {
+        let logged = merged.fails().len();
+        // 4,294,967,295 indicates missing data
+        if merged.totl_cnt != 4_294_967_295 {
+            self.check_count(&merged, "TOTL_CNT", merged.totl_cnt, logged);
+        }
+        merged
+    }
+}
+
+impl<I> Iterator for StrMergeIter<I>
+where
+    I: Iterator<Item = Result<RawDataElement, StdfError>>,
+{
+    type Item = Result<StdfRecord, StdfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let raw_element = match self.inner.next() {
+                Some(Ok(raw_element)) => raw_element,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    let (_, merged) = self.open_tests.pop_first()?;
+                    return Some(Ok(StdfRecord::STR(self.close(merged))));
+                }
+            };
+            match StdfRecord::from(&raw_element) {
+                StdfRecord::STR(str_rec) => {
+                    // missing array elements are filled by default values
+                    // in parsing, so the record gets longer after serialization
+                    let expected_len = str_rec.write_to_bytes(&raw_element.byte_order).len();
+                    if expected_len > raw_element.raw_data.len() {
+                        self.check_count(
+                            &str_rec,
+                            "REC_LEN",
+                            expected_len as U4,
+                            raw_element.raw_data.len(),
+                        );
+                    }
+                    let key = (str_rec.test_num, str_rec.head_num, str_rec.site_num);
+                    let merged = match self.open_tests.remove(&key) {
+                        Some(mut first) => {
+                            merge_str(&mut first, &str_rec);
+                            first
+                        }
+                        None => str_rec,
+                    };
+                    if merged.cont_flg[0] != 0 {
+                        self.open_tests.insert(key, merged);
+                    } else {
+                        return Some(Ok(StdfRecord::STR(self.close(merged))));
+                    }
+                }
+                other => return Some(Ok(other)),
+            }
+        }
+    }
+}
+
+/// append the arrays of a continuation STR
+fn merge_str(base: &mut STR, next: &STR) {
+    // rebase the offsets to the first record
+    let mut cyc_ofst = next.cyc_ofst.clone();
+    if next.cyc_base != base.cyc_base {
+        let values = next
+            .cyc_ofst
+            .to_u64_vec()
+            .into_iter()
+            .map(|x| (next.cyc_base.saturating_add(x)).saturating_sub(base.cyc_base))
+            .collect();
+        cyc_ofst = KxUf::F8(values);
+    }
+    let mut bit_pos = next.bit_pos.clone();
+    if next.bit_base != base.bit_base {
+        let values = next
+            .bit_pos
+            .to_u64_vec()
+            .into_iter()
+            .map(|x| {
+                (next.bit_base as U8)
+                    .saturating_add(x)
+                    .saturating_sub(base.bit_base as U8)
+            })
+            .collect();
+        bit_pos = KxUf::F8(values);
+    }
+
+    base.cont_flg = next.cont_flg;
+    if base.mask_map.is_empty() {
+        base.mask_map = next.mask_map.clone();
+    }
+    // union of the pins failed after buffer full
+    if base.fal_map.len() < next.fal_map.len() {
+        base.fal_map.resize(next.fal_map.len(), 0);
+    }
+    for (byte, next_byte) in base.fal_map.iter_mut().zip(next.fal_map.iter()) {
+        *byte |= next_byte;
+    }
+    base.lim_indx.extend_from_slice(&next.lim_indx);
+    base.lim_spec.extend_from_slice(&next.lim_spec);
+    base.cond_lst.extend_from_slice(&next.cond_lst);
+    base.cyc_ofst.append(&cyc_ofst);
+    base.pmr_indx.append(&next.pmr_indx);
+    base.chn_num.append(&next.chn_num);
+    base.exp_data.extend_from_slice(&next.exp_data);
+    base.cap_data.extend_from_slice(&next.cap_data);
+    base.new_data.extend_from_slice(&next.new_data);
+    base.pat_num.append(&next.pat_num);
+    base.bit_pos.append(&bit_pos);
+    base.usr1.append(&next.usr1);
+    base.usr2.append(&next.usr2);
+    base.usr3.append(&next.usr3);
+    base.user_txt.extend_from_slice(&next.user_txt);
+    base.utx_size = base.utx_size.max(next.utx_size);
+
+    let count = |len: usize| len.min(U2::MAX as usize) as U2;
+    base.cond_cnt = count(base.cond_lst.len());
+    base.lim_cnt = count(base.lim_indx.len());
+    base.cyc_cnt = count(base.cyc_ofst.len());
+    base.pmr_cnt = count(base.pmr_indx.len());
+    base.chn_cnt = count(base.chn_num.len());
+    base.exp_cnt = count(base.exp_data.len());
+    base.cap_cnt = count(base.cap_data.len());
+    base.new_cnt = count(base.new_data.len());
+    base.pat_cnt = count(base.pat_num.len());
+    base.bpos_cnt = count(base.bit_pos.len());
+    base.usr1_cnt = count(base.usr1.len());
+    base.usr2_cnt = count(base.usr2.len());
+    base.usr3_cnt = count(base.usr3.len());
+    base.txt_cnt = count(base.user_txt.len());
+    base.cyc_size = base.cyc_ofst.size();
+    base.pmr_size = base.pmr_indx.size();
+    base.chn_size = base.chn_num.size();
+    base.pat_size = base.pat_num.size();
+    base.bit_size = base.bit_pos.size();
+    base.u1_size = base.usr1.size();
+    base.u2_size = base.usr2.size();
+    base.u3_size = base.usr3.size();
+}
@@ -0,0 +1,313 @@
+//
+// flags.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_types::*;
+
+/// define a flag type of a `B1` field and
+/// the accessors of its bits
+macro_rules! flag_type {
+    (
+        $(#[$type_doc:meta])*
+        $name:ident {
+            $(
+                $(#[$fn_doc:meta])*
+                $fn_name:ident => $mask:expr;
+            )*
+        }
+    ) => {
+        $(#[$type_doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+        pub struct $name(pub u8);
+
+        impl $name {
+            #[inline(always)]
+            pub fn new(flag: B1) -> Self {
+                $name(flag[0])
+            }
+
+            /// raw bits of the flag
+            #[inline(always)]
+            pub fn bits(&self) -> u8 {
+                self.0
+            }
+
+            $(
+                $(#[$fn_doc])*
+                #[inline(always)]
+                pub fn $fn_name(&self) -> bool {
+                    self.0 & $mask != 0
+                }
+            )*
+        }
+
+        impl From<B1> for $name {
+            #[inline(always)]
+            fn from(flag: B1) -> Self {
+                $name(flag[0])
+            }
+        }
+
+        impl From<$name> for B1 {
+            #[inline(always)]
+            fn from(flag: $name) -> Self {
+                [flag.0]
+            }
+        }
+    };
+}
+
+flag_type! {
+    /// `TEST_FLG` of PTR, MPR, FTR and STR
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_stdf::{flags::PtrTestFlags, PTR};
+    ///
+    /// let ptr = PTR {
+    ///     test_flg: [0b1000_0000],
+    ///     ..Default::default()
+    /// };
+    /// let flags = PtrTestFlags::from(ptr.test_flg);
+    /// assert!(flags.is_fail());
+    /// assert!(flags.is_valid());
+    /// assert_eq!(ptr.passed(), Some(false));
+    /// ```
+    PtrTestFlags {
+        /// bit 0: alarm detected during testing
+        alarm => 0b0000_0001;
+        /// bit 1: result is not valid
+        result_invalid => 0b0000_0010;
+        /// bit 2: result is unreliable
+        unreliable => 0b0000_0100;
+        /// bit 3: timeout occurred
+        timeout => 0b0000_1000;
+        /// bit 4: test is not executed
+        not_executed => 0b0001_0000;
+        /// bit 5: test is aborted
+        aborted => 0b0010_0000;
+        /// bit 6: test is completed without pass/fail indication
+        no_pass_fail => 0b0100_0000;
+        /// bit 7: test is failed, valid only if bit 6 is 0
+        fail_bit => 0b1000_0000;
+    }
+}
+
+flag_type! {
+    /// `PARM_FLG` of PTR and MPR
+    ParmFlags {
+        /// bit 0: scale error
+        scale_error => 0b0000_0001;
+        /// bit 1: drift error (unstable measurement)
+        drift_error => 0b0000_0010;
+        /// bit 2: oscillation detected
+        oscillation => 0b0000_0100;
+        /// bit 3: measured value is higher than high test limit
+        over_hi_limit => 0b0000_1000;
+        /// bit 4: measured value is lower than low test limit
+        under_lo_limit => 0b0001_0000;
+        /// bit 5: test passed alternate limits
+        passed_alternate_limits => 0b0010_0000;
+        /// bit 6: low limit is included in the passing range
+        lo_limit_inclusive => 0b0100_0000;
+        /// bit 7: high limit is included in the passing range
+        hi_limit_inclusive => 0b1000_0000;
+    }
+}
+
+flag_type! {
+    /// `PART_FLG` of PRR
+    PartFlags {
+        /// bit 0: supersedes any previous part with
+        /// the same `PART_ID`
+        supersedes_part_id => 0b0000_0001;
+        /// bit 1: supersedes any previous part with
+        /// the same X/Y coordinates
+        supersedes_xy => 0b0000_0010;
+        /// bit 2: abnormal end of testing
+        abnormal_end => 0b0000_0100;
+        /// bit 3: part is failed, valid only if bit 4 is 0
+        fail_bit => 0b0000_1000;
+        /// bit 4: pass/fail flag (bit 3) is invalid
+        pass_fail_invalid => 0b0001_0000;
+    }
+}
+
+flag_type! {
+    /// `OPT_FLAG` of PTR
+    PtrOptFlags {
+        /// bit 0: `RES_SCAL` is invalid
+        res_scal_invalid => 0b0000_0001;
+        /// bit 2: no low spec limit
+        no_lo_spec => 0b0000_0100;
+        /// bit 3: no high spec limit
+        no_hi_spec => 0b0000_1000;
+        /// bit 4: `LO_LIMIT` and `LLM_SCAL` are invalid,
+        /// the default in the first PTR applies
+        lo_limit_invalid => 0b0001_0000;
+        /// bit 5: `HI_LIMIT` and `HLM_SCAL` are invalid,
+        /// the default in the first PTR applies
+        hi_limit_invalid => 0b0010_0000;
+        /// bit 6: no low test limit
+        no_lo_limit => 0b0100_0000;
+        /// bit 7: no high test limit
+        no_hi_limit => 0b1000_0000;
+    }
+}
+
+flag_type! {
+    /// `OPT_FLAG` of MPR
+    MprOptFlags {
+        /// bit 0: `RES_SCAL` is invalid
+        res_scal_invalid => 0b0000_0001;
+        /// bit 1: `START_IN` and `INCR_IN` are invalid
+        start_incr_invalid => 0b0000_0010;
+        /// bit 2: no low spec limit
+        no_lo_spec => 0b0000_0100;
+        /// bit 3: no high spec limit
+        no_hi_spec => 0b0000_1000;
+        /// bit 4: `LO_LIMIT` and `LLM_SCAL` are invalid,
+        /// the default in the first MPR applies
+        lo_limit_invalid => 0b0001_0000;
+        /// bit 5: `HI_LIMIT` and `HLM_SCAL` are invalid,
+        /// the default in the first MPR applies
+        hi_limit_invalid => 0b0010_0000;
+        /// bit 6: no low test limit
+        no_lo_limit => 0b0100_0000;
+        /// bit 7: no high test limit
+        no_hi_limit => 0b1000_0000;
+    }
+}
+
+flag_type! {
+    /// `OPT_FLAG` of FTR
+    FtrOptFlags {
+        /// bit 0: `CYCL_CNT` is invalid
+        cycl_cnt_invalid => 0b0000_0001;
+        /// bit 1: `REL_VADR` is invalid
+        rel_vadr_invalid => 0b0000_0010;
+        /// bit 2: `REPT_CNT` is invalid
+        rept_cnt_invalid => 0b0000_0100;
+        /// bit 3: `NUM_FAIL` is invalid
+        num_fail_invalid => 0b0000_1000;
+        /// bit 4: `XFAIL_AD` and `YFAIL_AD` are invalid
+        fail_ad_invalid => 0b0001_0000;
+        /// bit 5: `VECT_OFF` is invalid
+        vect_off_invalid => 0b0010_0000;
+    }
+}
+
+flag_type! {
+    /// `FMU_FLG` of STR
+    StrFmuFlags {
+        /// bit 0: `MASK_MAP` is used
+        mask_map_used => 0b0000_0001;
+        /// bit 1: `MASK_MAP` is the same as the previous STR
+        mask_map_unchanged => 0b0000_0010;
+        /// bit 2: `FAL_MAP` is used
+        fal_map_used => 0b0000_0100;
+        /// bit 3: `FAL_MAP` is the same as the previous STR
+        fal_map_unchanged => 0b0000_1000;
+        /// bit 4: pattern is changed since the previous STR
+        pattern_changed => 0b0001_0000;
+    }
+}
+
+impl PtrTestFlags {
+    /// returns true if the test is failed
+    #[inline(always)]
+    pub fn is_fail(&self) -> bool {
+        !self.no_pass_fail() && self.fail_bit()
+    }
+
+    /// returns true if the result is usable, i.e.
+    /// not invalid, timeout, not executed or aborted
+    #[inline(always)]
+    pub fn is_valid(&self) -> bool {
+        self.0 & 0b0011_1010 == 0
+    }
+
+    /// pass/fail of the test, `None` if the test is
+    /// not executed or has no pass/fail indication
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        if self.not_executed() || self.no_pass_fail() {
+            None
+        } else {
+            Some(!self.fail_bit())
+        }
+    }
+}
+
+impl PartFlags {
+    /// returns true if the part is a retest of a previous part,
+    /// identified either by `PART_ID` or X/Y coordinates
+    #[inline(always)]
+    pub fn retested(&self) -> bool {
+        self.0 & 0b0000_0011 != 0
+    }
+
+    /// returns true if the part is failed
+    #[inline(always)]
+    pub fn is_fail(&self) -> bool {
+        self.0 & 0b0001_1000 == 0b0000_1000
+    }
+
+    /// pass/fail of the part, `None` if
+    /// the pass/fail flag is invalid
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        if self.pass_fail_invalid() {
+            None
+        } else {
+            Some(!self.fail_bit())
+        }
+    }
+}
+
+impl PTR {
+    /// pass/fail of the test according to `TEST_FLG`,
+    /// `None` if the test is not executed or
+    /// completed without pass/fail indication
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        PtrTestFlags::from(self.test_flg).passed()
+    }
+}
+
+impl MPR {
+    /// pass/fail of the test according to `TEST_FLG`,
+    /// `None` if the test is not executed or
+    /// completed without pass/fail indication
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        PtrTestFlags::from(self.test_flg).passed()
+    }
+}
+
+impl FTR {
+    /// pass/fail of the test according to `TEST_FLG`,
+    /// `None` if the test is not executed or
+    /// completed without pass/fail indication
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        PtrTestFlags::from(self.test_flg).passed()
+    }
+}
+
+impl PRR {
+    /// pass/fail of the part according to `PART_FLG`,
+    /// `None` if the pass/fail flag is invalid
+    #[inline(always)]
+    pub fn passed(&self) -> Option<bool> {
+        PartFlags::from(self.part_flg).passed()
+    }
+}
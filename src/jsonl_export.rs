@@ -0,0 +1,335 @@
+//
+// jsonl_export.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use serde_json::{json, Map, Value};
+use smart_default::SmartDefault;
+use std::fs;
+use std::io::{BufRead, BufWriter, Seek, Write};
+use std::path::Path;
+
+/// fields of type `B1`
+const B1_FIELDS: [&str; 7] = [
+    "CONT_FLG", "OPT_FLG", "PART_FLG", "OPT_FLAG", "TEST_FLG", "PARM_FLG", "FMU_FLG",
+];
+/// fields of type `Dn`
+const DN_FIELDS: [&str; 4] = ["FAIL_PIN", "SPIN_MAP", "MASK_MAP", "FAL_MAP"];
+/// fields of type `Bn` or unparsed bytes
+const BN_FIELDS: [&str; 2] = ["PART_FIX", "RAW_DATA"];
+
+/// How the `B1` flag fields are written
+#[derive(SmartDefault, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagFormat {
+    /// bit string from bit 7 to bit 0, e.g. `"00010100"`
+    #[default]
+    Bits,
+    /// hex string, e.g. `"0x14"`
+    Hex,
+}
+
+/// Options of `JsonlWriter`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct JsonlOptions {
+    pub flag_format: FlagFormat,
+    /// only the records of `rec_types` are written,
+    /// e.g. `REC_PTR | REC_PRR`, all records by default
+    #[default(u64::MAX)]
+    pub rec_types: u64,
+}
+
+/// Convert the fields of a `StdfRecord` to a JSON object
+/// with the uppercase field names as keys, in the order
+/// of the STDF specification
+///
+/// Unlike the plain `serde` output, the binary fields
+/// are converted to values that are readable in JSON:
+///  - `B1`: bit string or hex string, see `FlagFormat`
+///  - `Dn`: list of the indexes of the set bits
+///  - `Bn`: hex string
+///  - `V1` in `GEN_DATA`: `{"type": "U1", "value": 3}`
+///
+/// The header of `InvalidRec` is written as
+/// `REC_LEN`, `REC_TYP` and `REC_SUB`.
+///
+/// ```
+/// use rust_stdf::{jsonl_export::*, stdf_record_type::REC_PRR, StdfRecord};
+///
+/// let mut rec = StdfRecord::new(REC_PRR);
+/// if let StdfRecord::PRR(ref mut prr_rec) = rec {
+///     prr_rec.part_flg = [0b0000_1000];
+///     prr_rec.part_fix = vec![0xAB, 0x01];
+/// }
+/// let fields = record_to_json(&rec, FlagFormat::Hex);
+/// assert_eq!(fields["PART_FLG"], "0x08");
+/// assert_eq!(fields["PART_FIX"], "AB01");
+/// ```
+pub fn record_to_json(rec: &StdfRecord, flag_format: FlagFormat) -> Value {
+    macro_rules! to_value {
+        ($($variant:ident),+) => {
+            match rec {
+                $(StdfRecord::$variant(r) => serde_json::to_value(r).unwrap_or(Value::Null),)+
+                StdfRecord::InvalidRec(h) => {
+                    json!({"REC_LEN": h.len, "REC_TYP": h.typ, "REC_SUB": h.sub})
+                }
+            }
+        };
+    }
+    let mut value = to_value!(
+        FAR,
+        ATR,
+        VUR,
+        MIR,
+        MRR,
+        PCR,
+        HBR,
+        SBR,
+        PMR,
+        PGR,
+        PLR,
+        RDR,
+        SDR,
+        PSR,
+        NMR,
+        CNR,
+        SSR,
+        CDR,
+        WIR,
+        WRR,
+        WCR,
+        PIR,
+        PRR,
+        TSR,
+        PTR,
+        MPR,
+        FTR,
+        STR,
+        BPS,
+        EPS,
+        GDR,
+        DTR,
+        ReservedRec
+    );
+    if let Value::Object(fields) = &mut value {
+        convert_fields(fields, flag_format);
+    }
+    value
+}
+
+fn convert_fields(fields: &mut Map<String, Value>, flag_format: FlagFormat) {
+    for (name, value) in fields.iter_mut() {
+        let name = name.as_str();
+        if B1_FIELDS.contains(&name) {
+            // optional B1 of PTR and MPR is null if omitted
+            if let Some(b) = first_byte(value) {
+                *value = Value::String(format_flag(b, flag_format));
+            }
+        } else if DN_FIELDS.contains(&name) {
+            *value = bit_indexes(&to_bytes(value));
+        } else if BN_FIELDS.contains(&name) {
+            *value = Value::String(to_hex(&to_bytes(value)));
+        } else if name == "GEN_DATA" {
+            if let Value::Array(data) = value {
+                data.iter_mut().for_each(|v| *v = typed_v1(v));
+            }
+        }
+    }
+}
+
+/// `V1` is serialized as `{"U1": 3}` or `"B0"`,
+/// convert it to `{"type": "U1", "value": 3}`
+fn typed_v1(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => match map.iter().next() {
+            Some((typ, v)) => {
+                let v = match typ.as_str() {
+                    "Bn" => Value::String(to_hex(&to_bytes(v))),
+                    "Dn" => bit_indexes(&to_bytes(v)),
+                    _ => v.clone(),
+                };
+                json!({"type": typ, "value": v})
+            }
+            None => Value::Null,
+        },
+        Value::String(typ) => json!({"type": typ, "value": Value::Null}),
+        _ => value.clone(),
+    }
+}
+
+#[inline(always)]
+fn first_byte(value: &Value) -> Option<u8> {
+    value.as_array()?.first()?.as_u64().map(|b| b as u8)
+}
+
+#[inline(always)]
+fn to_bytes(value: &Value) -> Vec<u8> {
+    match value.as_array() {
+        Some(arr) => arr
+            .iter()
+            .filter_map(|b| b.as_u64().map(|b| b as u8))
+            .collect(),
+        None => vec![],
+    }
+}
+
+#[inline(always)]
+fn format_flag(b: u8, flag_format: FlagFormat) -> String {
+    match flag_format {
+        FlagFormat::Bits => format!("{:08b}", b),
+        FlagFormat::Hex => format!("0x{:02X}", b),
+    }
+}
+
+#[inline(always)]
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// bit 0 is the LSB of the first byte
+#[inline(always)]
+fn bit_indexes(bytes: &[u8]) -> Value {
+    let bits = bytes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &b)| {
+            (0..8)
+                .filter(move |k| b & (1 << k) != 0)
+                .map(move |k| i * 8 + k)
+        })
+        .collect::<Vec<usize>>();
+    json!(bits)
+}
+
+/// Write STDF records as JSON Lines
+///
+/// Each record is written in one line as a JSON object of
+/// the record name, the byte offset of the record header
+/// in the file and its fields, e.g.
+///
+/// `{"rec":"PRR","offset":1024,"fields":{"HEAD_NUM":1,...}}`
+///
+/// see `record_to_json` for the format of the fields.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{jsonl_export::*, stdf_record_type::REC_PIR, StdfRecord};
+///
+/// let mut writer = JsonlWriter::new(Vec::new(), JsonlOptions::default());
+/// writer.write_record(&StdfRecord::new(REC_PIR), 128).unwrap();
+/// let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert_eq!(
+///     output,
+///     "{\"rec\":\"PIR\",\"offset\":128,\"fields\":{\"HEAD_NUM\":0,\"SITE_NUM\":0}}\n"
+/// );
+/// ```
+pub struct JsonlWriter<W: Write> {
+    writer: W,
+    options: JsonlOptions,
+    count: u64,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    pub fn new(writer: W, options: JsonlOptions) -> Self {
+        JsonlWriter {
+            writer,
+            options,
+            count: 0,
+        }
+    }
+
+    /// write a record whose header is located at `offset`,
+    /// records not in `rec_types` of the options are ignored
+    pub fn write_record(&mut self, rec: &StdfRecord, offset: u64) -> Result<(), StdfError> {
+        if !rec.is_type(self.options.rec_types) {
+            return Ok(());
+        }
+        let line = json!({
+            "rec": get_rec_name_from_code(rec.get_type()),
+            "offset": offset,
+            "fields": record_to_json(rec, self.options.flag_format),
+        });
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// parse and write a `RawDataElement`, the record
+    /// is parsed only if it is in `rec_types` of the options
+    pub fn write_raw(&mut self, raw_element: &RawDataElement) -> Result<(), StdfError> {
+        if !raw_element.is_type(self.options.rec_types) {
+            return Ok(());
+        }
+        // offset of `RawDataElement` is after the 4-byte header
+        self.write_record(&StdfRecord::from(raw_element), raw_element.offset - 4)
+    }
+
+    /// get the number of records written
+    pub fn get_record_count(&self) -> u64 {
+        self.count
+    }
+
+    /// flush and return the inner writer
+    pub fn finish(mut self) -> Result<W, StdfError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Write the records of `reader` to `writer` as JSON Lines,
+/// return the number of records written
+pub fn to_jsonl_stream<R, W>(
+    reader: &mut StdfReader<R>,
+    writer: W,
+    options: JsonlOptions,
+) -> Result<u64, StdfError>
+where
+    R: BufRead + Seek,
+    W: Write,
+{
+    let mut jsonl_writer = JsonlWriter::new(writer, options);
+    for raw_element in reader.get_rawdata_iter() {
+        jsonl_writer.write_raw(&raw_element?)?;
+    }
+    let count = jsonl_writer.get_record_count();
+    jsonl_writer.finish()?;
+    Ok(count)
+}
+
+/// Convert the STDF file at `src` to a JSON Lines
+/// file at `dst`, return the number of records written
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::jsonl_export::{to_jsonl, JsonlOptions};
+/// use rust_stdf::stdf_record_type::*;
+///
+/// let options = JsonlOptions {
+///     rec_types: REC_PTR | REC_PRR,
+///     ..Default::default()
+/// };
+/// if let Ok(count) = to_jsonl("input.stdf", "output.jsonl", options) {
+///     println!("{} records written", count);
+/// }
+/// ```
+pub fn to_jsonl<P, Q>(src: P, dst: Q, options: JsonlOptions) -> Result<u64, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(src)?;
+    let writer = BufWriter::new(fs::File::create(dst)?);
+    to_jsonl_stream(&mut reader, writer, options)
+}
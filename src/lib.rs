@@ -5,6 +5,25 @@
 //! Current capability:
 //!  - Reading & parsing STDF files.
 //!  - Reading & parsing ATDF files. (feature: `atdf`)
+//!  - Writing `StdfRecord` to STDF files.
+//!  - Salvaging truncated STDF files.
+//!  - Validating STDF files against the specification.
+//!  - Per-test statistics of parametric results.
+//!  - Wafer maps from PRR coordinates, export to SEMI E142 and SINF,
+//!    rendering to SVG and PNG.
+//!  - Typed views of bit flags and pass/fail determination.
+//!  - Merging STDF files of a lot into one file, and splitting
+//!    a file by wafer, site or part count.
+//!  - Filtering and rewriting records of STDF files,
+//!    converting between little and big endian.
+//!  - Redacting sensitive fields for sharing datalogs.
+//!  - Semantic diff of two STDF files.
+//!  - Exporting parts to CSV/TSV, one row per part and one column per test.
+//!  - Normalizing records into rows of results, parts, tests, bins and wafers.
+//!  - Converting to Arrow record batches and Parquet files. (feature: `arrow`)
+//!  - Loading into a normalized SQLite database. (feature: `sqlite`)
+//!  - Exporting records to JSON Lines. (feature: `jsonl`)
+//!  - Reading STDF V3 files and upconverting to V4. (feature: `v3`)
 //!  - Support several compressed formats.
 //!
 //! Available features:
@@ -13,9 +32,12 @@
 //!  - `zipfile`: zip compression (.zip) support powered by `zip`
 //!  - `atdf`: ATDF reader + STDF -> ATDF convertor (in dev)
 //!  - `serialize`: serialize STDF records by `serde`
+//!  - `jsonl`: JSON Lines export powered by `serde_json`, enables `serialize`
+//!  - `v3`: STDF V3 records and V3 -> V4 convertor
+//!  - `arrow`: Arrow record batches and Parquet export powered by `arrow` and `parquet`
+//!  - `sqlite`: SQLite database export powered by `rusqlite`
 //!
 //! In development:
-//!  - (dev) Dump `StdfRecord` to a new stdf file.
 //!  - (dev) Functions for ATDF <-> STDF format.
 
 // lib.rs
@@ -36,8 +58,8 @@ mod stdf_error;
 mod stdf_types;
 pub use stdf_types::*;
 
-/// This module contains STDF Reader
-/// and record iterator
+/// This module contains STDF Reader,
+/// record iterator and STDF Writer
 ///
 /// For more detailed example, see [`StdfReader`].
 pub mod stdf_file;
@@ -49,6 +71,163 @@ pub mod stdf_file;
 #[cfg(feature = "atdf")]
 pub mod atdf_file;
 
+/// This module accumulates part, bin and test
+/// counts, generates summary records
+/// (PCR, HBR, SBR and TSR) from them,
+/// cross-checks the summary records in the file
+/// and merges the bins in HBR/SBR into a `BinSummary`
+///
+/// For more detailed example, see [`summary::SummaryBuilder`].
+pub mod summary;
+
+/// This module contains the salvager of truncated
+/// STDF files, missing PRR, WRR, summary records
+/// and MRR are synthesized
+///
+/// For more detailed example, see [`salvage::Salvage`].
+pub mod salvage;
+
+/// This module contains a validator that checks
+/// the conformance of STDF files to the specification
+///
+/// For more detailed example, see [`validate::Validator`].
+pub mod validate;
+
+/// This module contains the per-test statistics
+/// of PTR and MPR results, e.g. mean, sigma and Cpk
+///
+/// For more detailed example, see [`stats::StatsBuilder`].
+pub mod stats;
+
+/// This module contains the wafer map model
+/// built from WCR, WIR, WRR and PRR
+///
+/// For more detailed example, see [`wafermap::WaferMapBuilder`].
+pub mod wafermap;
+
+/// This module exports wafer maps to
+/// SEMI E142 XML and SINF text
+pub mod map_export;
+
+/// This module renders wafer maps to SVG and PNG,
+/// colored by bins or by parametric results
+///
+/// For more detailed example, see [`map_render::HeatmapBuilder`].
+pub mod map_render;
+
+/// This module contains typed views of the
+/// bit flags, e.g. `TEST_FLG` and `PART_FLG`,
+/// and the pass/fail determination of PTR, MPR,
+/// FTR and PRR
+///
+/// For more detailed example, see [`flags::PtrTestFlags`].
+pub mod flags;
+
+/// This module resolves the PMR indexes in
+/// MPR, FTR and STR to pins and pin groups
+///
+/// For more detailed example, see [`pinmap::PinMap`].
+pub mod pinmap;
+
+/// This module decodes the fail bitmaps of
+/// FTR and STR, the logged fails of STR and
+/// merges STR continuation records
+///
+/// For more detailed example, see [`faildata::StrFailDecoder`].
+pub mod faildata;
+
+/// This module resolves the scan fails in STR with
+/// PSR, NMR, CNR, SSR and CDR, and exports them
+/// for scan diagnosis
+///
+/// For more detailed example, see [`scandiag::ScanDiagnosisBuilder`].
+pub mod scandiag;
+
+/// This module links the retests of parts within
+/// a file and across files, for yield calculation
+///
+/// For more detailed example, see [`parthistory::PartHistoryBuilder`].
+pub mod parthistory;
+
+/// This module merges several STDF files of a lot
+/// into one file, with the header records merged and
+/// the summary records recomputed
+///
+/// For more detailed example, see [`merge::HeaderMerger`].
+pub mod merge;
+
+/// This module splits an STDF file into several files
+/// by wafer, by site or by part count, each one
+/// with the header records and its own summary records
+///
+/// For more detailed example, see [`split::split_stream`].
+pub mod split;
+
+/// This module contains a STDF -> STDF pipeline
+/// that drops, modifies or inserts records by closures
+///
+/// For more detailed example, see [`transform::Transform`].
+pub mod transform;
+
+/// This module scrubs or hashes the sensitive
+/// fields of STDF files, e.g. lot ID and part ID,
+/// while the test results are preserved
+///
+/// For more detailed example, see [`redact::Redactor`].
+pub mod redact;
+
+/// This module compares two STDF files by part and
+/// by test number, for tester-to-tester correlation
+/// and test program release validation
+///
+/// For more detailed example, see [`diff::DiffBuilder`].
+pub mod diff;
+
+/// This module exports the parts of STDF files to
+/// a CSV or TSV table, one row per part and
+/// one column per PTR test or MPR pin
+///
+/// For more detailed example, see [`csv_export::CsvExporter`].
+pub mod csv_export;
+
+/// This module normalizes STDF records into rows of
+/// results, parts, tests, bins and wafers, shared by
+/// the Arrow and SQLite exporters
+///
+/// For more detailed example, see [`table_rows::RowNormalizer`].
+pub mod table_rows;
+
+/// This module converts STDF files to Arrow record
+/// batches of results, parts, tests, bins and wafers,
+/// and writes them to Parquet files
+///
+/// For more detailed example, see [`arrow_export::ArrowBatchBuilder`].
+#[cfg(feature = "arrow")]
+pub mod arrow_export;
+
+/// This module loads STDF files into a normalized
+/// SQLite database of file info, parts, tests,
+/// results, bins, wafers and pins
+///
+/// For more detailed example, see [`sqlite_export::SqliteLoader`].
+#[cfg(feature = "sqlite")]
+pub mod sqlite_export;
+
+/// This module writes STDF records as JSON Lines,
+/// one object per record with readable flag,
+/// bit map and generic data fields
+///
+/// For more detailed example, see [`jsonl_export::JsonlWriter`].
+#[cfg(feature = "jsonl")]
+pub mod jsonl_export;
+
+/// This module contains STDF V3 records and
+/// a converter that upconverts them to V4 records
+///
+/// For more detailed example, see [`stdf_v3::V3Converter`].
+#[cfg(feature = "v3")]
+pub mod stdf_v3;
+
 #[cfg(test)]
 mod tests {
     use crate::*;
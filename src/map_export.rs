@@ -0,0 +1,285 @@
+//
+// map_export.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_types::*;
+use crate::wafermap::{BinDef, Die, WaferMap};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Which bin is exported to the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapBinType {
+    #[default]
+    HardBin,
+    SoftBin,
+}
+
+/// bin code, count, pass/fail and name of the bins in the map
+pub(crate) struct BinTable {
+    pub(crate) width: usize,
+    pub(crate) bins: BTreeMap<U2, (usize, BinDef)>,
+}
+
+impl WaferMap {
+    /// Export the wafer map to SINF text.
+    ///
+    /// Bins are written as hexadecimal codes, 2 digits
+    /// unless a bin number requires more, positions without
+    /// die are filled with `_`. Bins marked 'P' in HBR/SBR,
+    /// or bins that contain only passed dies if pass/fail
+    /// is not specified in HBR/SBR, are listed in `BCEQU`.
+    ///
+    /// `FNLOC` is 0, 90, 180 or 270 degrees if the flat
+    /// is at the top, right, bottom or left respectively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_stdf::{map_export::MapBinType, stdf_file::*, wafermap::*};
+    ///
+    /// let mut builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    /// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+    ///     for rec in reader.get_record_iter().flatten() {
+    ///         builder.feed(&rec);
+    ///     }
+    /// }
+    /// for wafer in builder.finish() {
+    ///     println!("{}", wafer.to_sinf(MapBinType::HardBin));
+    /// }
+    /// ```
+    pub fn to_sinf(&self, bin_type: MapBinType) -> String {
+        let table = self.bin_table(bin_type);
+        let grid = self.die_grid();
+        let cols = grid.first().map_or(0, |r| r.len());
+        let good_bins: Vec<String> = table
+            .bins
+            .iter()
+            .filter(|(_, (_, def))| def.bin_pf == 'P')
+            .map(|(&bin, _)| table.code(bin))
+            .collect();
+        let (ref_x, ref_y) = self.bounds().map_or((0, 0), |b| {
+            let x = if self.wcr.pos_x == 'L' { b.2 } else { b.0 };
+            let y = if self.wcr.pos_y == 'U' { b.3 } else { b.1 };
+            (x, y)
+        });
+
+        let mut sinf = String::new();
+        let _ = writeln!(sinf, "DEVICE:{}", self.part_typ);
+        let _ = writeln!(sinf, "LOT:{}", self.lot_id);
+        let _ = writeln!(sinf, "WAFER:{}", self.wafer_id);
+        let _ = writeln!(sinf, "FNLOC:{}", flat_degree(self.wcr.wf_flat));
+        let _ = writeln!(sinf, "ROWCT:{}", grid.len());
+        let _ = writeln!(sinf, "COLCT:{}", cols);
+        let _ = writeln!(sinf, "BCEQU:{}", good_bins.join(" "));
+        let _ = writeln!(sinf, "REFPX:{}", ref_x);
+        let _ = writeln!(sinf, "REFPY:{}", ref_y);
+        let _ = writeln!(sinf, "DUTMS:{}", units_name(self.wcr.wf_units));
+        let _ = writeln!(sinf, "XDIES:{}", self.wcr.die_wid);
+        let _ = writeln!(sinf, "YDIES:{}", self.wcr.die_ht);
+        for row in grid.iter() {
+            let codes: Vec<String> = row
+                .iter()
+                .map(|die| match die {
+                    Some(d) => table.code(die_bin(d, bin_type)),
+                    None => "_".repeat(table.width),
+                })
+                .collect();
+            let _ = writeln!(sinf, "RowData:{}", codes.join(" "));
+        }
+        sinf
+    }
+
+    /// Export the wafer map to SEMI E142 substrate map XML.
+    ///
+    /// The map contains a `BinCodeMap` overlay in
+    /// hexadecimal with the bin definitions from HBR/SBR,
+    /// rows start from the upper left corner as they are viewed,
+    /// the null bin is `F` of the code width.
+    pub fn to_e142_xml(&self, bin_type: MapBinType) -> String {
+        let table = self.bin_table(bin_type);
+        let grid = self.die_grid();
+        let cols = grid.first().map_or(0, |r| r.len());
+        let null_bin = "F".repeat(table.width);
+        let units = units_name(self.wcr.wf_units);
+
+        let mut xml = String::new();
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<MapData xmlns="urn:semi-org:xsd.E142-1.V1005.SubstrateMap" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">"#
+        );
+        let _ = writeln!(xml, "  <Layouts>");
+        let _ = writeln!(
+            xml,
+            r#"    <Layout LayoutId="WaferLayout" DefaultUnits="{}" TopLevel="true">"#,
+            units
+        );
+        let _ = writeln!(xml, r#"      <Dimension X="1" Y="1"/>"#);
+        let _ = writeln!(
+            xml,
+            r#"      <ChildLayouts><ChildLayout LayoutId="Devices"/></ChildLayouts>"#
+        );
+        let _ = writeln!(xml, "    </Layout>");
+        let _ = writeln!(
+            xml,
+            r#"    <Layout LayoutId="Devices" DefaultUnits="{}">"#,
+            units
+        );
+        let _ = writeln!(xml, r#"      <Dimension X="{}" Y="{}"/>"#, cols, grid.len());
+        if let Some((min_x, min_y, _, _)) = self.bounds() {
+            let _ = writeln!(xml, r#"      <LowerLeft X="{}" Y="{}"/>"#, min_x, min_y);
+        }
+        let _ = writeln!(
+            xml,
+            r#"      <DeviceSize X="{}" Y="{}"/>"#,
+            self.wcr.die_wid, self.wcr.die_ht
+        );
+        let _ = writeln!(xml, "    </Layout>");
+        let _ = writeln!(xml, "  </Layouts>");
+        let _ = writeln!(xml, "  <Substrates>");
+        let _ = writeln!(
+            xml,
+            r#"    <Substrate SubstrateType="Wafer" SubstrateId="{}">"#,
+            escape_xml(&self.wafer_id)
+        );
+        let _ = writeln!(xml, "      <LotId>{}</LotId>", escape_xml(&self.lot_id));
+        let _ = writeln!(xml, "    </Substrate>");
+        let _ = writeln!(xml, "  </Substrates>");
+        let _ = writeln!(xml, "  <SubstrateMaps>");
+        let _ = writeln!(
+            xml,
+            r#"    <SubstrateMap SubstrateType="Wafer" SubstrateId="{}" LayoutSpecifier="WaferLayout/Devices" SubstrateSide="TopSide" OriginLocation="UpperLeft" Orientation="{}">"#,
+            escape_xml(&self.wafer_id),
+            flat_degree(self.wcr.wf_flat)
+        );
+        let _ = writeln!(xml, r#"      <Overlay MapName="BinMap" MapVersion="1">"#);
+        let _ = writeln!(
+            xml,
+            r#"        <BinCodeMap BinType="HexaDecimal" NullBin="{}">"#,
+            null_bin
+        );
+        let _ = writeln!(xml, "          <BinDefinitions>");
+        for (&bin, (count, def)) in table.bins.iter() {
+            let quality = match def.bin_pf {
+                'P' => "Pass",
+                'F' => "Fail",
+                _ => "Unknown",
+            };
+            let _ = writeln!(
+                xml,
+                r#"            <BinDefinition BinCode="{}" BinCount="{}" BinQuality="{}" BinDescription="{}"/>"#,
+                table.code(bin),
+                count,
+                quality,
+                escape_xml(&def.bin_nam)
+            );
+        }
+        let _ = writeln!(xml, "          </BinDefinitions>");
+        for row in grid.iter() {
+            let codes: String = row
+                .iter()
+                .map(|die| match die {
+                    Some(d) => table.code(die_bin(d, bin_type)),
+                    None => null_bin.clone(),
+                })
+                .collect();
+            let _ = writeln!(xml, "          <BinCode>{}</BinCode>", codes);
+        }
+        let _ = writeln!(xml, "        </BinCodeMap>");
+        let _ = writeln!(xml, "      </Overlay>");
+        let _ = writeln!(xml, "    </SubstrateMap>");
+        let _ = writeln!(xml, "  </SubstrateMaps>");
+        let _ = writeln!(xml, "</MapData>");
+        xml
+    }
+
+    pub(crate) fn bin_table(&self, bin_type: MapBinType) -> BinTable {
+        let (counts, defs) = match bin_type {
+            MapBinType::HardBin => (self.hbin_counts(), &self.hbin_defs),
+            MapBinType::SoftBin => (self.sbin_counts(), &self.sbin_defs),
+        };
+        let mut bins = BTreeMap::new();
+        for (&bin, &count) in counts.iter() {
+            let mut def = defs.get(&bin).cloned().unwrap_or(BinDef {
+                bin_pf: ' ',
+                bin_nam: String::new(),
+            });
+            if def.bin_pf != 'P' && def.bin_pf != 'F' {
+                // guess pass/fail from the dies
+                let all_passed = self
+                    .dies()
+                    .filter(|d| die_bin(d, bin_type) == bin)
+                    .all(|d| d.passed);
+                def.bin_pf = if all_passed { 'P' } else { 'F' };
+            }
+            bins.insert(bin, (count, def));
+        }
+        // reserve the all-F code for null bin
+        let max_bin = bins.keys().last().copied().unwrap_or(0) as usize;
+        let mut width = 2;
+        while max_bin >= 16usize.pow(width as u32) - 1 {
+            width += 1;
+        }
+        BinTable { width, bins }
+    }
+}
+
+impl BinTable {
+    #[inline(always)]
+    fn code(&self, bin: U2) -> String {
+        format!("{:0width$X}", bin, width = self.width)
+    }
+}
+
+#[inline(always)]
+pub(crate) fn die_bin(die: &Die, bin_type: MapBinType) -> U2 {
+    match bin_type {
+        MapBinType::HardBin => die.hard_bin,
+        MapBinType::SoftBin => die.soft_bin,
+    }
+}
+
+/// orientation of the flat in degrees,
+/// clockwise from the top
+#[inline(always)]
+fn flat_degree(wf_flat: C1) -> u16 {
+    match wf_flat {
+        'R' => 90,
+        'D' => 180,
+        'L' => 270,
+        _ => 0,
+    }
+}
+
+#[inline(always)]
+fn units_name(wf_units: U1) -> &'static str {
+    match wf_units {
+        1 => "in",
+        2 => "cm",
+        4 => "mil",
+        _ => "mm",
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,729 @@
+//
+// map_render.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::map_export::{die_bin, MapBinType};
+use crate::stdf_types::*;
+use crate::wafermap::{WaferKeys, WaferMap};
+#[cfg(feature = "gzip")]
+use flate2::{write::ZlibEncoder, Compression};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as FmtWrite;
+#[cfg(feature = "gzip")]
+use std::io::Write;
+
+type Rgb = (u8, u8, u8);
+
+const BACKGROUND: Rgb = (255, 255, 255);
+const OUTLINE: Rgb = (96, 96, 96);
+const TEXT: Rgb = (32, 32, 32);
+const NO_VALUE: Rgb = (211, 211, 211);
+const PASS_COLORS: [Rgb; 4] = [
+    (44, 160, 44),
+    (152, 223, 138),
+    (0, 168, 107),
+    (102, 194, 165),
+];
+const FAIL_COLORS: [Rgb; 12] = [
+    (214, 39, 40),
+    (255, 127, 14),
+    (148, 103, 189),
+    (140, 86, 75),
+    (227, 119, 194),
+    (188, 189, 34),
+    (23, 190, 207),
+    (31, 119, 180),
+    (255, 152, 150),
+    (197, 176, 213),
+    (196, 156, 148),
+    (247, 182, 210),
+];
+const MARGIN: f64 = 10.0;
+const LEGEND_WIDTH: f64 = 240.0;
+const LEGEND_ROW: f64 = 18.0;
+
+/// Options of wafer map rendering
+#[derive(Debug, Clone)]
+pub struct RenderOptions<'a> {
+    /// width of a die in pixels, height is
+    /// scaled by the die size in WCR
+    pub die_size: u32,
+    /// bin used for coloring the dies
+    pub bin_type: MapBinType,
+    /// color the dies by values (e.g. from `HeatmapBuilder`)
+    /// instead of bins, dies without value are gray
+    pub heatmap: Option<&'a BTreeMap<(I2, I2), R8>>,
+    /// draw the legend on the right side
+    pub legend: bool,
+}
+
+/// Collect the result of a PTR test for every die,
+/// for rendering a parametric heatmap.
+///
+/// Results that are not valid are ignored, the last
+/// result is kept if a die is tested more than once.
+///
+/// Wafers are separated in the same way as `WaferMapBuilder`,
+/// the heatmaps are in the order of its wafer maps if
+/// both are fed with the same records.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{map_render::*, stdf_file::*, wafermap::*};
+///
+/// let mut map_builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+/// let mut heatmap_builder = HeatmapBuilder::new(1000);
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         map_builder.feed(&rec);
+///         heatmap_builder.feed(&rec);
+///     }
+/// }
+/// let heatmaps = heatmap_builder.finish();
+/// for (wafer, heatmap) in map_builder.finish().iter().zip(heatmaps.iter()) {
+///     let options = RenderOptions {
+///         heatmap: Some(heatmap),
+///         ..Default::default()
+///     };
+///     let svg = wafer.to_svg(&options);
+///     let png = wafer.to_png(&options);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct HeatmapBuilder {
+    test_num: U4,
+    keys: WaferKeys,
+    open_parts: HashMap<(U1, U1), R8>,
+    maps: Vec<BTreeMap<(I2, I2), R8>>,
+}
+
+/// geometry of the rendered map, shared by SVG and PNG
+struct Scene {
+    width: f64,
+    height: f64,
+    title: String,
+    // x, y, width, height, color
+    dies: Vec<(f64, f64, f64, f64, Rgb)>,
+    // center x, center y, radius
+    circle: (f64, f64, f64),
+    notch: Option<[(f64, f64); 3]>,
+    legend_x: f64,
+    legend: Legend,
+}
+
+enum Legend {
+    None,
+    // color, bin number, label
+    Bins(Vec<(Rgb, U2, String)>),
+    // min, max
+    Gradient(R8, R8),
+}
+
+impl Default for RenderOptions<'_> {
+    fn default() -> Self {
+        RenderOptions {
+            die_size: 12,
+            bin_type: MapBinType::HardBin,
+            heatmap: None,
+            legend: true,
+        }
+    }
+}
+
+impl HeatmapBuilder {
+    pub fn new(test_num: U4) -> Self {
+        HeatmapBuilder {
+            test_num,
+            ..Default::default()
+        }
+    }
+
+    /// feed WIR, WRR, PIR, PTR and PRR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::WIR(wir_rec) => {
+                let (_, new) = self.keys.open(wir_rec);
+                if new {
+                    self.maps.push(BTreeMap::new());
+                }
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                self.keys.close(wrr_rec.head_num);
+            }
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts
+                    .remove(&(pir_rec.head_num, pir_rec.site_num));
+            }
+            // bit 1, 3, 4, 5: result is not valid, timeout,
+            // not executed or aborted
+            StdfRecord::PTR(ptr_rec)
+                if ptr_rec.test_num == self.test_num && ptr_rec.test_flg[0] & 0b0011_1010 == 0 =>
+            {
+                self.open_parts
+                    .insert((ptr_rec.head_num, ptr_rec.site_num), ptr_rec.result as R8);
+            }
+            StdfRecord::PRR(prr_rec) => {
+                let value = self
+                    .open_parts
+                    .remove(&(prr_rec.head_num, prr_rec.site_num));
+                // -32768 indicates the coordinate is invalid
+                if prr_rec.x_coord == -32768 || prr_rec.y_coord == -32768 {
+                    return;
+                }
+                let (index, new) = self.keys.locate(prr_rec.head_num);
+                if new {
+                    self.maps.push(BTreeMap::new());
+                }
+                if let Some(value) = value.filter(|v| v.is_finite()) {
+                    self.maps[index].insert((prr_rec.x_coord, prr_rec.y_coord), value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// return the values of every die of every wafer,
+    /// in the order of `WaferMapBuilder::finish`
+    pub fn finish(self) -> Vec<BTreeMap<(I2, I2), R8>> {
+        self.maps
+    }
+}
+
+impl WaferMap {
+    /// Render the wafer map to SVG.
+    ///
+    /// The wafer outline is drawn as a circle with a notch
+    /// on the side of `WCR.wf_flat`, its size is from WCR if
+    /// available, otherwise it encloses all the dies.
+    /// The legend lists the bin names from HBR/SBR and the
+    /// die counts, or the value range of the heatmap.
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let scene = self.build_scene(options);
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = scene.width,
+            h = scene.height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(BACKGROUND)
+        );
+        let (cx, cy, r) = scene.circle;
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            cx,
+            cy,
+            r,
+            hex(OUTLINE)
+        );
+        if let Some(notch) = scene.notch {
+            let _ = writeln!(
+                svg,
+                r#"<polygon points="{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}" fill="{}"/>"#,
+                notch[0].0,
+                notch[0].1,
+                notch[1].0,
+                notch[1].1,
+                notch[2].0,
+                notch[2].1,
+                hex(OUTLINE)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<g stroke="{}" stroke-width="0.5">"#,
+            hex(BACKGROUND)
+        );
+        for &(x, y, w, h, color) in scene.dies.iter() {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"/>"#,
+                x,
+                y,
+                w,
+                h,
+                hex(color)
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="14" fill="{}">{}</text>"#,
+            scene.legend_x,
+            MARGIN + 14.0,
+            hex(TEXT),
+            escape_svg(&scene.title)
+        );
+        match &scene.legend {
+            Legend::None => {}
+            Legend::Bins(items) => {
+                for (i, (color, _, label)) in items.iter().enumerate() {
+                    let y = MARGIN + 30.0 + i as f64 * LEGEND_ROW;
+                    let _ = writeln!(
+                        svg,
+                        r#"<rect x="{}" y="{}" width="12" height="12" fill="{}"/>"#,
+                        scene.legend_x,
+                        y,
+                        hex(*color)
+                    );
+                    let _ = writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="12" fill="{}">{}</text>"#,
+                        scene.legend_x + 18.0,
+                        y + 11.0,
+                        hex(TEXT),
+                        escape_svg(label)
+                    );
+                }
+            }
+            Legend::Gradient(min, max) => {
+                let _ = writeln!(
+                    svg,
+                    r#"<defs><linearGradient id="heat" x1="0" y1="1" x2="0" y2="0"><stop offset="0" stop-color="{}"/><stop offset="0.5" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient></defs>"#,
+                    hex(heat_color(0.0)),
+                    hex(heat_color(0.5)),
+                    hex(heat_color(1.0))
+                );
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="16" height="200" fill="url(#heat)"/>"#,
+                    scene.legend_x,
+                    MARGIN + 30.0
+                );
+                for (value, y) in [(max, MARGIN + 40.0), (min, MARGIN + 230.0)] {
+                    let _ = writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="12" fill="{}">{}</text>"#,
+                        scene.legend_x + 22.0,
+                        y,
+                        hex(TEXT),
+                        value
+                    );
+                }
+            }
+        }
+        let _ = writeln!(svg, "</svg>");
+        svg
+    }
+
+    /// Render the wafer map to PNG.
+    ///
+    /// The image is the same as `to_svg`, except that
+    /// only digits are drawn in the legend, since no
+    /// font is bundled: bins are labeled by their numbers
+    /// and names are omitted.
+    pub fn to_png(&self, options: &RenderOptions) -> Vec<u8> {
+        let scene = self.build_scene(options);
+        let mut canvas = Canvas::new(scene.width as usize, scene.height as usize);
+        let (cx, cy, r) = scene.circle;
+        canvas.draw_ring(cx, cy, r, 1.5, OUTLINE);
+        if let Some(notch) = scene.notch {
+            canvas.fill_triangle(notch, OUTLINE);
+        }
+        for &(x, y, w, h, color) in scene.dies.iter() {
+            // keep a gap between dies if they are large enough
+            let gap = if w >= 4.0 && h >= 4.0 { 1.0 } else { 0.0 };
+            canvas.fill_rect(x, y, x + w - gap, y + h - gap, color);
+        }
+        match &scene.legend {
+            Legend::None => {}
+            Legend::Bins(items) => {
+                for (i, (color, bin, _)) in items.iter().enumerate() {
+                    let y = MARGIN + 30.0 + i as f64 * LEGEND_ROW;
+                    canvas.fill_rect(scene.legend_x, y, scene.legend_x + 12.0, y + 12.0, *color);
+                    canvas.draw_text(scene.legend_x + 18.0, y + 1.0, &bin.to_string(), TEXT);
+                }
+            }
+            Legend::Gradient(min, max) => {
+                for i in 0..200 {
+                    let y = MARGIN + 30.0 + i as f64;
+                    let color = heat_color(1.0 - i as f64 / 199.0);
+                    canvas.fill_rect(scene.legend_x, y, scene.legend_x + 16.0, y + 1.0, color);
+                }
+                let x = scene.legend_x + 22.0;
+                canvas.draw_text(x, MARGIN + 30.0, &format!("{:.3}", max), TEXT);
+                canvas.draw_text(x, MARGIN + 220.0, &format!("{:.3}", min), TEXT);
+            }
+        }
+        canvas.encode_png()
+    }
+
+    fn build_scene(&self, options: &RenderOptions) -> Scene {
+        let die_w = options.die_size.max(1) as f64;
+        let die_h = if self.wcr.die_wid > 0.0 && self.wcr.die_ht > 0.0 {
+            die_w * (self.wcr.die_ht / self.wcr.die_wid) as f64
+        } else {
+            die_w
+        };
+        let grid = self.die_grid();
+        let rows = grid.len() as f64;
+        let cols = grid.first().map_or(0, |r| r.len()) as f64;
+        let (grid_w, grid_h) = (cols * die_w, rows * die_h);
+
+        // the center of the wafer in grid pixels
+        let (gcx, gcy) = match self.grid_position(self.wcr.center_x, self.wcr.center_y) {
+            Some((col, row))
+                if self.wcr.center_x != -32768
+                    && self.wcr.center_y != -32768
+                    && (0..cols as i32).contains(&col)
+                    && (0..rows as i32).contains(&row) =>
+            {
+                ((col as f64 + 0.5) * die_w, (row as f64 + 0.5) * die_h)
+            }
+            _ => (grid_w / 2.0, grid_h / 2.0),
+        };
+        // enclose all the dies
+        let far_x = gcx.max(grid_w - gcx);
+        let far_y = gcy.max(grid_h - gcy);
+        let mut radius = (far_x * far_x + far_y * far_y).sqrt().max(die_w);
+        if self.wcr.wafr_siz > 0.0 && self.wcr.die_wid > 0.0 {
+            let wcr_radius = (self.wcr.wafr_siz / self.wcr.die_wid) as f64 * die_w / 2.0;
+            // ignore the wafer size if it cannot contain the dies
+            if wcr_radius >= far_x.max(far_y) {
+                radius = wcr_radius;
+            }
+        }
+        let (cx, cy) = (MARGIN + radius, MARGIN + radius);
+        let (offset_x, offset_y) = (cx - gcx, cy - gcy);
+
+        // dies and legend
+        let mut dies = Vec::with_capacity(self.die_count());
+        let legend = match options.heatmap {
+            Some(values) => {
+                let (min, max) = self
+                    .dies()
+                    .filter_map(|d| values.get(&(d.x_coord, d.y_coord)))
+                    .fold((R8::INFINITY, R8::NEG_INFINITY), |(lo, hi), &v| {
+                        (lo.min(v), hi.max(v))
+                    });
+                for die in self.dies() {
+                    let color = match values.get(&(die.x_coord, die.y_coord)) {
+                        Some(&v) if max > min => heat_color((v - min) / (max - min)),
+                        Some(_) => heat_color(0.5),
+                        None => NO_VALUE,
+                    };
+                    let (col, row) = self.grid_position(die.x_coord, die.y_coord).unwrap();
+                    dies.push((
+                        offset_x + col as f64 * die_w,
+                        offset_y + row as f64 * die_h,
+                        die_w,
+                        die_h,
+                        color,
+                    ));
+                }
+                if min <= max {
+                    Legend::Gradient(min, max)
+                } else {
+                    Legend::None
+                }
+            }
+            None => {
+                let table = self.bin_table(options.bin_type);
+                let mut colors = HashMap::new();
+                let mut items = vec![];
+                let (mut pass_i, mut fail_i) = (0, 0);
+                for (&bin, (count, def)) in table.bins.iter() {
+                    let color = if def.bin_pf == 'P' {
+                        pass_i += 1;
+                        PASS_COLORS[(pass_i - 1) % PASS_COLORS.len()]
+                    } else {
+                        fail_i += 1;
+                        FAIL_COLORS[(fail_i - 1) % FAIL_COLORS.len()]
+                    };
+                    colors.insert(bin, color);
+                    let label = if def.bin_nam.is_empty() {
+                        format!("{} ({})", bin, count)
+                    } else {
+                        format!("{} {} ({})", bin, def.bin_nam, count)
+                    };
+                    items.push((color, bin, label));
+                }
+                for die in self.dies() {
+                    let (col, row) = self.grid_position(die.x_coord, die.y_coord).unwrap();
+                    dies.push((
+                        offset_x + col as f64 * die_w,
+                        offset_y + row as f64 * die_h,
+                        die_w,
+                        die_h,
+                        colors[&die_bin(die, options.bin_type)],
+                    ));
+                }
+                Legend::Bins(items)
+            }
+        };
+        let legend = if options.legend { legend } else { Legend::None };
+
+        let notch_size = (radius * 0.06).max(4.0);
+        let notch = match self.wcr.wf_flat {
+            'U' => Some([
+                (cx - notch_size, cy - radius),
+                (cx + notch_size, cy - radius),
+                (cx, cy - radius + notch_size),
+            ]),
+            'D' => Some([
+                (cx - notch_size, cy + radius),
+                (cx + notch_size, cy + radius),
+                (cx, cy + radius - notch_size),
+            ]),
+            'L' => Some([
+                (cx - radius, cy - notch_size),
+                (cx - radius, cy + notch_size),
+                (cx - radius + notch_size, cy),
+            ]),
+            'R' => Some([
+                (cx + radius, cy - notch_size),
+                (cx + radius, cy + notch_size),
+                (cx + radius - notch_size, cy),
+            ]),
+            _ => None,
+        };
+
+        let map_size = (2.0 * (radius + MARGIN)).ceil();
+        let legend_rows = match &legend {
+            Legend::Bins(items) => items.len() as f64 * LEGEND_ROW,
+            Legend::Gradient(..) => 200.0,
+            Legend::None => 0.0,
+        };
+        let title = if self.lot_id.is_empty() {
+            self.wafer_id.clone()
+        } else {
+            format!("{} / {}", self.lot_id, self.wafer_id)
+        };
+        Scene {
+            width: map_size + if options.legend { LEGEND_WIDTH } else { 0.0 },
+            height: map_size.max((MARGIN * 2.0 + 40.0 + legend_rows).ceil()),
+            title,
+            dies,
+            circle: (cx, cy, radius),
+            notch,
+            legend_x: map_size,
+            legend,
+        }
+    }
+}
+
+/// blue -> green -> red, `ratio` in [0, 1]
+#[inline(always)]
+fn heat_color(ratio: R8) -> Rgb {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8, t: R8| (a as R8 + (b as R8 - a as R8) * t).round() as u8;
+    if ratio < 0.5 {
+        let t = ratio * 2.0;
+        (lerp(49, 26, t), lerp(54, 152, t), lerp(149, 80, t))
+    } else {
+        let t = (ratio - 0.5) * 2.0;
+        (lerp(26, 215, t), lerp(152, 48, t), lerp(80, 39, t))
+    }
+}
+
+#[inline(always)]
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
+
+fn escape_svg(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// RGB raster canvas for PNG
+struct Canvas {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+/// 3x5 glyphs of digits, '.' and '-'
+const GLYPHS: [(char, [u8; 5]); 12] = [
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+];
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        let mut data = Vec::with_capacity(width * height * 3);
+        for _ in 0..width * height {
+            data.extend([BACKGROUND.0, BACKGROUND.1, BACKGROUND.2]);
+        }
+        Canvas {
+            width,
+            height,
+            data,
+        }
+    }
+
+    #[inline(always)]
+    fn set_pixel(&mut self, x: i64, y: i64, color: Rgb) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let i = (y as usize * self.width + x as usize) * 3;
+        self.data[i] = color.0;
+        self.data[i + 1] = color.1;
+        self.data[i + 2] = color.2;
+    }
+
+    /// fill pixels whose centers are in [x0, x1) x [y0, y1)
+    fn fill_rect(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: Rgb) {
+        let (px0, py0) = ((x0 - 0.5).ceil() as i64, (y0 - 0.5).ceil() as i64);
+        let (px1, py1) = ((x1 - 0.5).ceil() as i64, (y1 - 0.5).ceil() as i64);
+        for y in py0..py1 {
+            for x in px0..px1 {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    fn draw_ring(&mut self, cx: f64, cy: f64, r: f64, thickness: f64, color: Rgb) {
+        let reach = (r + thickness).ceil() as i64;
+        for y in (cy as i64 - reach)..=(cy as i64 + reach) {
+            for x in (cx as i64 - reach)..=(cx as i64 + reach) {
+                let dx = x as f64 + 0.5 - cx;
+                let dy = y as f64 + 0.5 - cy;
+                if ((dx * dx + dy * dy).sqrt() - r).abs() <= thickness / 2.0 {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn fill_triangle(&mut self, points: [(f64, f64); 3], color: Rgb) {
+        let [a, b, c] = points;
+        let edge = |p: (f64, f64), q: (f64, f64), x: f64, y: f64| {
+            (q.0 - p.0) * (y - p.1) - (q.1 - p.1) * (x - p.0)
+        };
+        let min_x = a.0.min(b.0).min(c.0).floor() as i64;
+        let max_x = a.0.max(b.0).max(c.0).ceil() as i64;
+        let min_y = a.1.min(b.1).min(c.1).floor() as i64;
+        let max_y = a.1.max(b.1).max(c.1).ceil() as i64;
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let e = [edge(a, b, px, py), edge(b, c, px, py), edge(c, a, px, py)];
+                if e.iter().all(|&v| v >= 0.0) || e.iter().all(|&v| v <= 0.0) {
+                    self.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// draw digits, '.' and '-' in 2x scaled 3x5 glyphs,
+    /// other characters are skipped
+    fn draw_text(&mut self, x: f64, y: f64, text: &str, color: Rgb) {
+        let mut pen_x = x as i64;
+        for ch in text.chars() {
+            if let Some((_, rows)) = GLYPHS.iter().find(|(c, _)| *c == ch) {
+                for (row, bits) in rows.iter().enumerate() {
+                    for col in 0..3 {
+                        if bits & (0b100 >> col) != 0 {
+                            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                                self.set_pixel(
+                                    pen_x + col * 2 + dx,
+                                    y as i64 + row as i64 * 2 + dy,
+                                    color,
+                                );
+                            }
+                        }
+                    }
+                }
+                pen_x += 8;
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        // filter type 0 for every scanline
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.data.chunks(self.width * 3) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        // 8 bit depth, RGB, deflate, no filter, no interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        write_chunk(&mut png, b"IHDR", &ihdr);
+        write_chunk(&mut png, b"IDAT", &zlib_compress(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(chunk_type);
+    png.extend(data);
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in chunk_type.iter().chain(data.iter()) {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    png.extend((!crc).to_be_bytes());
+}
+
+#[cfg(feature = "gzip")]
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // writing to Vec never fails
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+/// zlib stream of stored deflate blocks,
+/// used if `flate2` is not enabled
+#[cfg(not(feature = "gzip"))]
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(if blocks.peek().is_none() { 1 } else { 0 });
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend(((b << 16) | a).to_be_bytes());
+    out
+}
@@ -0,0 +1,326 @@
+//
+// merge.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use crate::summary::SummaryBuilder;
+use smart_default::SmartDefault;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Record types that are merged by `HeaderMerger`
+/// and written before the part data
+pub const HEADER_TYPES: u64 = REC_FAR
+    | REC_ATR
+    | REC_VUR
+    | REC_MIR
+    | REC_RDR
+    | REC_SDR
+    | REC_PSR
+    | REC_NMR
+    | REC_CNR
+    | REC_SSR
+    | REC_CDR
+    | REC_PMR
+    | REC_PGR
+    | REC_PLR
+    | REC_WCR;
+
+/// Record types that are recomputed
+/// from the part data and written at the end
+pub const SUMMARY_TYPES: u64 = REC_TSR | REC_HBR | REC_SBR | REC_PCR | REC_MRR;
+
+/// Options of `merge_files`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct MergeOptions {
+    /// byte order of the merged file,
+    /// `None` to use the byte order of the first file
+    pub byte_order: Option<ByteOrder>,
+}
+
+/// Result of `merge_files`
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    /// count of the source files
+    pub file_count: usize,
+    /// count of the records written to the output
+    pub record_count: u64,
+    /// count of PRRs of all the source files
+    pub part_count: u64,
+}
+
+/// Merge the header records of several files
+/// of the same lot, i.e. the records in `HEADER_TYPES`
+/// and MRR.
+///
+///  - FAR, MIR, RDR and WCR: the first one is kept,
+///    `SETUP_T` and `START_T` of MIR are the earliest
+///    non-zero time of all MIRs
+///  - PMR: union of all PMRs, the first one of
+///    the same (`PMR_INDX`, `HEAD_NUM`, `SITE_NUM`) is kept
+///  - PGR: union of all PGRs, the first one of
+///    the same `GRP_INDX` is kept
+///  - ATR, VUR, SDR, PLR, PSR, NMR, CNR, SSR and CDR:
+///    identical records are kept only once
+///  - MRR: the first one is kept, `FINISH_T` is
+///    the latest time of all MRRs
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{merge::HeaderMerger, stdf_record_type::*, StdfRecord};
+///
+/// let mut merger = HeaderMerger::new();
+/// let mut pmr = StdfRecord::new(REC_PMR);
+/// if let StdfRecord::PMR(ref mut pmr_rec) = pmr {
+///     pmr_rec.pmr_indx = 1;
+/// }
+/// // PMRs of the same index are merged
+/// merger.feed(&StdfRecord::new(REC_FAR));
+/// merger.feed(&pmr);
+/// merger.feed(&StdfRecord::new(REC_FAR));
+/// merger.feed(&pmr);
+///
+/// let recs = merger.header_records();
+/// assert_eq!(recs.len(), 2);
+/// assert!(recs[0].is_type(REC_FAR));
+/// assert!(recs[1].is_type(REC_PMR));
+/// ```
+#[derive(Debug, Default)]
+pub struct HeaderMerger {
+    far: Option<FAR>,
+    mir: Option<MIR>,
+    rdr: Option<RDR>,
+    wcr: Option<WCR>,
+    mrr: Option<MRR>,
+    atr_list: Vec<StdfRecord>,
+    vur_list: Vec<StdfRecord>,
+    sdr_list: Vec<StdfRecord>,
+    scan_list: Vec<StdfRecord>,
+    plr_list: Vec<StdfRecord>,
+    pmrs: BTreeMap<(U2, U1, U1), PMR>,
+    pgrs: BTreeMap<U2, PGR>,
+}
+
+impl HeaderMerger {
+    #[inline(always)]
+    pub fn new() -> Self {
+        HeaderMerger::default()
+    }
+
+    /// collect the records in `HEADER_TYPES` and MRR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::FAR(far_rec) => {
+                self.far.get_or_insert_with(|| far_rec.clone());
+            }
+            StdfRecord::MIR(mir_rec) => match self.mir.as_mut() {
+                Some(mir) => {
+                    mir.setup_t = earliest(mir.setup_t, mir_rec.setup_t);
+                    mir.start_t = earliest(mir.start_t, mir_rec.start_t);
+                }
+                None => self.mir = Some(mir_rec.clone()),
+            },
+            StdfRecord::RDR(rdr_rec) => {
+                self.rdr.get_or_insert_with(|| rdr_rec.clone());
+            }
+            StdfRecord::WCR(wcr_rec) => {
+                self.wcr.get_or_insert_with(|| wcr_rec.clone());
+            }
+            StdfRecord::MRR(mrr_rec) => match self.mrr.as_mut() {
+                Some(mrr) => mrr.finish_t = mrr.finish_t.max(mrr_rec.finish_t),
+                None => self.mrr = Some(mrr_rec.clone()),
+            },
+            StdfRecord::PMR(pmr_rec) => {
+                self.pmrs
+                    .entry((pmr_rec.pmr_indx, pmr_rec.head_num, pmr_rec.site_num))
+                    .or_insert_with(|| pmr_rec.clone());
+            }
+            StdfRecord::PGR(pgr_rec) => {
+                self.pgrs
+                    .entry(pgr_rec.grp_indx)
+                    .or_insert_with(|| pgr_rec.clone());
+            }
+            StdfRecord::ATR(_) => push_unique(&mut self.atr_list, rec),
+            StdfRecord::VUR(_) => push_unique(&mut self.vur_list, rec),
+            StdfRecord::SDR(_) => push_unique(&mut self.sdr_list, rec),
+            StdfRecord::PLR(_) => push_unique(&mut self.plr_list, rec),
+            StdfRecord::PSR(_)
+            | StdfRecord::NMR(_)
+            | StdfRecord::CNR(_)
+            | StdfRecord::SSR(_)
+            | StdfRecord::CDR(_) => push_unique(&mut self.scan_list, rec),
+            _ => {}
+        }
+    }
+
+    /// the merged header records, in the order
+    /// required by the specification:
+    ///
+    /// FAR, ATR, VUR, MIR, RDR, SDR, PSR, NMR,
+    /// CNR, SSR, CDR, PMR, PGR, PLR and WCR
+    pub fn header_records(&self) -> Vec<StdfRecord> {
+        let mut rec_list = vec![];
+        rec_list.extend(self.far.clone().map(StdfRecord::FAR));
+        rec_list.extend(self.atr_list.iter().cloned());
+        rec_list.extend(self.vur_list.iter().cloned());
+        rec_list.extend(self.mir.clone().map(StdfRecord::MIR));
+        rec_list.extend(self.rdr.clone().map(StdfRecord::RDR));
+        rec_list.extend(self.sdr_list.iter().cloned());
+        // keep the order of PSR, NMR, CNR, SSR and CDR
+        for rec_type in [REC_PSR, REC_NMR, REC_CNR, REC_SSR, REC_CDR] {
+            rec_list.extend(
+                self.scan_list
+                    .iter()
+                    .filter(|r| r.is_type(rec_type))
+                    .cloned(),
+            );
+        }
+        rec_list.extend(self.pmrs.values().cloned().map(StdfRecord::PMR));
+        rec_list.extend(self.pgrs.values().cloned().map(StdfRecord::PGR));
+        rec_list.extend(self.plr_list.iter().cloned());
+        rec_list.extend(self.wcr.clone().map(StdfRecord::WCR));
+        rec_list
+    }
+
+    /// the merged MRR, a default MRR
+    /// if no MRR is fed
+    #[inline(always)]
+    pub fn mrr_record(&self) -> MRR {
+        self.mrr.clone().unwrap_or_default()
+    }
+}
+
+/// Merge several STDF files of the same lot,
+/// e.g. files of different sites, retests or split wafers,
+/// into one file at `dst`.
+///
+/// The merged file contains:
+///  - the header records merged by `HeaderMerger`,
+///    `CPU_TYPE` of FAR is updated to the output byte order
+///  - all the other records of every source file except
+///    the summary records, in the order of `srcs`,
+///    therefore WIR/WRR of a split wafer appear
+///    once in every file that contains the wafer
+///  - TSR, HBR, SBR and PCR recomputed from the part data,
+///    bin names and test names are taken from the source files
+///  - the merged MRR
+///
+/// compression of all files are determined
+/// by the file extension
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::merge::{merge_files, MergeOptions};
+///
+/// let srcs = ["site1.stdf", "site2.stdf"];
+/// if let Ok(report) = merge_files(&srcs, "merged.stdf", MergeOptions::default()) {
+///     println!("{} parts merged", report.part_count);
+/// }
+/// ```
+pub fn merge_files<P, Q>(
+    srcs: &[P],
+    dst: Q,
+    options: MergeOptions,
+) -> Result<MergeReport, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    if srcs.is_empty() {
+        return Err(StdfError {
+            code: 1,
+            msg: String::from("no source file to merge"),
+        });
+    }
+
+    // 1st pass: merge the header records
+    let mut merger = HeaderMerger::new();
+    let mut order = options.byte_order;
+    for src in srcs {
+        let mut reader = StdfReader::new(src)?;
+        order.get_or_insert(reader.get_byte_order());
+        for raw_element in reader.get_rawdata_iter() {
+            let raw_element = raw_element?;
+            if raw_element.is_type(HEADER_TYPES | REC_MRR) {
+                merger.feed(&StdfRecord::from(&raw_element));
+            }
+        }
+    }
+    let order = order.unwrap_or(ByteOrder::LittleEndian);
+
+    let mut writer = StdfWriter::new(dst, order)?;
+    let mut report = MergeReport {
+        file_count: srcs.len(),
+        ..Default::default()
+    };
+    for rec in merger.header_records() {
+        writer.write_record(&rec)?;
+        report.record_count += 1;
+    }
+
+    // 2nd pass: copy the part data and recompute the summary
+    let mut summary = SummaryBuilder::new();
+    for src in srcs {
+        let mut reader = StdfReader::new(src)?;
+        for raw_element in reader.get_rawdata_iter() {
+            let raw_element = raw_element?;
+            if raw_element
+                .is_type(REC_PRR | REC_PTR | REC_MPR | REC_FTR | REC_TSR | REC_HBR | REC_SBR)
+            {
+                // summary records of the sources
+                // only provide the names
+                summary.feed(&StdfRecord::from(&raw_element));
+            }
+            if raw_element.is_type(HEADER_TYPES | SUMMARY_TYPES) {
+                continue;
+            }
+            if raw_element.is_type(REC_PRR) {
+                report.part_count += 1;
+            }
+            writer.write_raw_data(&raw_element)?;
+            report.record_count += 1;
+        }
+    }
+
+    let mut rec_list = vec![];
+    rec_list.extend(summary.tsr_records().into_iter().map(StdfRecord::TSR));
+    rec_list.extend(summary.hbr_records().into_iter().map(StdfRecord::HBR));
+    rec_list.extend(summary.sbr_records().into_iter().map(StdfRecord::SBR));
+    rec_list.extend(summary.pcr_records().into_iter().map(StdfRecord::PCR));
+    rec_list.push(StdfRecord::MRR(merger.mrr_record()));
+    for rec in rec_list.iter() {
+        writer.write_record(rec)?;
+    }
+    report.record_count += rec_list.len() as u64;
+    writer.finish()?;
+    Ok(report)
+}
+
+#[inline(always)]
+fn earliest(a: U4, b: U4) -> U4 {
+    // 0 indicates the time is unknown
+    match (a, b) {
+        (0, t) | (t, 0) => t,
+        _ => a.min(b),
+    }
+}
+
+#[inline(always)]
+fn push_unique(rec_list: &mut Vec<StdfRecord>, rec: &StdfRecord) {
+    if !rec_list.contains(rec) {
+        rec_list.push(rec.clone());
+    }
+}
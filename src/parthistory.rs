@@ -0,0 +1,281 @@
+//
+// parthistory.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::flags::PartFlags;
+use crate::stdf_types::*;
+use smart_default::SmartDefault;
+use std::collections::{BTreeMap, HashMap};
+
+/// Which attempt represents a part that is tested more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartView {
+    /// the first attempt, for first pass yield
+    FirstPass,
+    /// the last attempt, it supersedes all previous attempts
+    #[default]
+    LastPass,
+    /// the passed attempt with the lowest hard bin, or
+    /// the failed attempt with the lowest hard bin if none passed
+    BestBin,
+}
+
+/// Options of `PartHistoryBuilder`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct PartHistoryOptions {
+    /// link a PRR to the part of the same `PART_ID` in
+    /// previous files, even if bit 0 of `PART_FLG` is not set
+    #[default = false]
+    pub link_part_id_across_files: bool,
+}
+
+/// A test attempt of a part, from PRR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartAttempt {
+    /// index of the file that contains the PRR,
+    /// increased by every FAR fed to the builder
+    pub file_index: usize,
+    pub wafer_id: Cn,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub part_id: Cn,
+    pub x_coord: I2,
+    pub y_coord: I2,
+    pub hard_bin: U2,
+    pub soft_bin: U2,
+    pub part_flg: B1,
+    pub test_t: U4,
+    pub passed: bool,
+}
+
+/// All the attempts of a part, in the order they are tested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartHistory {
+    pub attempts: Vec<PartAttempt>,
+}
+
+/// Parts collected by `PartHistoryBuilder`
+#[derive(Debug, Clone, Default)]
+pub struct PartHistories {
+    pub parts: Vec<PartHistory>,
+}
+
+/// Link the PRRs of the same part within a file and
+/// across files, so that superseded results can be
+/// excluded from yield calculation.
+///
+/// A PRR is linked to the previous attempts by:
+///  - wafer id (from WIR of the same head) and X/Y
+///    coordinates, if bit 1 of `PART_FLG` is set, or the
+///    coordinates are valid and bit 0 is not set;
+///  - `PART_ID` otherwise, if it's not empty and bit 0 of
+///    `PART_FLG` is set. PRRs without the flag are linked to
+///    the parts of previous files only if
+///    `link_part_id_across_files` is enabled.
+///
+/// PRRs that cannot be linked are regarded as new parts.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{parthistory::*, stdf_file::*};
+///
+/// let mut builder = PartHistoryBuilder::new();
+/// for path in ["demo_file.stdf"] {
+///     if let Ok(mut reader) = StdfReader::new(path) {
+///         for rec in reader.get_record_iter().flatten() {
+///             builder.feed(&rec);
+///         }
+///     }
+/// }
+/// let histories = builder.finish();
+/// println!(
+///     "first pass yield: {:?}, final yield: {:?}",
+///     histories.yield_rate(PartView::FirstPass),
+///     histories.yield_rate(PartView::LastPass)
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct PartHistoryBuilder {
+    options: PartHistoryOptions,
+    file_index: usize,
+    far_seen: bool,
+    open_wafers: HashMap<U1, Cn>,
+    by_coord: HashMap<(Cn, I2, I2), usize>,
+    by_id: HashMap<Cn, usize>,
+    parts: Vec<PartHistory>,
+}
+
+impl PartHistoryBuilder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        PartHistoryBuilder::default()
+    }
+
+    pub fn with_options(options: PartHistoryOptions) -> Self {
+        PartHistoryBuilder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// collect FAR, WIR, WRR and PRR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::FAR(_) => {
+                // the first file is 0
+                if self.far_seen {
+                    self.file_index += 1;
+                }
+                self.far_seen = true;
+                self.open_wafers.clear();
+            }
+            StdfRecord::WIR(wir_rec) => {
+                self.open_wafers
+                    .insert(wir_rec.head_num, wir_rec.wafer_id.clone());
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                self.open_wafers.remove(&wrr_rec.head_num);
+            }
+            StdfRecord::PRR(prr_rec) => self.add_prr(prr_rec),
+            _ => {}
+        }
+    }
+
+    pub fn finish(self) -> PartHistories {
+        PartHistories { parts: self.parts }
+    }
+
+    fn add_prr(&mut self, prr: &PRR) {
+        let flags = PartFlags::from(prr.part_flg);
+        let wafer_id = self
+            .open_wafers
+            .get(&prr.head_num)
+            .cloned()
+            .unwrap_or_default();
+        // -32768 indicates the coordinate is invalid
+        let valid_coord = prr.x_coord != -32768 && prr.y_coord != -32768;
+        let coord_key = (wafer_id.clone(), prr.x_coord, prr.y_coord);
+        let link_by_coord = valid_coord && (flags.supersedes_xy() || !flags.supersedes_part_id());
+
+        let previous = if link_by_coord {
+            self.by_coord.get(&coord_key).copied()
+        } else if !prr.part_id.is_empty() {
+            // without the flag, the same PART_ID in a file is another part
+            self.by_id.get(&prr.part_id).copied().filter(|&index| {
+                let last_file = self.parts[index].get(PartView::LastPass).file_index;
+                flags.supersedes_part_id()
+                    || (self.options.link_part_id_across_files && last_file < self.file_index)
+            })
+        } else {
+            None
+        };
+        let attempt = PartAttempt {
+            file_index: self.file_index,
+            wafer_id,
+            head_num: prr.head_num,
+            site_num: prr.site_num,
+            part_id: prr.part_id.clone(),
+            x_coord: prr.x_coord,
+            y_coord: prr.y_coord,
+            hard_bin: prr.hard_bin,
+            soft_bin: prr.soft_bin,
+            part_flg: prr.part_flg,
+            test_t: prr.test_t,
+            passed: prr.passed() == Some(true),
+        };
+        let index = match previous {
+            Some(index) => {
+                self.parts[index].attempts.push(attempt);
+                index
+            }
+            None => {
+                self.parts.push(PartHistory {
+                    attempts: vec![attempt],
+                });
+                self.parts.len() - 1
+            }
+        };
+        // make the part reachable by both keys
+        if valid_coord {
+            self.by_coord.insert(coord_key, index);
+        }
+        if !prr.part_id.is_empty() {
+            self.by_id.insert(prr.part_id.clone(), index);
+        }
+    }
+}
+
+impl PartHistory {
+    /// the attempt that represents the part in the view
+    pub fn get(&self, view: PartView) -> &PartAttempt {
+        match view {
+            PartView::FirstPass => &self.attempts[0],
+            PartView::LastPass => &self.attempts[self.attempts.len() - 1],
+            PartView::BestBin => self
+                .attempts
+                .iter()
+                .rev()
+                .min_by_key(|a| (!a.passed, a.hard_bin))
+                .unwrap_or(&self.attempts[0]),
+        }
+    }
+
+    /// count of attempts after the first one
+    #[inline(always)]
+    pub fn retest_count(&self) -> usize {
+        self.attempts.len() - 1
+    }
+}
+
+impl PartHistories {
+    /// count of unique parts
+    #[inline(always)]
+    pub fn part_count(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// count of parts that are tested more than once
+    #[inline(always)]
+    pub fn retested_count(&self) -> usize {
+        self.parts.iter().filter(|p| p.attempts.len() > 1).count()
+    }
+
+    /// count of passed parts in the view
+    #[inline(always)]
+    pub fn good_count(&self, view: PartView) -> usize {
+        self.parts.iter().filter(|p| p.get(view).passed).count()
+    }
+
+    /// good count / part count in the view,
+    /// `None` if there is no part
+    pub fn yield_rate(&self, view: PartView) -> Option<f64> {
+        if self.parts.is_empty() {
+            None
+        } else {
+            Some(self.good_count(view) as f64 / self.parts.len() as f64)
+        }
+    }
+
+    /// the attempts that represent every part in the view
+    pub fn final_attempts(&self, view: PartView) -> Vec<&PartAttempt> {
+        self.parts.iter().map(|p| p.get(view)).collect()
+    }
+
+    /// part count of every hard bin in the view
+    pub fn hbin_counts(&self, view: PartView) -> BTreeMap<U2, usize> {
+        let mut counts = BTreeMap::new();
+        for attempt in self.final_attempts(view) {
+            *counts.entry(attempt.hard_bin).or_insert(0) += 1;
+        }
+        counts
+    }
+}
@@ -0,0 +1,286 @@
+//
+// pinmap.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_types::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// A pin from PMR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pin {
+    pub pmr_indx: U2,
+    pub chan_typ: U2,
+    pub chan_nam: Cn,
+    pub phy_nam: Cn,
+    pub log_nam: Cn,
+    pub head_num: U1,
+    pub site_num: U1,
+}
+
+/// A pin group from PGR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinGroup {
+    pub grp_indx: U2,
+    pub grp_nam: Cn,
+    pub pmr_indx: KxU2,
+}
+
+/// Display settings of a pin or pin group from PLR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinListInfo {
+    pub grp_mode: U2,
+    pub grp_radx: U1,
+    pub pgm_char: Cn,
+    pub rtn_char: Cn,
+    pub pgm_chal: Cn,
+    pub rtn_chal: Cn,
+}
+
+/// A returned state and result of MPR,
+/// paired with the pin
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinResult<'a> {
+    pub pmr_indx: U2,
+    /// `None` if the PMR of `pmr_indx` is not found
+    pub pin: Option<&'a Pin>,
+    pub rtn_stat: Option<U1>,
+    pub rtn_rslt: Option<R4>,
+}
+
+/// A returned or programmed state of FTR,
+/// paired with the pin
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinState<'a> {
+    pub pmr_indx: U2,
+    /// `None` if the PMR of `pmr_indx` is not found
+    pub pin: Option<&'a Pin>,
+    pub state: U1,
+}
+
+/// Resolve the PMR indexes in MPR, FTR and STR
+/// to pins, built from PMR, PGR and PLR.
+///
+/// PMR indexes may be reused by different heads/sites,
+/// a pin is looked up by the head/site of the test record
+/// first, and by the index only if there is no such PMR.
+///
+/// MPR omits `RTN_INDX` if it is the same as the first
+/// MPR of the test, therefore MPRs should be fed as well,
+/// in order to resolve the MPRs after the first one.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{pinmap::PinMap, stdf_file::*, StdfRecord};
+///
+/// let mut pin_map = PinMap::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         pin_map.feed(&rec);
+///         if let StdfRecord::MPR(mpr_rec) = &rec {
+///             for pin_rslt in pin_map.resolve_mpr(mpr_rec) {
+///                 let name = pin_rslt.pin.map_or("", |p| p.log_nam.as_str());
+///                 println!("{}: {:?}", name, pin_rslt.rtn_rslt);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct PinMap {
+    pins: BTreeMap<U2, Vec<Pin>>,
+    groups: BTreeMap<U2, PinGroup>,
+    // groups of every pin
+    memberships: HashMap<U2, Vec<U2>>,
+    pin_lists: BTreeMap<U2, PinListInfo>,
+    // RTN_INDX of the first MPR of every test
+    mpr_indexes: HashMap<U4, KxU2>,
+}
+
+impl PinMap {
+    #[inline(always)]
+    pub fn new() -> Self {
+        PinMap::default()
+    }
+
+    /// collect PMR, PGR, PLR and the `RTN_INDX`
+    /// of MPR, other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::PMR(pmr_rec) => {
+                let pin = Pin {
+                    pmr_indx: pmr_rec.pmr_indx,
+                    chan_typ: pmr_rec.chan_typ,
+                    chan_nam: pmr_rec.chan_nam.clone(),
+                    phy_nam: pmr_rec.phy_nam.clone(),
+                    log_nam: pmr_rec.log_nam.clone(),
+                    head_num: pmr_rec.head_num,
+                    site_num: pmr_rec.site_num,
+                };
+                let pins = self.pins.entry(pmr_rec.pmr_indx).or_default();
+                // a later PMR of the same head/site replaces the previous one
+                match pins
+                    .iter_mut()
+                    .find(|p| p.head_num == pin.head_num && p.site_num == pin.site_num)
+                {
+                    Some(p) => *p = pin,
+                    None => pins.push(pin),
+                }
+            }
+            StdfRecord::PGR(pgr_rec) => {
+                if let Some(old) = self.groups.get(&pgr_rec.grp_indx) {
+                    for indx in old.pmr_indx.iter() {
+                        if let Some(grps) = self.memberships.get_mut(indx) {
+                            grps.retain(|&g| g != pgr_rec.grp_indx);
+                        }
+                    }
+                }
+                for &indx in pgr_rec.pmr_indx.iter() {
+                    let grps = self.memberships.entry(indx).or_default();
+                    if !grps.contains(&pgr_rec.grp_indx) {
+                        grps.push(pgr_rec.grp_indx);
+                    }
+                }
+                self.groups.insert(
+                    pgr_rec.grp_indx,
+                    PinGroup {
+                        grp_indx: pgr_rec.grp_indx,
+                        grp_nam: pgr_rec.grp_nam.clone(),
+                        pmr_indx: pgr_rec.pmr_indx.clone(),
+                    },
+                );
+            }
+            StdfRecord::PLR(plr_rec) => {
+                let text = |v: &KxCn, i: usize| v.get(i).cloned().unwrap_or_default();
+                for (i, &indx) in plr_rec.grp_indx.iter().enumerate() {
+                    self.pin_lists.insert(
+                        indx,
+                        PinListInfo {
+                            grp_mode: plr_rec.grp_mode.get(i).copied().unwrap_or(0),
+                            grp_radx: plr_rec.grp_radx.get(i).copied().unwrap_or(0),
+                            pgm_char: text(&plr_rec.pgm_char, i),
+                            rtn_char: text(&plr_rec.rtn_char, i),
+                            pgm_chal: text(&plr_rec.pgm_chal, i),
+                            rtn_chal: text(&plr_rec.rtn_chal, i),
+                        },
+                    );
+                }
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                if let Some(rtn_indx) = &mpr_rec.rtn_indx {
+                    self.mpr_indexes
+                        .entry(mpr_rec.test_num)
+                        .or_insert_with(|| rtn_indx.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// return the pin of `pmr_indx` on the head/site,
+    /// or the pin shared by all sites of the head (site 255)
+    /// or by all heads (head 255) if the head/site has no PMR
+    pub fn get_pin(&self, head_num: U1, site_num: U1, pmr_indx: U2) -> Option<&Pin> {
+        let pins = self.pins.get(&pmr_indx)?;
+        pins.iter()
+            .find(|p| p.head_num == head_num && p.site_num == site_num)
+            .or_else(|| {
+                pins.iter()
+                    .find(|p| p.head_num == head_num && p.site_num == 255)
+            })
+            .or_else(|| pins.iter().find(|p| p.head_num == 255))
+    }
+
+    /// return the pin group of `grp_indx`
+    #[inline(always)]
+    pub fn get_group(&self, grp_indx: U2) -> Option<&PinGroup> {
+        self.groups.get(&grp_indx)
+    }
+
+    /// return the pins of a group on the head/site,
+    /// pins without PMR are skipped
+    pub fn get_group_pins(&self, head_num: U1, site_num: U1, grp_indx: U2) -> Vec<&Pin> {
+        self.groups.get(&grp_indx).map_or(vec![], |grp| {
+            grp.pmr_indx
+                .iter()
+                .filter_map(|&indx| self.get_pin(head_num, site_num, indx))
+                .collect()
+        })
+    }
+
+    /// return the groups that contain `pmr_indx`
+    pub fn get_pin_groups(&self, pmr_indx: U2) -> Vec<&PinGroup> {
+        self.memberships.get(&pmr_indx).map_or(vec![], |grps| {
+            grps.iter().filter_map(|g| self.groups.get(g)).collect()
+        })
+    }
+
+    /// return the PLR settings of a pin or pin group
+    #[inline(always)]
+    pub fn get_pin_list(&self, indx: U2) -> Option<&PinListInfo> {
+        self.pin_lists.get(&indx)
+    }
+
+    /// pair `RTN_STAT` and `RTN_RSLT` of MPR with the pins,
+    /// `RTN_INDX` of the first MPR of the test is used
+    /// if it is omitted
+    pub fn resolve_mpr(&self, mpr: &MPR) -> Vec<PinResult<'_>> {
+        let rtn_indx = match &mpr.rtn_indx {
+            Some(indx) if !indx.is_empty() => indx,
+            _ => match self.mpr_indexes.get(&mpr.test_num) {
+                Some(indx) => indx,
+                None => return vec![],
+            },
+        };
+        rtn_indx
+            .iter()
+            .enumerate()
+            .map(|(i, &pmr_indx)| PinResult {
+                pmr_indx,
+                pin: self.get_pin(mpr.head_num, mpr.site_num, pmr_indx),
+                rtn_stat: mpr.rtn_stat.get(i).copied(),
+                rtn_rslt: mpr.rtn_rslt.get(i).copied(),
+            })
+            .collect()
+    }
+
+    /// pair the returned states and programmed states
+    /// of FTR with the pins, respectively
+    pub fn resolve_ftr(&self, ftr: &FTR) -> (Vec<PinState<'_>>, Vec<PinState<'_>>) {
+        let pair = |indx: &KxU2, stat: &KxN1| {
+            indx.iter()
+                .zip(stat.iter())
+                .map(|(&pmr_indx, &state)| PinState {
+                    pmr_indx,
+                    pin: self.get_pin(ftr.head_num, ftr.site_num, pmr_indx),
+                    state,
+                })
+                .collect()
+        };
+        (
+            pair(&ftr.rtn_indx, &ftr.rtn_stat),
+            pair(&ftr.pgm_indx, &ftr.pgm_stat),
+        )
+    }
+
+    /// return the pins of `PMR_INDX` in STR,
+    /// in the same order
+    pub fn resolve_str(&self, str_rec: &STR) -> Vec<Option<&Pin>> {
+        (0..str_rec.pmr_indx.len())
+            .map(|i| {
+                str_rec
+                    .pmr_indx
+                    .get(i)
+                    .and_then(|indx| U2::try_from(indx).ok())
+                    .and_then(|indx| self.get_pin(str_rec.head_num, str_rec.site_num, indx))
+            })
+            .collect()
+    }
+}
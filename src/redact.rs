@@ -0,0 +1,379 @@
+//
+// redact.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use crate::transform::{Transform, TransformReport};
+use smart_default::SmartDefault;
+use std::collections::HashMap;
+use std::io::{BufRead, Seek, Write};
+use std::path::Path;
+
+/// Kind of a sensitive field, values of the same
+/// kind share a pseudonym table, e.g. `LOT_ID` of MIR
+/// and `LOT_ID` of another file are redacted to the
+/// same value by the same `Redactor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// MIR: `LOT_ID`, `ENG_ID`
+    Lot,
+    /// MIR: `SBLOT_ID`
+    Sublot,
+    /// MIR: `PART_TYP`, `FAMLY_ID`
+    Product,
+    /// MIR: `NODE_NAM`, `SERL_NUM`
+    Node,
+    /// MIR: `JOB_NAM`, `AUX_FILE`
+    Job,
+    /// MIR: `OPER_NAM`, `SUPR_NAM`
+    Operator,
+    /// MIR: `FACIL_ID`
+    Facility,
+    /// MIR: `FLOOR_ID`
+    Floor,
+    /// MIR: `PROC_ID`
+    Process,
+    /// SDR: the IDs of handler, probe card, load board, etc.
+    Equipment,
+    /// WIR/WRR: `WAFER_ID`, `FABWF_ID`, `FRAME_ID`, `MASK_ID`
+    Wafer,
+    /// PRR: `PART_ID`
+    Part,
+    /// PTR/MPR/FTR/STR: `TEST_TXT`, TSR: `TEST_NAM`
+    Test,
+    /// free text, e.g. DTR, `Cn` of GDR, `USER_TXT` of MIR
+    Text,
+}
+
+impl FieldKind {
+    /// prefix of the pseudonyms
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            FieldKind::Lot => "LOT",
+            FieldKind::Sublot => "SUBLOT",
+            FieldKind::Product => "PRODUCT",
+            FieldKind::Node => "NODE",
+            FieldKind::Job => "JOB",
+            FieldKind::Operator => "OPERATOR",
+            FieldKind::Facility => "FACILITY",
+            FieldKind::Floor => "FLOOR",
+            FieldKind::Process => "PROCESS",
+            FieldKind::Equipment => "EQUIP",
+            FieldKind::Wafer => "WAFER",
+            FieldKind::Part => "PART",
+            FieldKind::Test => "TEST",
+            FieldKind::Text => "TEXT",
+        }
+    }
+}
+
+/// How the sensitive fields are redacted
+#[derive(SmartDefault, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactMode {
+    /// replace by the prefix of `FieldKind` and
+    /// a sequence number, e.g. `LOT_1`, `PART_25`
+    #[default]
+    Pseudonym,
+    /// replace by the hex string of the salted
+    /// 64-bit FNV-1a hash, results are consistent
+    /// across `Redactor`s using the same salt.
+    ///
+    /// FNV-1a is not a cryptographic hash, the values can
+    /// be recovered by brute force, even with an unknown
+    /// salt, once a single value and its hash are known.
+    /// Use `Pseudonym` if the mapping must not be reversible.
+    Hash,
+    /// replace by an empty string
+    Blank,
+}
+
+/// Options of `Redactor`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct RedactOptions {
+    pub mode: RedactMode,
+    /// salt of `RedactMode::Hash`, different salts
+    /// give unrelated hashes of the same value
+    pub salt: String,
+    /// redact the test names, limits, results and
+    /// test numbers are kept in any case
+    #[default = false]
+    pub test_names: bool,
+    /// redact the text of DTR and `Cn` fields of GDR
+    #[default = true]
+    pub text_records: bool,
+    /// byte order of the output file in `redact_file`,
+    /// `None` to keep the byte order of the source file
+    pub byte_order: Option<ByteOrder>,
+}
+
+/// Scrub or hash the sensitive fields of STDF records
+///
+/// Only the text fields that identify the lot, product,
+/// site, people or equipment are changed, so the structure
+/// of the file, the test results and the statistics are
+/// preserved. A value is redacted consistently within the
+/// `Redactor`, e.g. retests of the same `PART_ID` are
+/// still linked after redaction.
+///
+/// Empty fields are kept empty.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{redact::*, stdf_record_type::*, StdfRecord};
+///
+/// let mut redactor = Redactor::new();
+/// let mut mir = StdfRecord::new(REC_MIR);
+/// if let StdfRecord::MIR(ref mut mir_rec) = mir {
+///     mir_rec.lot_id = "SECRET_LOT".to_string();
+/// }
+/// redactor.redact(&mut mir);
+/// assert!(matches!(&mir, StdfRecord::MIR(m) if m.lot_id == "LOT_1"));
+/// assert_eq!(redactor.lookup(FieldKind::Lot, "SECRET_LOT"), Some("LOT_1"));
+///
+/// // or redact files
+/// // redact_file("input.stdf", "output.stdf", RedactOptions::default()).unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct Redactor {
+    options: RedactOptions,
+    table: HashMap<(FieldKind, String), String>,
+    counters: HashMap<FieldKind, u32>,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Redactor::default()
+    }
+
+    pub fn with_options(options: RedactOptions) -> Self {
+        Redactor {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// record types that may contain sensitive fields
+    /// under the current options
+    pub fn rec_types(&self) -> u64 {
+        let mut rec_types = REC_ATR | REC_MIR | REC_MRR | REC_SDR | REC_WIR | REC_WRR | REC_PRR;
+        if self.options.test_names {
+            rec_types |= REC_PTR | REC_MPR | REC_FTR | REC_STR | REC_TSR;
+        }
+        if self.options.text_records {
+            rec_types |= REC_DTR | REC_GDR;
+        }
+        rec_types
+    }
+
+    /// return the redacted value of `value`
+    pub fn redact_value(&mut self, kind: FieldKind, value: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        let key = (kind, value.to_string());
+        if let Some(redacted) = self.table.get(&key) {
+            return redacted.clone();
+        }
+        let redacted = match self.options.mode {
+            RedactMode::Pseudonym => {
+                let counter = self.counters.entry(kind).or_insert(0);
+                *counter += 1;
+                format!("{}_{}", kind.prefix(), counter)
+            }
+            RedactMode::Hash => format!("{:016X}", fnv1a(&self.options.salt, value)),
+            RedactMode::Blank => String::new(),
+        };
+        self.table.insert(key, redacted.clone());
+        redacted
+    }
+
+    /// return the redacted value of `value` if it
+    /// has been redacted by this `Redactor`
+    pub fn lookup(&self, kind: FieldKind, value: &str) -> Option<&str> {
+        self.table
+            .get(&(kind, value.to_string()))
+            .map(|s| s.as_str())
+    }
+
+    /// return the table of (kind, original value) -> redacted value,
+    /// the sender may keep it to translate the feedback
+    /// of the receiver
+    pub fn get_mapping(&self) -> &HashMap<(FieldKind, String), String> {
+        &self.table
+    }
+
+    /// redact the sensitive fields of `rec` in place
+    pub fn redact(&mut self, rec: &mut StdfRecord) {
+        match rec {
+            StdfRecord::ATR(atr_rec) => {
+                self.field(FieldKind::Text, &mut atr_rec.cmd_line);
+            }
+            StdfRecord::MIR(mir_rec) => {
+                self.field(FieldKind::Lot, &mut mir_rec.lot_id);
+                self.field(FieldKind::Lot, &mut mir_rec.eng_id);
+                self.field(FieldKind::Sublot, &mut mir_rec.sblot_id);
+                self.field(FieldKind::Product, &mut mir_rec.part_typ);
+                self.field(FieldKind::Product, &mut mir_rec.famly_id);
+                self.field(FieldKind::Node, &mut mir_rec.node_nam);
+                self.field(FieldKind::Node, &mut mir_rec.serl_num);
+                self.field(FieldKind::Job, &mut mir_rec.job_nam);
+                self.field(FieldKind::Job, &mut mir_rec.aux_file);
+                self.field(FieldKind::Operator, &mut mir_rec.oper_nam);
+                self.field(FieldKind::Operator, &mut mir_rec.supr_nam);
+                self.field(FieldKind::Facility, &mut mir_rec.facil_id);
+                self.field(FieldKind::Floor, &mut mir_rec.floor_id);
+                self.field(FieldKind::Process, &mut mir_rec.proc_id);
+                self.field(FieldKind::Text, &mut mir_rec.user_txt);
+            }
+            StdfRecord::MRR(mrr_rec) => {
+                self.field(FieldKind::Text, &mut mrr_rec.usr_desc);
+                self.field(FieldKind::Text, &mut mrr_rec.exc_desc);
+            }
+            StdfRecord::SDR(sdr_rec) => {
+                for id in [
+                    &mut sdr_rec.hand_id,
+                    &mut sdr_rec.card_id,
+                    &mut sdr_rec.load_id,
+                    &mut sdr_rec.dib_id,
+                    &mut sdr_rec.cabl_id,
+                    &mut sdr_rec.cont_id,
+                    &mut sdr_rec.lasr_id,
+                    &mut sdr_rec.extr_id,
+                ] {
+                    self.field(FieldKind::Equipment, id);
+                }
+            }
+            StdfRecord::WIR(wir_rec) => {
+                self.field(FieldKind::Wafer, &mut wir_rec.wafer_id);
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                self.field(FieldKind::Wafer, &mut wrr_rec.wafer_id);
+                self.field(FieldKind::Wafer, &mut wrr_rec.fabwf_id);
+                self.field(FieldKind::Wafer, &mut wrr_rec.frame_id);
+                self.field(FieldKind::Wafer, &mut wrr_rec.mask_id);
+                self.field(FieldKind::Text, &mut wrr_rec.usr_desc);
+                self.field(FieldKind::Text, &mut wrr_rec.exc_desc);
+            }
+            StdfRecord::PRR(prr_rec) => {
+                self.field(FieldKind::Part, &mut prr_rec.part_id);
+                self.field(FieldKind::Text, &mut prr_rec.part_txt);
+            }
+            StdfRecord::PTR(ptr_rec) if self.options.test_names => {
+                self.field(FieldKind::Test, &mut ptr_rec.test_txt);
+            }
+            StdfRecord::MPR(mpr_rec) if self.options.test_names => {
+                self.field(FieldKind::Test, &mut mpr_rec.test_txt);
+            }
+            StdfRecord::FTR(ftr_rec) if self.options.test_names => {
+                self.field(FieldKind::Test, &mut ftr_rec.test_txt);
+            }
+            StdfRecord::STR(str_rec) if self.options.test_names => {
+                self.field(FieldKind::Test, &mut str_rec.test_txt);
+            }
+            StdfRecord::TSR(tsr_rec) if self.options.test_names => {
+                self.field(FieldKind::Test, &mut tsr_rec.test_nam);
+            }
+            StdfRecord::DTR(dtr_rec) if self.options.text_records => {
+                self.field(FieldKind::Text, &mut dtr_rec.text_dat);
+            }
+            StdfRecord::GDR(gdr_rec) if self.options.text_records => {
+                for data in gdr_rec.gen_data.iter_mut() {
+                    if let V1::Cn(text) = data {
+                        self.field(FieldKind::Text, text);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    fn field(&mut self, kind: FieldKind, value: &mut Cn) {
+        *value = self.redact_value(kind, value);
+    }
+}
+
+/// 64-bit FNV-1a hash of `salt` followed by `value`,
+/// non-cryptographic
+fn fnv1a(salt: &str, value: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in salt.bytes().chain(value.bytes()) {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Redact the records from `reader` by `redactor`
+/// and write to `writer`, records without sensitive
+/// fields are copied without parsing.
+///
+/// The same `redactor` can be used for several files
+/// to keep the redacted values consistent among them.
+///
+/// Reading stops at the first error, the writer
+/// is not finished, caller should call `finish` on it.
+pub fn redact_stream<R, W>(
+    reader: &mut StdfReader<R>,
+    writer: &mut StdfWriter<W>,
+    redactor: &mut Redactor,
+) -> Result<TransformReport, StdfError>
+where
+    R: BufRead + Seek,
+    W: Write,
+{
+    let rec_types = redactor.rec_types();
+    Transform::new()
+        .modify(rec_types, |rec| redactor.redact(rec))
+        .run_stream(reader, writer)
+}
+
+/// Redact the STDF file at `src` and write to `dst`,
+/// compression of both files are determined
+/// by the file extension
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::redact::{redact_file, RedactMode, RedactOptions};
+///
+/// let options = RedactOptions {
+///     mode: RedactMode::Hash,
+///     salt: "not-so-secret".to_string(),
+///     test_names: true,
+///     ..Default::default()
+/// };
+/// if let Ok(report) = redact_file("input.stdf", "for_vendor.stdf", options) {
+///     println!("{} records written", report.write_count);
+/// }
+/// ```
+pub fn redact_file<P, Q>(
+    src: P,
+    dst: Q,
+    options: RedactOptions,
+) -> Result<TransformReport, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(src)?;
+    let order = options
+        .byte_order
+        .unwrap_or_else(|| reader.get_byte_order());
+    let mut redactor = Redactor::with_options(options);
+    let mut writer = StdfWriter::new(dst, order)?;
+    let report = redact_stream(&mut reader, &mut writer, &mut redactor)?;
+    writer.finish()?;
+    Ok(report)
+}
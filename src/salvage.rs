@@ -0,0 +1,295 @@
+//
+// salvage.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use crate::summary::{PartCount, SummaryBuilder};
+use smart_default::SmartDefault;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Seek, Write};
+use std::path::Path;
+
+/// Options of the synthesized records
+#[derive(SmartDefault, Debug, Clone)]
+pub struct SalvageOptions {
+    /// hardware bin assigned to the parts
+    /// that are not closed by a PRR
+    #[default = 0]
+    pub abort_hbin: U2,
+    /// software bin assigned to the parts
+    /// that are not closed by a PRR,
+    /// 65535 indicates soft bin is not used
+    #[default = 65535]
+    pub abort_sbin: U2,
+}
+
+/// Truncated STDF file salvager
+///
+/// All records of the damaged file are fed into
+/// the salvager in order, `finish` returns the records
+/// that must be appended to make the file complete:
+///
+///  - EPS for every unclosed BPS
+///  - PRR for every part that is not finished,
+///    with `abnormal end` flag set, and `failed` flag
+///    set if any of the executed tests failed
+///  - WRR for the unclosed wafer
+///  - TSR, HBR, SBR and PCR recomputed from the
+///    parts and tests, only if the type is absent
+///  - MRR
+///
+/// Nothing is synthesized if MRR is already seen.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{salvage::Salvage, stdf_record_type::*, StdfRecord};
+///
+/// let mut salvage = Salvage::new();
+/// let mut pir = StdfRecord::new(REC_PIR);
+/// if let StdfRecord::PIR(ref mut pir_rec) = pir {
+///     pir_rec.head_num = 1;
+///     pir_rec.site_num = 1;
+/// }
+/// salvage.feed(&StdfRecord::new(REC_FAR));
+/// salvage.feed(&StdfRecord::new(REC_MIR));
+/// salvage.feed(&pir);
+/// // the file ends here...
+///
+/// let recs = salvage.finish();
+/// assert!(recs[0].is_type(REC_PRR));
+/// assert!(recs.last().unwrap().is_type(REC_MRR));
+/// ```
+#[derive(Debug, Default)]
+pub struct Salvage {
+    options: SalvageOptions,
+    summary: SummaryBuilder,
+    seen_types: u64,
+    last_time: U4,
+    bps_depth: usize,
+    open_parts: BTreeMap<(U1, U1), OpenPart>,
+    open_wafer: Option<(WIR, PartCount)>,
+}
+
+/// Result of `salvage_stream` or `salvage_file`
+#[derive(Debug)]
+pub struct SalvageReport {
+    /// count of the records copied from the source
+    pub record_count: u64,
+    /// the error that stops the reading,
+    /// `None` if the source is read to the end
+    pub read_error: Option<StdfError>,
+    /// records appended to the output
+    pub synthesized: Vec<StdfRecord>,
+}
+
+#[derive(Debug, Default)]
+struct OpenPart {
+    num_test: U2,
+    failed: bool,
+}
+
+impl Salvage {
+    pub fn new() -> Self {
+        Salvage::default()
+    }
+
+    pub fn with_options(options: SalvageOptions) -> Self {
+        Salvage {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// feed a record of the damaged file
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        self.seen_types |= rec.get_type();
+        self.summary.feed(rec);
+        match rec {
+            StdfRecord::MIR(mir_rec) => {
+                self.update_time(mir_rec.setup_t);
+                self.update_time(mir_rec.start_t);
+            }
+            StdfRecord::WIR(wir_rec) => {
+                self.update_time(wir_rec.start_t);
+                self.open_wafer = Some((wir_rec.clone(), PartCount::default()));
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                self.update_time(wrr_rec.finish_t);
+                self.open_wafer = None;
+            }
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts
+                    .insert((pir_rec.head_num, pir_rec.site_num), OpenPart::default());
+            }
+            StdfRecord::PTR(ptr_rec) => {
+                self.add_test(ptr_rec.head_num, ptr_rec.site_num, ptr_rec.test_flg)
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                self.add_test(mpr_rec.head_num, mpr_rec.site_num, mpr_rec.test_flg)
+            }
+            StdfRecord::FTR(ftr_rec) => {
+                self.add_test(ftr_rec.head_num, ftr_rec.site_num, ftr_rec.test_flg)
+            }
+            StdfRecord::PRR(prr_rec) => {
+                self.open_parts
+                    .remove(&(prr_rec.head_num, prr_rec.site_num));
+                if let Some((_, wafer_cnt)) = self.open_wafer.as_mut() {
+                    wafer_cnt.add_prr(prr_rec);
+                }
+            }
+            StdfRecord::BPS(_) => self.bps_depth += 1,
+            StdfRecord::EPS(_) => self.bps_depth = self.bps_depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// generate the records that
+    /// complete the damaged file
+    pub fn finish(mut self) -> Vec<StdfRecord> {
+        let mut rec_list = vec![];
+        if self.seen_types & REC_MRR != 0 {
+            return rec_list;
+        }
+        rec_list.extend((0..self.bps_depth).map(|_| StdfRecord::EPS(EPS {})));
+
+        let open_parts = std::mem::take(&mut self.open_parts);
+        for ((head_num, site_num), part) in open_parts {
+            // bit 2: abnormal end of testing
+            // bit 3: part failed, or
+            // bit 4: pass/fail flag is invalid
+            let part_flg = if part.failed {
+                0b0000_1100
+            } else {
+                0b0001_0100
+            };
+            let prr = StdfRecord::PRR(PRR {
+                head_num,
+                site_num,
+                part_flg: [part_flg],
+                num_test: part.num_test,
+                hard_bin: self.options.abort_hbin,
+                soft_bin: self.options.abort_sbin,
+                ..Default::default()
+            });
+            self.feed(&prr);
+            rec_list.push(prr);
+        }
+
+        if let Some((wir, cnt)) = self.open_wafer.take() {
+            let wrr = StdfRecord::WRR(WRR {
+                head_num: wir.head_num,
+                site_grp: wir.site_grp,
+                finish_t: self.last_time,
+                part_cnt: cnt.part_cnt,
+                rtst_cnt: cnt.rtst_cnt,
+                abrt_cnt: cnt.abrt_cnt,
+                good_cnt: cnt.good_cnt,
+                wafer_id: wir.wafer_id,
+                ..Default::default()
+            });
+            rec_list.push(wrr);
+        }
+
+        if self.seen_types & REC_TSR == 0 {
+            rec_list.extend(self.summary.tsr_records().into_iter().map(StdfRecord::TSR));
+        }
+        if self.seen_types & REC_HBR == 0 {
+            rec_list.extend(self.summary.hbr_records().into_iter().map(StdfRecord::HBR));
+        }
+        if self.seen_types & REC_SBR == 0 {
+            rec_list.extend(self.summary.sbr_records().into_iter().map(StdfRecord::SBR));
+        }
+        if self.seen_types & REC_PCR == 0 {
+            rec_list.extend(self.summary.pcr_records().into_iter().map(StdfRecord::PCR));
+        }
+        rec_list.push(StdfRecord::MRR(MRR {
+            finish_t: self.last_time,
+            ..Default::default()
+        }));
+        rec_list
+    }
+
+    #[inline(always)]
+    fn update_time(&mut self, t: U4) {
+        self.last_time = self.last_time.max(t);
+    }
+
+    #[inline(always)]
+    fn add_test(&mut self, head_num: U1, site_num: U1, test_flg: [u8; 1]) {
+        if let Some(part) = self.open_parts.get_mut(&(head_num, site_num)) {
+            part.num_test = part.num_test.saturating_add(1);
+            // bit 6: no pass/fail indication, bit 7: test failed
+            part.failed |= test_flg[0] & 0b1100_0000 == 0b1000_0000;
+        }
+    }
+}
+
+/// Copy the readable records from `reader` to `writer`,
+/// and append the records synthesized by `Salvage`.
+///
+/// Reading stops at the first error, which is
+/// reported in `SalvageReport.read_error`, the writer
+/// is not finished, caller should call `finish` on it.
+pub fn salvage_stream<R, W>(
+    reader: &mut StdfReader<R>,
+    writer: &mut StdfWriter<W>,
+    options: SalvageOptions,
+) -> Result<SalvageReport, StdfError>
+where
+    R: BufRead + Seek,
+    W: Write,
+{
+    let mut salvage = Salvage::with_options(options);
+    let mut record_count = 0;
+    let mut read_error = None;
+    for raw_element in reader.get_rawdata_iter() {
+        let raw_element = match raw_element {
+            Ok(r) => r,
+            Err(e) => {
+                read_error = Some(e);
+                break;
+            }
+        };
+        writer.write_raw_data(&raw_element)?;
+        salvage.feed(&StdfRecord::from(&raw_element));
+        record_count += 1;
+    }
+    let synthesized = salvage.finish();
+    for rec in synthesized.iter() {
+        writer.write_record(rec)?;
+    }
+    Ok(SalvageReport {
+        record_count,
+        read_error,
+        synthesized,
+    })
+}
+
+/// Salvage the STDF file at `src` and
+/// write a repaired copy to `dst`,
+/// byte order of the source file is kept.
+///
+/// compression of both files are determined
+/// by the file extension
+pub fn salvage_file<P, Q>(src: P, dst: Q) -> Result<SalvageReport, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(src)?;
+    let mut writer = StdfWriter::new(dst, reader.get_byte_order())?;
+    let report = salvage_stream(&mut reader, &mut writer, SalvageOptions::default())?;
+    writer.finish()?;
+    Ok(report)
+}
@@ -0,0 +1,350 @@
+//
+// scandiag.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::faildata::{StrFail, StrFailDecoder};
+use crate::pinmap::PinMap;
+use crate::stdf_types::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// A scan fail of STR, resolved with the
+/// V4-2007 scan records, fields are `None` if
+/// the data is absent or cannot be resolved
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ScanFail {
+    /// `PART_ID` of the PRR that ends the part,
+    /// empty if the STR is not inside PIR/PRR
+    pub part_id: Cn,
+    pub x_coord: I2,
+    pub y_coord: I2,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_num: U4,
+    /// pattern file in PSR that contains the cycle
+    pub pat_file: Option<Cn>,
+    pub pat_lbl: Option<Cn>,
+    pub cycle: Option<U8>,
+    pub pmr_indx: Option<U2>,
+    /// ATPG signal name in NMR, or
+    /// logical pin name in PMR
+    pub pin_nam: Option<Cn>,
+    pub chn_num: Option<U8>,
+    /// chain name in CDR
+    pub chn_nam: Option<Cn>,
+    /// name of the scan structure (SSR)
+    /// that contains the chain
+    pub ssr_nam: Option<Cn>,
+    pub bit_pos: Option<U8>,
+    /// cell name in CNR, or in the cell list of CDR
+    pub cell_nam: Option<Cn>,
+    pub expected: Option<U1>,
+    pub captured: Option<U1>,
+}
+
+/// Scan fails of a file, see `ScanDiagnosisBuilder`
+#[derive(Debug, Clone, Default)]
+pub struct ScanDiagnosis {
+    pub fails: Vec<ScanFail>,
+}
+
+/// Resolve the fails in STR into pattern files,
+/// chains and scan cells, for scan diagnosis.
+///
+///  - pattern: the PSR referenced by `STR.PSR_REF`,
+///    whose `PAT_BGN`..=`PAT_END` contains the cycle
+///  - chain: `STR.CHN_NUM` is the CDR index,
+///    the SSR that lists the CDR is the scan structure
+///  - cell: CNR of the chain number and bit position,
+///    or the `CELL_LST` of CDR at the bit position
+///  - pin: ATPG name in NMR, or logical name in PMR
+///
+/// Continuation records of STR, PSR, NMR and CDR
+/// are merged. STR, PSR, NMR and CDR are expected to
+/// be in the file before the STR that refers to them,
+/// the part information is filled by the PRR.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{scandiag::ScanDiagnosisBuilder, stdf_file::*};
+///
+/// let mut builder = ScanDiagnosisBuilder::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         builder.feed(&rec);
+///     }
+/// }
+/// let diagnosis = builder.finish();
+/// println!("{}", diagnosis.to_table());
+/// ```
+#[derive(Debug, Default)]
+pub struct ScanDiagnosisBuilder {
+    pin_map: PinMap,
+    str_decoder: StrFailDecoder,
+    // psr_indx -> (pat_bgn, pat_end, pat_file, pat_lbl)
+    patterns: HashMap<U2, Vec<(U8, U8, Cn, Cn)>>,
+    atpg_names: HashMap<U2, Cn>,
+    // cdr_indx -> (chn_nam, cell_lst)
+    chains: HashMap<U2, (Cn, KxSn)>,
+    // cdr_indx -> ssr_nam
+    scan_structs: HashMap<U2, Cn>,
+    cell_names: HashMap<(U2, U4), Cn>,
+    // fails of the parts that are not ended
+    open_parts: HashMap<(U1, U1), Vec<ScanFail>>,
+    fails: Vec<ScanFail>,
+}
+
+const TABLE_HEADER: [&str; 18] = [
+    "PART_ID", "X_COORD", "Y_COORD", "HEAD_NUM", "SITE_NUM", "TEST_NUM", "PAT_FILE", "PAT_LBL",
+    "CYCLE", "PMR_INDX", "PIN_NAM", "CHN_NUM", "CHN_NAM", "SSR_NAM", "BIT_POS", "CELL_NAM",
+    "EXPECTED", "CAPTURED",
+];
+
+impl ScanDiagnosisBuilder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        ScanDiagnosisBuilder::default()
+    }
+
+    /// collect the scan records, pin records and STR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::PMR(_) => self.pin_map.feed(rec),
+            StdfRecord::PSR(psr_rec) => {
+                let text = |v: &KxCn, i: usize| v.get(i).cloned().unwrap_or_default();
+                let patterns = self.patterns.entry(psr_rec.psr_indx).or_default();
+                for (i, &pat_bgn) in psr_rec.pat_bgn.iter().enumerate() {
+                    patterns.push((
+                        pat_bgn,
+                        psr_rec.pat_end.get(i).copied().unwrap_or(U8::MAX),
+                        text(&psr_rec.pat_file, i),
+                        text(&psr_rec.pat_lbl, i),
+                    ));
+                }
+            }
+            StdfRecord::NMR(nmr_rec) => {
+                for (&indx, name) in nmr_rec.pmr_indx.iter().zip(nmr_rec.atpg_nam.iter()) {
+                    self.atpg_names.insert(indx, name.clone());
+                }
+            }
+            StdfRecord::CNR(cnr_rec) => {
+                self.cell_names
+                    .insert((cnr_rec.chn_num, cnr_rec.bit_pos), cnr_rec.cell_nam.clone());
+            }
+            StdfRecord::SSR(ssr_rec) => {
+                for &cdr_indx in ssr_rec.chn_list.iter() {
+                    self.scan_structs
+                        .entry(cdr_indx)
+                        .or_insert_with(|| ssr_rec.ssr_nam.clone());
+                }
+            }
+            StdfRecord::CDR(cdr_rec) => {
+                let chain = self
+                    .chains
+                    .entry(cdr_rec.cdr_indx)
+                    .or_insert_with(|| (cdr_rec.chn_nam.clone(), vec![]));
+                chain.1.extend_from_slice(&cdr_rec.cell_lst);
+            }
+            StdfRecord::STR(str_rec) => {
+                if let Some(str_fails) = self.str_decoder.feed(str_rec) {
+                    let fails: Vec<ScanFail> = str_fails
+                        .fails
+                        .iter()
+                        .map(|f| self.resolve(str_rec, f))
+                        .collect();
+                    match self
+                        .open_parts
+                        .get_mut(&(str_rec.head_num, str_rec.site_num))
+                    {
+                        Some(part_fails) => part_fails.extend(fails),
+                        None => self.fails.extend(fails),
+                    }
+                }
+            }
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts
+                    .insert((pir_rec.head_num, pir_rec.site_num), vec![]);
+            }
+            StdfRecord::PRR(prr_rec) => {
+                if let Some(part_fails) = self
+                    .open_parts
+                    .remove(&(prr_rec.head_num, prr_rec.site_num))
+                {
+                    self.fails.extend(part_fails.into_iter().map(|f| ScanFail {
+                        part_id: prr_rec.part_id.clone(),
+                        x_coord: prr_rec.x_coord,
+                        y_coord: prr_rec.y_coord,
+                        ..f
+                    }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// return the scan fails, including the fails
+    /// of the parts that are not ended
+    pub fn finish(mut self) -> ScanDiagnosis {
+        let open_parts: BTreeMap<(U1, U1), Vec<ScanFail>> = self.open_parts.into_iter().collect();
+        for (_, part_fails) in open_parts {
+            self.fails.extend(part_fails);
+        }
+        ScanDiagnosis { fails: self.fails }
+    }
+
+    fn resolve(&self, str_rec: &STR, fail: &StrFail) -> ScanFail {
+        let pattern = fail.cycle.and_then(|cycle| {
+            self.patterns
+                .get(&str_rec.psr_ref)?
+                .iter()
+                .find(|p| p.0 <= cycle && cycle <= p.1)
+        });
+        let pin_nam = fail.pmr_indx.and_then(|indx| {
+            self.atpg_names.get(&indx).cloned().or_else(|| {
+                self.pin_map
+                    .get_pin(str_rec.head_num, str_rec.site_num, indx)
+                    .map(|p| p.log_nam.clone())
+            })
+        });
+        let cdr_indx = fail.chain.and_then(|c| U2::try_from(c).ok());
+        let chain = cdr_indx.and_then(|c| self.chains.get(&c));
+        let cell_nam = match (cdr_indx, fail.bit_pos) {
+            (Some(chn), Some(pos)) => U4::try_from(pos).ok().and_then(|pos| {
+                self.cell_names
+                    .get(&(chn, pos))
+                    .or_else(|| chain.and_then(|c| c.1.get(pos as usize)))
+                    .cloned()
+            }),
+            _ => None,
+        };
+        ScanFail {
+            part_id: String::new(),
+            x_coord: -32768,
+            y_coord: -32768,
+            head_num: str_rec.head_num,
+            site_num: str_rec.site_num,
+            test_num: str_rec.test_num,
+            pat_file: pattern.map(|p| p.2.clone()),
+            pat_lbl: pattern.map(|p| p.3.clone()),
+            cycle: fail.cycle,
+            pmr_indx: fail.pmr_indx,
+            pin_nam,
+            chn_num: fail.chain,
+            chn_nam: chain.map(|c| c.0.clone()),
+            ssr_nam: cdr_indx.and_then(|c| self.scan_structs.get(&c).cloned()),
+            bit_pos: fail.bit_pos,
+            cell_nam,
+            expected: fail.expected,
+            captured: fail.captured,
+        }
+    }
+}
+
+impl ScanDiagnosis {
+    /// export the fails as tab separated text with
+    /// a header line, absent values are empty
+    pub fn to_table(&self) -> String {
+        let mut table = TABLE_HEADER.join("\t");
+        table.push('\n');
+        for fail in self.fails.iter() {
+            let row: Vec<String> = fail_values(fail)
+                .into_iter()
+                .map(|v| match v {
+                    Value::Text(s) => s.replace(['\t', '\n', '\r'], " "),
+                    Value::Number(n) => n,
+                    Value::Null => String::new(),
+                })
+                .collect();
+            table.push_str(&row.join("\t"));
+            table.push('\n');
+        }
+        table
+    }
+
+    /// export the fails as a JSON array of objects,
+    /// keys are the same as the table header,
+    /// absent values are `null`
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (i, fail) in self.fails.iter().enumerate() {
+            json.push_str(if i == 0 { "\n  {" } else { ",\n  {" });
+            for (j, (key, value)) in TABLE_HEADER.iter().zip(fail_values(fail)).enumerate() {
+                if j != 0 {
+                    json.push_str(", ");
+                }
+                let _ = match value {
+                    Value::Text(s) => write!(json, "\"{}\": \"{}\"", key, escape_json(&s)),
+                    Value::Number(n) => write!(json, "\"{}\": {}", key, n),
+                    Value::Null => write!(json, "\"{}\": null", key),
+                };
+            }
+            json.push('}');
+        }
+        json.push_str(if self.fails.is_empty() { "]" } else { "\n]" });
+        json
+    }
+}
+
+enum Value {
+    Text(String),
+    Number(String),
+    Null,
+}
+
+fn fail_values(fail: &ScanFail) -> Vec<Value> {
+    fn text(v: &Option<Cn>) -> Value {
+        v.clone().map_or(Value::Null, Value::Text)
+    }
+    fn number<T: ToString>(v: Option<T>) -> Value {
+        v.map_or(Value::Null, |n| Value::Number(n.to_string()))
+    }
+    // -32768 indicates the coordinate is invalid
+    let coord = |c: I2| number(if c == -32768 { None } else { Some(c) });
+    vec![
+        Value::Text(fail.part_id.clone()),
+        coord(fail.x_coord),
+        coord(fail.y_coord),
+        number(Some(fail.head_num)),
+        number(Some(fail.site_num)),
+        number(Some(fail.test_num)),
+        text(&fail.pat_file),
+        text(&fail.pat_lbl),
+        number(fail.cycle),
+        number(fail.pmr_indx),
+        text(&fail.pin_nam),
+        number(fail.chn_num),
+        text(&fail.chn_nam),
+        text(&fail.ssr_nam),
+        number(fail.bit_pos),
+        text(&fail.cell_nam),
+        number(fail.expected),
+        number(fail.captured),
+    ]
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
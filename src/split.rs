@@ -0,0 +1,432 @@
+//
+// split.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::merge::HEADER_TYPES;
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_types::*;
+use crate::summary::{PartCount, SummaryBuilder};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// How the records are distributed to the outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitBy {
+    /// one output per wafer id of WIR,
+    /// parts outside any wafer go to the output
+    /// of an empty wafer id
+    #[default]
+    Wafer,
+    /// one output per head/site
+    Site,
+    /// one output every N parts, in the order of PIR
+    PartCount(usize),
+}
+
+/// Identifier of an output
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SplitKey {
+    Wafer(Cn),
+    /// (head, site)
+    Site(U1, U1),
+    /// index of the chunk, starts from 0
+    Chunk(usize),
+}
+
+/// An output of `split_stream`
+#[derive(Debug)]
+pub struct SplitOutput<W> {
+    pub key: SplitKey,
+    /// count of PRRs in the output
+    pub part_count: u64,
+    /// the inner stream of the finished writer
+    pub output: W,
+}
+
+struct Output<W: Write> {
+    key: SplitKey,
+    writer: StdfWriter<W>,
+    summary: SummaryBuilder,
+    // part counts of the wafer opened on every head
+    open_wafers: HashMap<U1, PartCount>,
+    part_count: u64,
+}
+
+struct Splitter<W: Write, F> {
+    split_by: SplitBy,
+    create_writer: F,
+    headers: Vec<StdfRecord>,
+    // records before the first output is created
+    preamble: Vec<StdfRecord>,
+    // HBR, SBR and TSR of the source, for the names
+    names: Vec<StdfRecord>,
+    mrr: Option<MRR>,
+    outputs: Vec<Output<W>>,
+    output_index: HashMap<SplitKey, usize>,
+    current: usize,
+    // WIR and part counts of the wafer opened on every head
+    open_wafers: HashMap<U1, (WIR, PartCount)>,
+    open_parts: HashMap<(U1, U1), usize>,
+    pir_count: usize,
+}
+
+/// Split the records from `reader` into several STDF files,
+/// `create_writer` is called for creating the writer
+/// of a new output.
+///
+/// Every output is a complete STDF file:
+///  - the file-level header records, i.e. the records
+///    in `merge::HEADER_TYPES`, are copied to every output
+///  - records of a part (PIR, PTR, MPR, FTR, STR and PRR)
+///    are copied to the output of the part
+///  - WIR is copied to every output that contains
+///    parts of the wafer, WRR part counts are recomputed
+///    from the parts in the output
+///  - other records are copied to the output of the
+///    latest part, or to all outputs if split by site;
+///    records before the first part are copied to every output
+///  - TSR, HBR, SBR and PCR are recomputed
+///    from the parts in the output
+///  - MRR of the source is copied to every output
+///
+/// Reading stops at the first error.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{split::*, stdf_file::*, CompressType};
+///
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     let order = reader.get_byte_order();
+///     let outputs = split_stream(&mut reader, SplitBy::Site, |_key| {
+///         StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order)
+///     });
+///     for out in outputs.unwrap() {
+///         println!("{:?}: {} parts, {} bytes", out.key, out.part_count, out.output.len());
+///     }
+/// }
+/// ```
+pub fn split_stream<R, W, F>(
+    reader: &mut StdfReader<R>,
+    split_by: SplitBy,
+    create_writer: F,
+) -> Result<Vec<SplitOutput<W>>, StdfError>
+where
+    R: BufRead + Seek,
+    W: Write,
+    F: FnMut(&SplitKey) -> Result<StdfWriter<W>, StdfError>,
+{
+    let mut splitter = Splitter {
+        split_by,
+        create_writer,
+        headers: vec![],
+        preamble: vec![],
+        names: vec![],
+        mrr: None,
+        outputs: vec![],
+        output_index: HashMap::new(),
+        current: 0,
+        open_wafers: HashMap::new(),
+        open_parts: HashMap::new(),
+        pir_count: 0,
+    };
+    for raw_element in reader.get_rawdata_iter() {
+        splitter.feed(&raw_element?)?;
+    }
+    splitter.finish()
+}
+
+/// Split the STDF file at `src` into files in `dst_dir`,
+/// byte order of the source file is kept.
+///
+/// The outputs are uncompressed and named after the source file
+/// and the `SplitKey`, e.g. `lot1_W01.stdf`, `lot1_H1_S2.stdf`
+/// or `lot1_part0.stdf`, the paths are returned in the
+/// order of creation. If the name is already used by another
+/// output, e.g. wafer ids `W/1` and `W_1`, a number is appended
+/// to the name, e.g. `lot1_W_1_2.stdf`.
+pub fn split_file<P, Q>(src: P, dst_dir: Q, split_by: SplitBy) -> Result<Vec<PathBuf>, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let src = src.as_ref();
+    // strip all the extensions, e.g. `.stdf.gz`
+    let file_name = src
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let base_name = file_name.split('.').next().unwrap_or_default().to_string();
+
+    let mut reader = StdfReader::new(src)?;
+    let order = reader.get_byte_order();
+    let mut paths = vec![];
+    // lowercase names, in case the file system is case-insensitive
+    let mut used_names = HashSet::new();
+    split_stream(
+        &mut reader,
+        split_by,
+        |key| -> Result<StdfWriter<BufWriter<File>>, StdfError> {
+            let stem = format!("{}_{}", base_name, key.file_suffix());
+            let mut name = format!("{}.stdf", stem);
+            let mut n = 1;
+            while !used_names.insert(name.to_lowercase()) {
+                n += 1;
+                name = format!("{}_{}.stdf", stem, n);
+            }
+            let path = dst_dir.as_ref().join(name);
+            let writer = StdfWriter::new(&path, order)?;
+            paths.push(path);
+            Ok(writer)
+        },
+    )?;
+    Ok(paths)
+}
+
+impl SplitKey {
+    /// a string of the key that can be used in file names,
+    /// characters other than letters, digits, `-` and `_`
+    /// in the wafer id are replaced by `_`
+    pub fn file_suffix(&self) -> String {
+        match self {
+            SplitKey::Wafer(wafer_id) if wafer_id.is_empty() => "nowafer".to_string(),
+            SplitKey::Wafer(wafer_id) => wafer_id
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect(),
+            SplitKey::Site(head_num, site_num) => format!("H{}_S{}", head_num, site_num),
+            SplitKey::Chunk(index) => format!("part{}", index),
+        }
+    }
+}
+
+impl<W, F> Splitter<W, F>
+where
+    W: Write,
+    F: FnMut(&SplitKey) -> Result<StdfWriter<W>, StdfError>,
+{
+    fn feed(&mut self, raw_element: &RawDataElement) -> Result<(), StdfError> {
+        let rec = StdfRecord::from(raw_element);
+        match &rec {
+            _ if rec.is_type(HEADER_TYPES) => {
+                // a late header record goes to the existing outputs as well
+                for out in self.outputs.iter_mut() {
+                    out.writer.write_record(&rec)?;
+                }
+                self.headers.push(rec);
+            }
+            StdfRecord::HBR(_) | StdfRecord::SBR(_) | StdfRecord::TSR(_) => self.names.push(rec),
+            StdfRecord::PCR(_) => {}
+            StdfRecord::MRR(mrr_rec) => {
+                self.mrr.get_or_insert_with(|| mrr_rec.clone());
+            }
+            StdfRecord::WIR(wir_rec) => {
+                self.open_wafers
+                    .insert(wir_rec.head_num, (wir_rec.clone(), PartCount::default()));
+                if self.split_by == SplitBy::Wafer {
+                    let index = self.get_output(SplitKey::Wafer(wir_rec.wafer_id.clone()))?;
+                    self.open_wafer_in(index, wir_rec.head_num)?;
+                    self.current = index;
+                }
+            }
+            StdfRecord::WRR(wrr_rec) => self.close_wafer(wrr_rec)?,
+            StdfRecord::PIR(pir_rec) => {
+                let key = self.part_key(pir_rec.head_num, pir_rec.site_num);
+                self.pir_count += 1;
+                let index = self.get_output(key)?;
+                self.open_parts
+                    .insert((pir_rec.head_num, pir_rec.site_num), index);
+                self.write_part_record(index, pir_rec.head_num, raw_element, &rec)?;
+            }
+            StdfRecord::PTR(PTR {
+                head_num, site_num, ..
+            })
+            | StdfRecord::MPR(MPR {
+                head_num, site_num, ..
+            })
+            | StdfRecord::FTR(FTR {
+                head_num, site_num, ..
+            })
+            | StdfRecord::STR(STR {
+                head_num, site_num, ..
+            }) => {
+                let index = self.part_output(*head_num, *site_num)?;
+                self.write_part_record(index, *head_num, raw_element, &rec)?;
+            }
+            StdfRecord::PRR(prr_rec) => {
+                let index = self.part_output(prr_rec.head_num, prr_rec.site_num)?;
+                self.open_parts
+                    .remove(&(prr_rec.head_num, prr_rec.site_num));
+                self.write_part_record(index, prr_rec.head_num, raw_element, &rec)?;
+                let out = &mut self.outputs[index];
+                out.part_count += 1;
+                if let Some(cnt) = out.open_wafers.get_mut(&prr_rec.head_num) {
+                    cnt.add_prr(prr_rec);
+                }
+                if let Some((_, cnt)) = self.open_wafers.get_mut(&prr_rec.head_num) {
+                    cnt.add_prr(prr_rec);
+                }
+            }
+            _ if self.outputs.is_empty() => self.preamble.push(rec),
+            _ if self.split_by == SplitBy::Site => {
+                for out in self.outputs.iter_mut() {
+                    out.writer.write_raw_data(raw_element)?;
+                }
+            }
+            _ => self.outputs[self.current]
+                .writer
+                .write_raw_data(raw_element)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<SplitOutput<W>>, StdfError> {
+        let mrr = self.mrr.unwrap_or_default();
+        let mut split_outputs = Vec::with_capacity(self.outputs.len());
+        for mut out in self.outputs {
+            for rec in self.names.iter() {
+                out.summary.feed(rec);
+            }
+            let mut rec_list = vec![];
+            rec_list.extend(out.summary.tsr_records().into_iter().map(StdfRecord::TSR));
+            rec_list.extend(out.summary.hbr_records().into_iter().map(StdfRecord::HBR));
+            rec_list.extend(out.summary.sbr_records().into_iter().map(StdfRecord::SBR));
+            rec_list.extend(out.summary.pcr_records().into_iter().map(StdfRecord::PCR));
+            rec_list.push(StdfRecord::MRR(mrr.clone()));
+            for rec in rec_list.iter() {
+                out.writer.write_record(rec)?;
+            }
+            split_outputs.push(SplitOutput {
+                key: out.key,
+                part_count: out.part_count,
+                output: out.writer.finish()?,
+            });
+        }
+        Ok(split_outputs)
+    }
+
+    /// the key of a new part on the head/site
+    fn part_key(&self, head_num: U1, site_num: U1) -> SplitKey {
+        match self.split_by {
+            SplitBy::Wafer => SplitKey::Wafer(
+                self.open_wafers
+                    .get(&head_num)
+                    .map(|(wir, _)| wir.wafer_id.clone())
+                    .unwrap_or_default(),
+            ),
+            SplitBy::Site => SplitKey::Site(head_num, site_num),
+            SplitBy::PartCount(n) => SplitKey::Chunk(self.pir_count / n.max(1)),
+        }
+    }
+
+    /// the output of the part that is being tested on
+    /// the head/site, the record is regarded as the
+    /// beginning of a new part if PIR is missing
+    fn part_output(&mut self, head_num: U1, site_num: U1) -> Result<usize, StdfError> {
+        match self.open_parts.get(&(head_num, site_num)) {
+            Some(&index) => Ok(index),
+            None => {
+                let index = self.get_output(self.part_key(head_num, site_num))?;
+                self.open_parts.insert((head_num, site_num), index);
+                Ok(index)
+            }
+        }
+    }
+
+    fn write_part_record(
+        &mut self,
+        index: usize,
+        head_num: U1,
+        raw_element: &RawDataElement,
+        rec: &StdfRecord,
+    ) -> Result<(), StdfError> {
+        self.open_wafer_in(index, head_num)?;
+        let out = &mut self.outputs[index];
+        out.writer.write_raw_data(raw_element)?;
+        out.summary.feed(rec);
+        self.current = index;
+        Ok(())
+    }
+
+    /// return the index of the output of `key`,
+    /// the output is created if not exist
+    fn get_output(&mut self, key: SplitKey) -> Result<usize, StdfError> {
+        if let Some(&index) = self.output_index.get(&key) {
+            return Ok(index);
+        }
+        let mut writer = (self.create_writer)(&key)?;
+        for rec in self.headers.iter().chain(self.preamble.iter()) {
+            writer.write_record(rec)?;
+        }
+        self.outputs.push(Output {
+            key: key.clone(),
+            writer,
+            summary: SummaryBuilder::new(),
+            open_wafers: HashMap::new(),
+            part_count: 0,
+        });
+        let index = self.outputs.len() - 1;
+        self.output_index.insert(key, index);
+        Ok(index)
+    }
+
+    /// write the WIR of the head to the output,
+    /// if the wafer is not opened in it yet
+    fn open_wafer_in(&mut self, index: usize, head_num: U1) -> Result<(), StdfError> {
+        let out = &mut self.outputs[index];
+        if let Some((wir, _)) = self.open_wafers.get(&head_num) {
+            if !out.open_wafers.contains_key(&head_num) {
+                out.writer.write_record(&StdfRecord::WIR(wir.clone()))?;
+                out.open_wafers.insert(head_num, PartCount::default());
+            }
+        }
+        Ok(())
+    }
+
+    /// write WRR to every output that opened the wafer
+    fn close_wafer(&mut self, wrr: &WRR) -> Result<(), StdfError> {
+        let total = self.open_wafers.remove(&wrr.head_num).map(|(_, cnt)| cnt);
+        for out in self.outputs.iter_mut() {
+            if let Some(cnt) = out.open_wafers.remove(&wrr.head_num) {
+                let mut wrr = wrr.clone();
+                // 4294967295 indicates the count is missing,
+                // keep it as is
+                if wrr.part_cnt != 4294967295 {
+                    wrr.part_cnt = cnt.part_cnt;
+                }
+                if wrr.rtst_cnt != 4294967295 {
+                    wrr.rtst_cnt = cnt.rtst_cnt;
+                }
+                if wrr.abrt_cnt != 4294967295 {
+                    wrr.abrt_cnt = cnt.abrt_cnt;
+                }
+                if wrr.good_cnt != 4294967295 {
+                    wrr.good_cnt = cnt.good_cnt;
+                }
+                // functional count cannot be recomputed
+                // if the wafer is split into several outputs
+                if total != Some(cnt) {
+                    wrr.func_cnt = 4294967295;
+                }
+                out.writer.write_record(&StdfRecord::WRR(wrr))?;
+            }
+        }
+        Ok(())
+    }
+}
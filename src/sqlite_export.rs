@@ -0,0 +1,389 @@
+//
+// sqlite_export.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_types::*;
+use crate::table_rows::{RowNormalizer, TableRow};
+use rusqlite::{params, Connection};
+use std::io::{BufRead, Seek};
+use std::path::Path;
+
+/// SQL statements that create the tables and indices,
+/// existing tables are kept, so that several files can
+/// be loaded into the same database, distinguished by
+/// `file_id`.
+///
+/// Optional fields with their missing values,
+/// e.g. `X_COORD` -32768, are NULL.
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS file_info (
+    file_id INTEGER PRIMARY KEY,
+    file_name TEXT,
+    lot_id TEXT,
+    sblot_id TEXT,
+    part_typ TEXT,
+    job_nam TEXT,
+    job_rev TEXT,
+    node_nam TEXT,
+    tstr_typ TEXT,
+    stat_num INTEGER,
+    test_cod TEXT,
+    mode_cod TEXT,
+    rtst_cod TEXT,
+    facil_id TEXT,
+    floor_id TEXT,
+    oper_nam TEXT,
+    setup_t INTEGER,
+    start_t INTEGER,
+    finish_t INTEGER,
+    usr_desc TEXT,
+    exc_desc TEXT
+);
+CREATE TABLE IF NOT EXISTS parts (
+    file_id INTEGER NOT NULL,
+    part_index INTEGER NOT NULL,
+    head_num INTEGER,
+    site_num INTEGER,
+    part_id TEXT,
+    wafer_id TEXT,
+    x_coord INTEGER,
+    y_coord INTEGER,
+    hard_bin INTEGER,
+    soft_bin INTEGER,
+    num_test INTEGER,
+    test_t INTEGER,
+    passed INTEGER,
+    PRIMARY KEY (file_id, part_index)
+);
+CREATE TABLE IF NOT EXISTS tests (
+    file_id INTEGER NOT NULL,
+    test_num INTEGER NOT NULL,
+    test_typ TEXT NOT NULL,
+    test_txt TEXT,
+    units TEXT,
+    res_scal INTEGER,
+    lo_limit REAL,
+    hi_limit REAL,
+    PRIMARY KEY (file_id, test_num, test_typ)
+);
+CREATE TABLE IF NOT EXISTS results (
+    file_id INTEGER NOT NULL,
+    part_index INTEGER NOT NULL,
+    test_num INTEGER NOT NULL,
+    test_typ TEXT NOT NULL,
+    pmr_indx INTEGER,
+    result REAL,
+    passed INTEGER,
+    test_flg INTEGER
+);
+CREATE TABLE IF NOT EXISTS bins (
+    file_id INTEGER NOT NULL,
+    bin_typ TEXT NOT NULL,
+    head_num INTEGER,
+    site_num INTEGER,
+    bin_num INTEGER,
+    bin_cnt INTEGER,
+    bin_pf TEXT,
+    bin_nam TEXT
+);
+CREATE TABLE IF NOT EXISTS wafers (
+    file_id INTEGER NOT NULL,
+    head_num INTEGER,
+    wafer_id TEXT,
+    start_t INTEGER,
+    finish_t INTEGER,
+    part_cnt INTEGER,
+    rtst_cnt INTEGER,
+    abrt_cnt INTEGER,
+    good_cnt INTEGER,
+    func_cnt INTEGER
+);
+CREATE TABLE IF NOT EXISTS pins (
+    file_id INTEGER NOT NULL,
+    pmr_indx INTEGER NOT NULL,
+    head_num INTEGER,
+    site_num INTEGER,
+    chan_typ INTEGER,
+    chan_nam TEXT,
+    phy_nam TEXT,
+    log_nam TEXT
+);
+CREATE INDEX IF NOT EXISTS results_part ON results (file_id, part_index);
+CREATE INDEX IF NOT EXISTS results_test ON results (file_id, test_num);
+CREATE INDEX IF NOT EXISTS parts_bin ON parts (file_id, hard_bin, soft_bin);
+CREATE INDEX IF NOT EXISTS parts_id ON parts (file_id, part_id);
+CREATE INDEX IF NOT EXISTS pins_indx ON pins (file_id, pmr_indx);
+";
+
+/// Load STDF records into the tables of `SCHEMA`
+///
+/// Rows are normalized by `RowNormalizer`, see it for how
+/// parts are numbered, missing values are written as NULL.
+/// `tests` contains the name, units and limits of the first
+/// PTR/MPR/FTR of every test number.
+///
+/// Rows are inserted as the records are fed, it's recommended
+/// to load a file in a transaction for speed, as `export_sqlite` does.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{sqlite_export::*, PIR, PRR, PTR, StdfRecord};
+/// use rusqlite::Connection;
+///
+/// let conn = Connection::open_in_memory().unwrap();
+/// conn.execute_batch(SCHEMA).unwrap();
+/// let mut loader = SqliteLoader::new(&conn, "demo.stdf").unwrap();
+/// for rec in [
+///     StdfRecord::PIR(PIR::default()),
+///     StdfRecord::PTR(PTR {
+///         test_num: 100,
+///         result: 1.5,
+///         ..Default::default()
+///     }),
+///     StdfRecord::PRR(PRR::default()),
+/// ] {
+///     loader.feed(&rec).unwrap();
+/// }
+/// let file_id = loader.finish();
+/// let result: f64 = conn
+///     .query_row(
+///         "SELECT result FROM results WHERE file_id = ?1 AND test_num = 100",
+///         [file_id],
+///         |row| row.get(0),
+///     )
+///     .unwrap();
+/// assert_eq!(result, 1.5);
+/// ```
+pub struct SqliteLoader<'c> {
+    conn: &'c Connection,
+    file_id: i64,
+    normalizer: RowNormalizer,
+    rows: Vec<TableRow>,
+}
+
+impl<'c> SqliteLoader<'c> {
+    /// insert a row of `file_info` for the file, the
+    /// tables of `SCHEMA` must have been created
+    pub fn new(conn: &'c Connection, file_name: &str) -> Result<Self, StdfError> {
+        conn.execute("INSERT INTO file_info (file_name) VALUES (?1)", [file_name])?;
+        Ok(SqliteLoader {
+            conn,
+            file_id: conn.last_insert_rowid(),
+            normalizer: RowNormalizer::new(),
+            rows: Vec::new(),
+        })
+    }
+
+    /// collect MIR, MRR, PMR, WIR, WRR, PIR, PRR, PTR,
+    /// MPR, FTR, HBR and SBR, other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) -> Result<(), StdfError> {
+        match rec {
+            StdfRecord::MIR(mir_rec) => {
+                self.conn
+                    .prepare_cached(
+                        "UPDATE file_info SET lot_id = ?2, sblot_id = ?3, part_typ = ?4,
+                        job_nam = ?5, job_rev = ?6, node_nam = ?7, tstr_typ = ?8,
+                        stat_num = ?9, test_cod = ?10, mode_cod = ?11, rtst_cod = ?12,
+                        facil_id = ?13, floor_id = ?14, oper_nam = ?15, setup_t = ?16,
+                        start_t = ?17 WHERE file_id = ?1",
+                    )?
+                    .execute(params![
+                        self.file_id,
+                        mir_rec.lot_id,
+                        mir_rec.sblot_id,
+                        mir_rec.part_typ,
+                        mir_rec.job_nam,
+                        mir_rec.job_rev,
+                        mir_rec.node_nam,
+                        mir_rec.tstr_typ,
+                        mir_rec.stat_num,
+                        mir_rec.test_cod,
+                        mir_rec.mode_cod.to_string(),
+                        mir_rec.rtst_cod.to_string(),
+                        mir_rec.facil_id,
+                        mir_rec.floor_id,
+                        mir_rec.oper_nam,
+                        mir_rec.setup_t,
+                        mir_rec.start_t,
+                    ])?;
+            }
+            StdfRecord::MRR(mrr_rec) => {
+                self.conn
+                    .prepare_cached(
+                        "UPDATE file_info SET finish_t = ?2, usr_desc = ?3,
+                        exc_desc = ?4 WHERE file_id = ?1",
+                    )?
+                    .execute(params![
+                        self.file_id,
+                        mrr_rec.finish_t,
+                        mrr_rec.usr_desc,
+                        mrr_rec.exc_desc,
+                    ])?;
+            }
+            StdfRecord::PMR(pmr_rec) => {
+                self.conn
+                    .prepare_cached("INSERT INTO pins VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?
+                    .execute(params![
+                        self.file_id,
+                        pmr_rec.pmr_indx,
+                        pmr_rec.head_num,
+                        pmr_rec.site_num,
+                        pmr_rec.chan_typ,
+                        pmr_rec.chan_nam,
+                        pmr_rec.phy_nam,
+                        pmr_rec.log_nam,
+                    ])?;
+            }
+            _ => {}
+        }
+        let mut rows = std::mem::take(&mut self.rows);
+        self.normalizer.feed(rec, &mut rows);
+        let result = rows.drain(..).try_for_each(|row| self.add_row(row));
+        self.rows = rows;
+        result
+    }
+
+    /// return the `file_id` of the loaded file
+    #[inline(always)]
+    pub fn finish(self) -> i64 {
+        self.file_id
+    }
+
+    fn add_row(&mut self, row: TableRow) -> Result<(), StdfError> {
+        match row {
+            TableRow::Result(r) => self
+                .conn
+                .prepare_cached("INSERT INTO results VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?
+                .execute(params![
+                    self.file_id,
+                    r.part_index as i64,
+                    r.test_num,
+                    r.test_typ.to_string(),
+                    r.pmr_indx,
+                    r.result,
+                    r.passed,
+                    r.test_flg,
+                ])?,
+            TableRow::Part(r) => self
+                .conn
+                .prepare_cached(
+                    "INSERT INTO parts VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                )?
+                .execute(params![
+                    self.file_id,
+                    r.part_index as i64,
+                    r.head_num,
+                    r.site_num,
+                    r.part_id,
+                    r.wafer_id,
+                    r.x_coord,
+                    r.y_coord,
+                    r.hard_bin,
+                    r.soft_bin,
+                    r.num_test,
+                    r.test_t,
+                    r.passed,
+                ])?,
+            TableRow::Test(r) => self
+                .conn
+                .prepare_cached("INSERT INTO tests VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?
+                .execute(params![
+                    self.file_id,
+                    r.test_num,
+                    r.test_typ.to_string(),
+                    r.test_txt,
+                    r.units,
+                    r.res_scal,
+                    r.lo_limit,
+                    r.hi_limit,
+                ])?,
+            TableRow::Bin(r) => self
+                .conn
+                .prepare_cached("INSERT INTO bins VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?
+                .execute(params![
+                    self.file_id,
+                    r.bin_typ.to_string(),
+                    r.head_num,
+                    r.site_num,
+                    r.bin_num,
+                    r.bin_cnt,
+                    r.bin_pf.map(|pf| pf.to_string()),
+                    r.bin_nam,
+                ])?,
+            TableRow::Wafer(r) => self
+                .conn
+                .prepare_cached(
+                    "INSERT INTO wafers VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                )?
+                .execute(params![
+                    self.file_id,
+                    r.head_num,
+                    r.wafer_id,
+                    r.start_t,
+                    r.finish_t,
+                    r.part_cnt,
+                    r.rtst_cnt,
+                    r.abrt_cnt,
+                    r.good_cnt,
+                    r.func_cnt,
+                ])?,
+        };
+        Ok(())
+    }
+}
+
+/// Load all the records of `reader` into `conn` in
+/// a transaction, the tables are created if not exist,
+/// return the `file_id` of the file.
+///
+/// Nothing is committed if reading fails.
+pub fn export_sqlite_stream<R: BufRead + Seek>(
+    reader: &mut StdfReader<R>,
+    conn: &mut Connection,
+    file_name: &str,
+) -> Result<i64, StdfError> {
+    conn.execute_batch(SCHEMA)?;
+    let tx = conn.transaction()?;
+    let mut loader = SqliteLoader::new(&tx, file_name)?;
+    for rec in reader.get_record_iter() {
+        loader.feed(&rec?)?;
+    }
+    let file_id = loader.finish();
+    tx.commit()?;
+    Ok(file_id)
+}
+
+/// Load the STDF file at `src` into the SQLite
+/// database at `db_path`, which is created if not exists,
+/// return the `file_id` of the file.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::sqlite_export::export_sqlite;
+///
+/// if let Ok(file_id) = export_sqlite("input.stdf", "datalogs.db") {
+///     // SELECT hard_bin, COUNT(*) FROM parts WHERE file_id = ? GROUP BY hard_bin
+///     println!("loaded as file {}", file_id);
+/// }
+/// ```
+pub fn export_sqlite<P, Q>(src: P, db_path: Q) -> Result<i64, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(&src)?;
+    let mut conn = Connection::open(db_path)?;
+    let file_name = src.as_ref().to_string_lossy();
+    export_sqlite_stream(&mut reader, &mut conn, &file_name)
+}
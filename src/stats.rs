@@ -0,0 +1,681 @@
+//
+// stats.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_types::*;
+use smart_default::SmartDefault;
+use std::collections::{BTreeMap, HashMap};
+
+/// Effective limits and units of a PTR or MPR,
+/// after inheriting the default values from the
+/// first record of the same test number.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestLimits {
+    pub res_scal: I1,
+    pub llm_scal: I1,
+    pub hlm_scal: I1,
+    /// `None` if the test has no low limit
+    pub lo_limit: Option<R4>,
+    /// `None` if the test has no high limit
+    pub hi_limit: Option<R4>,
+    pub lo_spec: Option<R4>,
+    pub hi_spec: Option<R4>,
+    pub units: Cn,
+}
+
+/// Resolve the effective limits of PTR and MPR.
+///
+/// As the spec says, the first PTR/MPR of a test number
+/// provides the default values of the optional fields,
+/// later records may omit them, or mark them invalid
+/// by `opt_flag`, in both cases the defaults are used.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stats::LimitTracker, PTR};
+///
+/// let mut tracker = LimitTracker::new();
+/// let first = PTR {
+///     test_num: 1,
+///     opt_flag: Some([0b0000_1110]),
+///     res_scal: Some(3),
+///     llm_scal: Some(3),
+///     hlm_scal: Some(3),
+///     lo_limit: Some(0.1),
+///     hi_limit: Some(0.5),
+///     units: Some("A".to_string()),
+///     ..Default::default()
+/// };
+/// let second = PTR {
+///     test_num: 1,
+///     result: 0.2,
+///     ..Default::default()
+/// };
+/// tracker.resolve_ptr(&first);
+/// let limits = tracker.resolve_ptr(&second);
+/// assert_eq!(limits.lo_limit, Some(0.1));
+/// assert_eq!(limits.units, "A");
+/// ```
+#[derive(Debug, Default)]
+pub struct LimitTracker {
+    ptr_defaults: HashMap<U4, TestLimits>,
+    mpr_defaults: HashMap<U4, TestLimits>,
+}
+
+/// Options of the statistics
+#[derive(SmartDefault, Debug, Clone)]
+pub struct StatsOptions {
+    /// multiply results and limits by 10^scale,
+    /// e.g. 0.001 A with `res_scal` 3 becomes 1 mA
+    #[default = true]
+    pub apply_scaling: bool,
+    /// probabilities of the quantiles to estimate,
+    /// median is always estimated, probabilities
+    /// out of [0, 1] are clamped to the range
+    #[default(vec![0.25, 0.75])]
+    pub quantiles: Vec<R8>,
+}
+
+/// Statistics of a test, accumulated in one pass.
+///
+/// mean and standard deviation are computed by
+/// Welford's algorithm, median and quantiles are
+/// estimated by the P² algorithm, which keeps only
+/// 5 markers per quantile, the estimation is exact
+/// if there are no more than 5 results.
+#[derive(Debug, Clone)]
+pub struct TestStats {
+    pub test_num: U4,
+    /// 'P' for PTR, 'M' for MPR
+    pub test_typ: C1,
+    pub test_txt: Cn,
+    pub units: Cn,
+    /// low limit of the first result, scaled if required
+    pub lo_limit: Option<R8>,
+    /// high limit of the first result, scaled if required
+    pub hi_limit: Option<R8>,
+    /// count of the executed records
+    pub exec_cnt: u64,
+    /// count of the failed records
+    pub fail_cnt: u64,
+    /// count of the valid results
+    pub count: u64,
+    pub min: R8,
+    pub max: R8,
+    mean: R8,
+    m2: R8,
+    median: P2Quantile,
+    quantiles: Vec<P2Quantile>,
+}
+
+/// Per-test statistics builder, results
+/// of every head/site are also kept separately.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stats::StatsBuilder, stdf_file::*};
+///
+/// let mut builder = StatsBuilder::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         builder.feed(&rec);
+///     }
+/// }
+/// for (test_num, stats) in builder.get_test_stats() {
+///     println!("{} mean {:?} cpk {:?}", test_num, stats.mean(), stats.cpk());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct StatsBuilder {
+    options: StatsOptions,
+    limits: LimitTracker,
+    tests: BTreeMap<U4, TestStats>,
+    sites: BTreeMap<(U1, U1, U4), TestStats>,
+}
+
+/// P² quantile estimator (Jain & Chlamtac, 1985)
+#[derive(Debug, Clone)]
+struct P2Quantile {
+    p: R8,
+    count: usize,
+    heights: [R8; 5],
+    positions: [R8; 5],
+    desired: [R8; 5],
+    increments: [R8; 5],
+}
+
+impl LimitTracker {
+    pub fn new() -> Self {
+        LimitTracker::default()
+    }
+
+    /// return the effective limits of the PTR,
+    /// the first PTR of a test number becomes the default
+    pub fn resolve_ptr(&mut self, ptr_rec: &PTR) -> TestLimits {
+        let current = TestLimitsInRecord::new(
+            ptr_rec.opt_flag,
+            [ptr_rec.res_scal, ptr_rec.llm_scal, ptr_rec.hlm_scal],
+            [
+                ptr_rec.lo_limit,
+                ptr_rec.hi_limit,
+                ptr_rec.lo_spec,
+                ptr_rec.hi_spec,
+            ],
+            &ptr_rec.units,
+        );
+        resolve(
+            &mut self.ptr_defaults,
+            ptr_rec.test_num,
+            ptr_rec.opt_flag,
+            current,
+        )
+    }
+
+    /// return the effective limits of the MPR,
+    /// the first MPR of a test number becomes the default
+    pub fn resolve_mpr(&mut self, mpr_rec: &MPR) -> TestLimits {
+        let current = TestLimitsInRecord::new(
+            mpr_rec.opt_flag,
+            [mpr_rec.res_scal, mpr_rec.llm_scal, mpr_rec.hlm_scal],
+            [
+                mpr_rec.lo_limit,
+                mpr_rec.hi_limit,
+                mpr_rec.lo_spec,
+                mpr_rec.hi_spec,
+            ],
+            &mpr_rec.units,
+        );
+        resolve(
+            &mut self.mpr_defaults,
+            mpr_rec.test_num,
+            mpr_rec.opt_flag,
+            current,
+        )
+    }
+}
+
+impl TestLimitsInRecord {
+    /// limits as they are in the record
+    fn new(
+        opt_flag: Option<B1>,
+        scales: [Option<I1>; 3],
+        values: [Option<R4>; 4],
+        units: &Option<Cn>,
+    ) -> Self {
+        let flag = opt_flag.map_or(0, |f| f[0]);
+        let [res_scal, llm_scal, hlm_scal] = scales;
+        let [lo_limit, hi_limit, lo_spec, hi_spec] = values;
+        // bit 0: res_scal invalid
+        // bit 2, 3: no low, high spec limit
+        // bit 4, 5: lo_limit, hi_limit invalid, use default
+        // bit 6, 7: no low, high limit
+        TestLimitsInRecord {
+            res_scal: if flag & 0b0000_0001 == 0 {
+                res_scal
+            } else {
+                None
+            },
+            llm_scal: if flag & 0b0101_0000 == 0 {
+                llm_scal
+            } else {
+                None
+            },
+            hlm_scal: if flag & 0b1010_0000 == 0 {
+                hlm_scal
+            } else {
+                None
+            },
+            lo_limit: limit_state(flag, 0b0100_0000, 0b0001_0000, lo_limit),
+            hi_limit: limit_state(flag, 0b1000_0000, 0b0010_0000, hi_limit),
+            lo_spec: limit_state(flag, 0b0000_0100, 0, lo_spec),
+            hi_spec: limit_state(flag, 0b0000_1000, 0, hi_spec),
+            units: units.clone(),
+        }
+    }
+}
+
+impl TestLimits {
+    /// low limit multiplied by 10^llm_scal
+    #[inline(always)]
+    pub fn scaled_lo_limit(&self) -> Option<R8> {
+        self.lo_limit.map(|v| scale(v as R8, self.llm_scal))
+    }
+
+    /// high limit multiplied by 10^hlm_scal
+    #[inline(always)]
+    pub fn scaled_hi_limit(&self) -> Option<R8> {
+        self.hi_limit.map(|v| scale(v as R8, self.hlm_scal))
+    }
+
+    /// result multiplied by 10^res_scal
+    #[inline(always)]
+    pub fn scaled_result(&self, result: R4) -> R8 {
+        scale(result as R8, self.res_scal)
+    }
+}
+
+/// state of a limit field in a single record
+#[derive(Debug, Clone, Copy)]
+enum LimitState {
+    /// the test has no such limit
+    NoLimit,
+    /// absent or invalid, use the default
+    Inherit,
+    Value(R4),
+}
+
+#[derive(Debug, Clone)]
+struct TestLimitsInRecord {
+    res_scal: Option<I1>,
+    llm_scal: Option<I1>,
+    hlm_scal: Option<I1>,
+    lo_limit: LimitState,
+    hi_limit: LimitState,
+    lo_spec: LimitState,
+    hi_spec: LimitState,
+    units: Option<Cn>,
+}
+
+#[inline(always)]
+fn limit_state(flag: u8, no_limit_bit: u8, invalid_bit: u8, value: Option<R4>) -> LimitState {
+    if flag & no_limit_bit != 0 {
+        LimitState::NoLimit
+    } else if flag & invalid_bit != 0 {
+        LimitState::Inherit
+    } else {
+        match value {
+            Some(v) => LimitState::Value(v),
+            None => LimitState::Inherit,
+        }
+    }
+}
+
+#[inline(always)]
+fn inherit(state: LimitState, default: Option<R4>) -> Option<R4> {
+    match state {
+        LimitState::NoLimit => None,
+        LimitState::Inherit => default,
+        LimitState::Value(v) => Some(v),
+    }
+}
+
+fn resolve(
+    defaults: &mut HashMap<U4, TestLimits>,
+    test_num: U4,
+    opt_flag: Option<B1>,
+    current: TestLimitsInRecord,
+) -> TestLimits {
+    match defaults.get(&test_num) {
+        Some(default) => TestLimits {
+            res_scal: current.res_scal.unwrap_or(default.res_scal),
+            llm_scal: current.llm_scal.unwrap_or(default.llm_scal),
+            hlm_scal: current.hlm_scal.unwrap_or(default.hlm_scal),
+            lo_limit: inherit(current.lo_limit, default.lo_limit),
+            hi_limit: inherit(current.hi_limit, default.hi_limit),
+            lo_spec: inherit(current.lo_spec, default.lo_spec),
+            hi_spec: inherit(current.hi_spec, default.hi_spec),
+            units: current.units.unwrap_or_else(|| default.units.clone()),
+        },
+        None => {
+            // the first record without `opt_flag`
+            // has no limits at all
+            let first = TestLimits {
+                res_scal: current.res_scal.unwrap_or(0),
+                llm_scal: current.llm_scal.unwrap_or(0),
+                hlm_scal: current.hlm_scal.unwrap_or(0),
+                lo_limit: inherit(current.lo_limit, None),
+                hi_limit: inherit(current.hi_limit, None),
+                lo_spec: inherit(current.lo_spec, None),
+                hi_spec: inherit(current.hi_spec, None),
+                units: current.units.unwrap_or_default(),
+            };
+            if opt_flag.is_some() {
+                defaults.insert(test_num, first.clone());
+            }
+            first
+        }
+    }
+}
+
+#[inline(always)]
+fn scale(value: R8, exponent: I1) -> R8 {
+    if exponent == 0 {
+        value
+    } else {
+        value * 10f64.powi(exponent as i32)
+    }
+}
+
+impl StatsBuilder {
+    pub fn new() -> Self {
+        StatsBuilder::default()
+    }
+
+    pub fn with_options(options: StatsOptions) -> Self {
+        StatsBuilder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// accumulate the results of PTR and MPR,
+    /// other records are ignored.
+    ///
+    /// results of tests that are not executed, aborted,
+    /// timeout or marked invalid are not counted.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::PTR(ptr_rec) => {
+                let limits = self.limits.resolve_ptr(ptr_rec);
+                let results = [ptr_rec.result];
+                self.add_results(
+                    (ptr_rec.head_num, ptr_rec.site_num, ptr_rec.test_num),
+                    'P',
+                    &ptr_rec.test_txt,
+                    ptr_rec.test_flg[0],
+                    &limits,
+                    &results,
+                );
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                let limits = self.limits.resolve_mpr(mpr_rec);
+                self.add_results(
+                    (mpr_rec.head_num, mpr_rec.site_num, mpr_rec.test_num),
+                    'M',
+                    &mpr_rec.test_txt,
+                    mpr_rec.test_flg[0],
+                    &limits,
+                    &mpr_rec.rtn_rslt,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// statistics of all sites, by test number
+    #[inline(always)]
+    pub fn get_test_stats(&self) -> &BTreeMap<U4, TestStats> {
+        &self.tests
+    }
+
+    /// statistics of every (head, site, test number)
+    #[inline(always)]
+    pub fn get_site_stats(&self) -> &BTreeMap<(U1, U1, U4), TestStats> {
+        &self.sites
+    }
+
+    fn add_results(
+        &mut self,
+        key: (U1, U1, U4),
+        test_typ: C1,
+        test_txt: &str,
+        test_flg: u8,
+        limits: &TestLimits,
+        results: &[R4],
+    ) {
+        // bit 4: test not executed
+        if test_flg & 0b0001_0000 != 0 {
+            return;
+        }
+        let (lo_limit, hi_limit, scaled): (Option<R8>, Option<R8>, Vec<R8>) =
+            if self.options.apply_scaling {
+                (
+                    limits.scaled_lo_limit(),
+                    limits.scaled_hi_limit(),
+                    results.iter().map(|&r| limits.scaled_result(r)).collect(),
+                )
+            } else {
+                (
+                    limits.lo_limit.map(|v| v as R8),
+                    limits.hi_limit.map(|v| v as R8),
+                    results.iter().map(|&r| r as R8).collect(),
+                )
+            };
+        // bit 1, 3, 5: result is not valid, timeout or aborted
+        let valid = test_flg & 0b0010_1010 == 0;
+        // bit 7: test failed, only valid if bit 6 is 0
+        let failed = test_flg & 0b1100_0000 == 0b1000_0000;
+
+        let options = &self.options;
+        let new_stats = || {
+            TestStats::new(
+                key.2,
+                test_typ,
+                test_txt,
+                &limits.units,
+                lo_limit,
+                hi_limit,
+                &options.quantiles,
+            )
+        };
+        let overall = self.tests.entry(key.2).or_insert_with(new_stats);
+        overall.add_execution(failed, valid, &scaled);
+        let site = self.sites.entry(key).or_insert_with(new_stats);
+        site.add_execution(failed, valid, &scaled);
+    }
+}
+
+impl TestStats {
+    fn new(
+        test_num: U4,
+        test_typ: C1,
+        test_txt: &str,
+        units: &str,
+        lo_limit: Option<R8>,
+        hi_limit: Option<R8>,
+        quantiles: &[R8],
+    ) -> Self {
+        TestStats {
+            test_num,
+            test_typ,
+            test_txt: test_txt.to_string(),
+            units: units.to_string(),
+            lo_limit,
+            hi_limit,
+            exec_cnt: 0,
+            fail_cnt: 0,
+            count: 0,
+            min: R8::NAN,
+            max: R8::NAN,
+            mean: 0.0,
+            m2: 0.0,
+            median: P2Quantile::new(0.5),
+            quantiles: quantiles.iter().map(|&p| P2Quantile::new(p)).collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn add_execution(&mut self, failed: bool, valid: bool, results: &[R8]) {
+        self.exec_cnt += 1;
+        if failed {
+            self.fail_cnt += 1;
+        }
+        if valid {
+            results
+                .iter()
+                .filter(|r| r.is_finite())
+                .for_each(|&r| self.add_value(r));
+        }
+    }
+
+    /// add a value by Welford's algorithm
+    #[inline(always)]
+    fn add_value(&mut self, value: R8) {
+        self.count += 1;
+        if self.count == 1 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        let delta = value - self.mean;
+        self.mean += delta / self.count as R8;
+        self.m2 += delta * (value - self.mean);
+        self.median.add(value);
+        self.quantiles.iter_mut().for_each(|q| q.add(value));
+    }
+
+    pub fn mean(&self) -> Option<R8> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.mean)
+        }
+    }
+
+    /// sample standard deviation
+    pub fn stdev(&self) -> Option<R8> {
+        if self.count < 2 {
+            None
+        } else {
+            Some((self.m2 / (self.count - 1) as R8).sqrt())
+        }
+    }
+
+    pub fn median(&self) -> Option<R8> {
+        self.median.value()
+    }
+
+    /// estimated quantiles as (probability, value),
+    /// in the order of `StatsOptions.quantiles`
+    pub fn quantiles(&self) -> Vec<(R8, Option<R8>)> {
+        self.quantiles.iter().map(|q| (q.p, q.value())).collect()
+    }
+
+    /// (hi - lo) / 6σ, both limits are required
+    pub fn cp(&self) -> Option<R8> {
+        let sigma = self.valid_stdev()?;
+        match (self.lo_limit, self.hi_limit) {
+            (Some(lo), Some(hi)) => Some((hi - lo) / (6.0 * sigma)),
+            _ => None,
+        }
+    }
+
+    /// min(hi - mean, mean - lo) / 3σ,
+    /// one-sided if only one limit exists
+    pub fn cpk(&self) -> Option<R8> {
+        let sigma = self.valid_stdev()?;
+        let mean = self.mean;
+        let cpu = self.hi_limit.map(|hi| (hi - mean) / (3.0 * sigma));
+        let cpl = self.lo_limit.map(|lo| (mean - lo) / (3.0 * sigma));
+        match (cpl, cpu) {
+            (Some(l), Some(u)) => Some(l.min(u)),
+            (Some(l), None) => Some(l),
+            (None, Some(u)) => Some(u),
+            (None, None) => None,
+        }
+    }
+
+    /// ratio of the passed executions
+    pub fn yield_rate(&self) -> Option<R8> {
+        if self.exec_cnt == 0 {
+            None
+        } else {
+            Some((self.exec_cnt - self.fail_cnt) as R8 / self.exec_cnt as R8)
+        }
+    }
+
+    #[inline(always)]
+    fn valid_stdev(&self) -> Option<R8> {
+        self.stdev().filter(|&s| s > 0.0)
+    }
+}
+
+impl P2Quantile {
+    fn new(p: R8) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        P2Quantile {
+            p,
+            count: 0,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    fn add(&mut self, value: R8) {
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(|a, b| a.total_cmp(b));
+            }
+            return;
+        }
+        self.count += 1;
+
+        // find the cell of the value
+        let k = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (1..5).find(|&i| value < self.heights[i]).unwrap_or(4) - 1
+        };
+        self.positions[(k + 1)..].iter_mut().for_each(|n| *n += 1.0);
+        for (d, inc) in self.desired.iter_mut().zip(self.increments.iter()) {
+            *d += inc;
+        }
+
+        // adjust the heights of the middle markers
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let forward = self.positions[i + 1] - self.positions[i];
+            let backward = self.positions[i - 1] - self.positions[i];
+            if (d >= 1.0 && forward > 1.0) || (d <= -1.0 && backward < -1.0) {
+                let d = d.signum();
+                let h = self.parabolic(i, d);
+                self.heights[i] = if self.heights[i - 1] < h && h < self.heights[i + 1] {
+                    h
+                } else {
+                    self.linear(i, d)
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn parabolic(&self, i: usize, d: R8) -> R8 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    #[inline(always)]
+    fn linear(&self, i: usize, d: R8) -> R8 {
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        self.heights[i]
+            + d * (self.heights[j] - self.heights[i]) / (self.positions[j] - self.positions[i])
+    }
+
+    fn value(&self) -> Option<R8> {
+        match self.count {
+            0 => None,
+            1..=5 => {
+                // exact quantile with linear interpolation
+                let mut values = self.heights[..self.count].to_vec();
+                values.sort_by(|a, b| a.total_cmp(b));
+                let rank = self.p * (values.len() - 1) as R8;
+                let lower = rank.floor() as usize;
+                let upper = rank.ceil() as usize;
+                Some(values[lower] + (rank - lower as R8) * (values[upper] - values[lower]))
+            }
+            _ => Some(self.heights[2]),
+        }
+    }
+}
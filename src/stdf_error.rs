@@ -9,6 +9,14 @@
 // Copyright (c) 2022 noonchen
 //
 
+#[cfg(feature = "arrow")]
+use arrow::error::ArrowError;
+#[cfg(feature = "arrow")]
+use parquet::errors::ParquetError;
+#[cfg(feature = "sqlite")]
+use rusqlite::Error as SqliteError;
+#[cfg(feature = "jsonl")]
+use serde_json::Error as JsonError;
 use std::fmt;
 use std::io::{self, ErrorKind};
 #[cfg(feature = "zipfile")]
@@ -32,6 +40,13 @@ impl fmt::Display for StdfError {
             7 => "Invalid ATDF File",
             #[cfg(feature = "zipfile")]
             8 => "Zip related",
+            9 => "Invalid Record Data",
+            #[cfg(feature = "arrow")]
+            10 => "Arrow related",
+            #[cfg(feature = "sqlite")]
+            11 => "SQLite related",
+            #[cfg(feature = "jsonl")]
+            12 => "JSON related",
             _ => "Other error",
         };
         write!(f, "{}, {}", short_msg, self.msg)
@@ -68,3 +83,52 @@ impl From<ZipError> for StdfError {
         }
     }
 }
+
+#[cfg(feature = "arrow")]
+impl From<ArrowError> for StdfError {
+    fn from(error: ArrowError) -> Self {
+        match error {
+            ArrowError::IoError(msg, _) => StdfError { code: 3, msg },
+            _ => StdfError {
+                code: 10,
+                msg: error.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<ParquetError> for StdfError {
+    fn from(error: ParquetError) -> Self {
+        StdfError {
+            code: 10,
+            msg: error.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<SqliteError> for StdfError {
+    fn from(error: SqliteError) -> Self {
+        StdfError {
+            code: 11,
+            msg: error.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "jsonl")]
+impl From<JsonError> for StdfError {
+    fn from(error: JsonError) -> Self {
+        match error.io_error_kind() {
+            Some(_) => StdfError {
+                code: 3,
+                msg: error.to_string(),
+            },
+            None => StdfError {
+                code: 12,
+                msg: error.to_string(),
+            },
+        }
+    }
+}
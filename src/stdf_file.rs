@@ -3,7 +3,7 @@
 // Author: noonchen - chennoon233@foxmail.com
 // Created Date: October 3rd 2022
 // -----
-// Last Modified: Mon Nov 14 2022
+// Last Modified: Sun Oct 18 2026
 // Modified By: noonchen
 // -----
 // Copyright (c) 2022 noonchen
@@ -12,11 +12,11 @@
 use crate::stdf_error::StdfError;
 use crate::stdf_types::*;
 #[cfg(feature = "bzip")]
-use bzip2::bufread::BzDecoder;
+use bzip2::{bufread::BzDecoder, write::BzEncoder, Compression as BzCompression};
 #[cfg(feature = "gzip")]
-use flate2::bufread::GzDecoder;
-use std::io::{self, BufReader, SeekFrom}; // struct or enum
-use std::io::{BufRead, Read, Seek};
+use flate2::{bufread::GzDecoder, write::GzEncoder, Compression as GzCompression};
+use std::io::{self, BufReader, BufWriter, SeekFrom}; // struct or enum
+use std::io::{BufRead, Read, Seek, Write};
 use std::{fs, path::Path}; // trait
 #[cfg(feature = "zipfile")]
 use zip::{read::ZipFile, ZipArchive};
@@ -45,6 +45,15 @@ pub(crate) enum StdfStream<R> {
     Zip(ZipBundle<R>),
 }
 
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum StdfSink<W: Write> {
+    Binary(W),
+    #[cfg(feature = "gzip")]
+    Gz(GzEncoder<W>),
+    #[cfg(feature = "bzip")]
+    Bz(BzEncoder<W>),
+}
+
 /// STDF Reader
 ///
 /// This reader can process STDF datalogs of Version V4 and V4-2007
@@ -100,11 +109,54 @@ pub(crate) enum StdfStream<R> {
 /// ```
 pub struct StdfReader<R> {
     endianness: ByteOrder,
+    stdf_version: u8,
     stream: StdfStream<R>,
 }
 
+/// STDF Writer
+///
+/// This writer serializes `StdfRecord` into a STDF file
+/// of the given byte order, record header is generated
+/// from the record data automatically.
+///
+/// Supported compression:
+///  - Uncompressed
+///  - Gzip (.gz)
+///  - Bzip (.bz2)
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_file::*, stdf_record_type::*, ByteOrder, CompressType, StdfRecord};
+/// use std::io::Cursor;
+///
+/// let mut writer =
+///     StdfWriter::from(Vec::new(), &CompressType::Uncompressed, ByteOrder::LittleEndian)
+///         .unwrap();
+/// let mut far = StdfRecord::new(REC_FAR);
+/// if let StdfRecord::FAR(ref mut far_rec) = far {
+///     far_rec.cpu_type = 2;
+///     far_rec.stdf_ver = 4;
+/// }
+/// writer.write_record(&far).unwrap();
+/// writer.write_record(&StdfRecord::new(REC_MIR)).unwrap();
+/// // finish the file and take the inner stream
+/// let data = writer.finish().unwrap();
+///
+/// // read it back
+/// let mut reader =
+///     StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+/// let recs: Vec<StdfRecord> = reader.get_record_iter().map(|x| x.unwrap()).collect();
+/// assert_eq!(recs, vec![far, StdfRecord::new(REC_MIR)]);
+/// ```
+pub struct StdfWriter<W: Write> {
+    endianness: ByteOrder,
+    stream: StdfSink<W>,
+}
+
 pub struct RecordIter<'a, R> {
     inner: &'a mut StdfReader<R>,
+    v3_refused: bool,
 }
 
 pub struct RawDataIter<'a, R> {
@@ -179,6 +231,11 @@ impl<R: BufRead + Seek> StdfReader<R> {
                 ),
             });
         }
+        // STDF_VER of FAR, V3 records are not parsed
+        // by `get_record_iter`
+        let mut far_data = [0u8; 2];
+        stream.read_exact(&mut far_data)?;
+        let stdf_version = far_data[1];
         // restore file position
         // current flate2 does not support fseek, we need to consume
         // old stream and create a new one.
@@ -188,7 +245,25 @@ impl<R: BufRead + Seek> StdfReader<R> {
         //
         stream = rewind_stream_position(stream)?;
 
-        Ok(StdfReader { endianness, stream })
+        Ok(StdfReader {
+            endianness,
+            stdf_version,
+            stream,
+        })
+    }
+
+    /// return the byte order of the STDF file
+    #[inline(always)]
+    pub fn get_byte_order(&self) -> ByteOrder {
+        self.endianness
+    }
+
+    /// return `STDF_VER` of FAR, e.g. 4 for V4 and V4-2007,
+    /// 3 for V3, which should be read by `stdf_v3` module
+    /// (feature: `v3`)
+    #[inline(always)]
+    pub fn get_stdf_version(&self) -> u8 {
+        self.stdf_version
     }
 
     #[inline(always)]
@@ -202,9 +277,17 @@ impl<R: BufRead + Seek> StdfReader<R> {
     ///
     /// Only the records after the current file position
     /// can be read.
+    ///
+    /// Records of STDF V3 files are not parsed in V4 layout,
+    /// the iterator yields an error instead, use
+    /// `stdf_v3::upconvert_stream` or `stdf_v3::upconvert_file`
+    /// (feature: `v3`) for them.
     #[inline(always)]
     pub fn get_record_iter(&mut self) -> RecordIter<R> {
-        RecordIter { inner: self }
+        RecordIter {
+            inner: self,
+            v3_refused: false,
+        }
     }
 
     /// return an iterator for unprocessed STDF bytes
@@ -220,6 +303,151 @@ impl<R: BufRead + Seek> StdfReader<R> {
     }
 }
 
+impl StdfWriter<BufWriter<fs::File>> {
+    /// Create the given file and return a StdfWriter, if successful,
+    /// the file will be truncated if exists.
+    ///
+    /// compression is determined by the file extension
+    #[inline(always)]
+    pub fn new<P>(path: P, order: ByteOrder) -> Result<Self, StdfError>
+    where
+        P: AsRef<Path>,
+    {
+        // determine the compress type by file extension
+        let path_string = path.as_ref().display().to_string();
+        let file_ext = path_string.rsplit('.').next();
+        let compress_type = match file_ext {
+            Some(ext) => match ext {
+                #[cfg(feature = "gzip")]
+                "gz" => CompressType::GzipCompressed,
+                #[cfg(feature = "bzip")]
+                "bz2" => CompressType::BzipCompressed,
+                #[cfg(feature = "zipfile")]
+                "zip" => CompressType::ZipCompressed,
+                _ => CompressType::Uncompressed,
+            },
+            None => CompressType::Uncompressed,
+        };
+        // reject before the existing file is truncated
+        #[cfg(feature = "zipfile")]
+        if matches!(compress_type, CompressType::ZipCompressed) {
+            return Err(zip_write_error());
+        }
+        let fp = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let bw = BufWriter::with_capacity(2 << 20, fp);
+        StdfWriter::from(bw, &compress_type, order)
+    }
+}
+
+impl<W: Write> StdfWriter<W> {
+    /// Consume a output stream and generate a StdfWriter, if successful
+    ///
+    /// zip archive is not supported for writing.
+    #[inline(always)]
+    pub fn from(
+        out_stream: W,
+        compress_type: &CompressType,
+        order: ByteOrder,
+    ) -> Result<Self, StdfError> {
+        let stream = match compress_type {
+            #[cfg(feature = "gzip")]
+            CompressType::GzipCompressed => {
+                StdfSink::Gz(GzEncoder::new(out_stream, GzCompression::default()))
+            }
+            #[cfg(feature = "bzip")]
+            CompressType::BzipCompressed => {
+                StdfSink::Bz(BzEncoder::new(out_stream, BzCompression::default()))
+            }
+            #[cfg(feature = "zipfile")]
+            CompressType::ZipCompressed => return Err(zip_write_error()),
+            _ => StdfSink::Binary(out_stream),
+        };
+        Ok(StdfWriter {
+            endianness: order,
+            stream,
+        })
+    }
+
+    /// return the byte order of the output file
+    #[inline(always)]
+    pub fn get_byte_order(&self) -> ByteOrder {
+        self.endianness
+    }
+
+    /// serialize a StdfRecord and write it
+    /// to the stream with its header.
+    ///
+    /// `CPU_TYPE` of FAR is updated to the byte order
+    /// of the writer.
+    ///
+    /// `ReservedRec` and `InvalidRec` cannot be written,
+    /// use `write_raw_data` for them instead.
+    #[inline(always)]
+    pub fn write_record(&mut self, rec: &StdfRecord) -> Result<(), StdfError> {
+        let cpu_type = self.endianness.cpu_type();
+        let data = match rec {
+            StdfRecord::FAR(far_rec) if far_rec.cpu_type != cpu_type => StdfRecord::FAR(FAR {
+                cpu_type,
+                stdf_ver: far_rec.stdf_ver,
+            })
+            .write_to_bytes_with_header(&self.endianness)?,
+            _ => rec.write_to_bytes_with_header(&self.endianness)?,
+        };
+        self.stream.write_all(&data)?;
+        Ok(())
+    }
+
+    /// write unprocessed STDF bytes to the stream.
+    ///
+    /// if the byte order of `RawDataElement` is the same as
+    /// the writer, data is copied directly, otherwise it will be
+    /// parsed and serialized in the byte order of the writer,
+    /// except for reserved or invalid records, which
+    /// are copied as is. `CPU_TYPE` of FAR is updated
+    /// to the byte order of the writer in this case.
+    #[inline(always)]
+    pub fn write_raw_data(&mut self, raw_element: &RawDataElement) -> Result<(), StdfError> {
+        let copy_as_is = raw_element.byte_order == self.endianness
+            || raw_element.is_type(stdf_record_type::REC_RESERVE | stdf_record_type::REC_INVALID);
+        if copy_as_is {
+            let mut header = raw_element.header;
+            header.len = raw_element.raw_data.len() as u16;
+            self.stream
+                .write_all(&header.write_to_bytes(&self.endianness))?;
+            self.stream.write_all(&raw_element.raw_data)?;
+            Ok(())
+        } else {
+            self.write_record(&StdfRecord::from(raw_element))
+        }
+    }
+
+    /// flush all the data to the output stream,
+    /// finish the compression and return the inner stream.
+    ///
+    /// data is also flushed when the writer is dropped,
+    /// but errors will be ignored.
+    #[inline(always)]
+    pub fn finish(self) -> Result<W, StdfError> {
+        // the sink is always binary without compression features
+        #[cfg(not(any(feature = "gzip", feature = "bzip")))]
+        let StdfSink::Binary(mut out_stream) = self.stream;
+        #[cfg(any(feature = "gzip", feature = "bzip"))]
+        let mut out_stream = match self.stream {
+            StdfSink::Binary(bstream) => bstream,
+            #[cfg(feature = "gzip")]
+            StdfSink::Gz(gzstream) => gzstream.finish()?,
+            #[cfg(feature = "bzip")]
+            StdfSink::Bz(bzstream) => bzstream.finish()?,
+        };
+        out_stream.flush()?;
+        Ok(out_stream)
+    }
+}
+
 #[cfg(feature = "zipfile")]
 impl<R: BufRead + Seek> ZipBundle<R> {
     /// the following code is modified from this SO post:
@@ -284,6 +512,30 @@ impl<R: BufRead + Seek> Read for StdfStream<R> {
     }
 }
 
+impl<W: Write> Write for StdfSink<W> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            StdfSink::Binary(bstream) => bstream.write(buf),
+            #[cfg(feature = "gzip")]
+            StdfSink::Gz(gzstream) => gzstream.write(buf),
+            #[cfg(feature = "bzip")]
+            StdfSink::Bz(bzstream) => bzstream.write(buf),
+        }
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            StdfSink::Binary(bstream) => bstream.flush(),
+            #[cfg(feature = "gzip")]
+            StdfSink::Gz(gzstream) => gzstream.flush(),
+            #[cfg(feature = "bzip")]
+            StdfSink::Bz(bzstream) => bzstream.flush(),
+        }
+    }
+}
+
 // impl<R: Seek> Seek for StdfStream<R> {
 //     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
 //         match self {
@@ -299,6 +551,20 @@ impl<R: BufRead + Seek> Iterator for RecordIter<'_, R> {
 
     #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> {
+        if self.inner.stdf_version == 3 {
+            // report once and stop
+            if self.v3_refused {
+                return None;
+            }
+            self.v3_refused = true;
+            return Some(Err(StdfError {
+                code: 1,
+                msg: String::from(
+                    "STDF V3 file cannot be parsed in V4 layout, \
+                    use `stdf_v3::upconvert_stream` or `stdf_v3::upconvert_file` (feature: `v3`)",
+                ),
+            }));
+        }
         let header = match self.inner.read_header() {
             Ok(h) => h,
             Err(e) => {
@@ -398,6 +664,15 @@ pub(crate) fn rewind_stream_position<R: BufRead + Seek>(
     Ok(new_stream)
 }
 
+#[cfg(feature = "zipfile")]
+#[inline(always)]
+fn zip_write_error() -> StdfError {
+    StdfError {
+        code: 8,
+        msg: String::from("Writing zip archive is not supported"),
+    }
+}
+
 #[cfg(all(feature = "atdf", any(feature = "gzip", feature = "bzip",)))]
 #[inline(always)]
 fn general_read_until<T: Read>(r: &mut T, delim: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
@@ -52,6 +52,14 @@ macro_rules! read_optional {
     }};
 }
 
+macro_rules! write_optional {
+    ($remains:ident > $index:expr, $func:ident($($arg:tt)+)) => {{
+        if $remains > $index {
+            $func($($arg)+);
+        }
+    }};
+}
+
 // Common Type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ByteOrder {
@@ -59,6 +67,25 @@ pub enum ByteOrder {
     BigEndian,
 }
 
+impl ByteOrder {
+    /// `CPU_TYPE` of FAR for the byte order,
+    /// 1 for Sun (big endian) and 2 for PC (little endian)
+    ///
+    /// ```
+    /// use rust_stdf::ByteOrder;
+    ///
+    /// assert_eq!(ByteOrder::BigEndian.cpu_type(), 1);
+    /// assert_eq!(ByteOrder::LittleEndian.cpu_type(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn cpu_type(&self) -> U1 {
+        match self {
+            ByteOrder::BigEndian => 1,
+            ByteOrder::LittleEndian => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressType {
     Uncompressed,
@@ -1279,6 +1306,88 @@ pub struct ReservedRec {
 
 // implementation
 
+impl KxUf {
+    /// return the element count regardless of the data size
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        match self {
+            KxUf::F1(v) => v.len(),
+            KxUf::F2(v) => v.len(),
+            KxUf::F4(v) => v.len(),
+            KxUf::F8(v) => v.len(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// return the element at `index` as u64
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<u64> {
+        match self {
+            KxUf::F1(v) => v.get(index).map(|&x| x as u64),
+            KxUf::F2(v) => v.get(index).map(|&x| x as u64),
+            KxUf::F4(v) => v.get(index).map(|&x| x as u64),
+            KxUf::F8(v) => v.get(index).copied(),
+        }
+    }
+
+    /// convert all elements to u64
+    ///
+    /// ```
+    /// use rust_stdf::KxUf;
+    ///
+    /// let data = KxUf::F2(vec![1, 2, 65535]);
+    /// assert_eq!(vec![1u64, 2, 65535], data.to_u64_vec());
+    /// ```
+    #[inline(always)]
+    pub fn to_u64_vec(&self) -> Vec<u64> {
+        (0..self.len()).filter_map(|i| self.get(i)).collect()
+    }
+
+    /// return the data size (f) in bytes
+    #[inline(always)]
+    pub fn size(&self) -> u8 {
+        match self {
+            KxUf::F1(_) => 1,
+            KxUf::F2(_) => 2,
+            KxUf::F4(_) => 4,
+            KxUf::F8(_) => 8,
+        }
+    }
+
+    /// append the elements of `other`, the data size
+    /// is widened if `other` has a larger size
+    ///
+    /// ```
+    /// use rust_stdf::KxUf;
+    ///
+    /// let mut data = KxUf::F1(vec![1, 2]);
+    /// data.append(&KxUf::F2(vec![300]));
+    /// assert_eq!(data, KxUf::F2(vec![1, 2, 300]));
+    /// ```
+    pub fn append(&mut self, other: &KxUf) {
+        if other.size() > self.size() {
+            let values = self.to_u64_vec();
+            *self = match other {
+                KxUf::F2(_) => KxUf::F2(values.iter().map(|&x| x as u16).collect()),
+                KxUf::F4(_) => KxUf::F4(values.iter().map(|&x| x as u32).collect()),
+                _ => KxUf::F8(values),
+            };
+        }
+        // elements of `other` fit into the size of self
+        let values = other.to_u64_vec();
+        match self {
+            KxUf::F1(v) => v.extend(values.iter().map(|&x| x as u8)),
+            KxUf::F2(v) => v.extend(values.iter().map(|&x| x as u16)),
+            KxUf::F4(v) => v.extend(values.iter().map(|&x| x as u32)),
+            KxUf::F8(v) => v.extend(values),
+        }
+    }
+}
+
 impl RecordHeader {
     #[inline(always)]
     pub fn new() -> Self {
@@ -1323,6 +1432,16 @@ impl RecordHeader {
     pub fn get_type(&self) -> u64 {
         stdf_record_type::get_code_from_typ_sub(self.typ, self.sub)
     }
+
+    /// Serialize the header into 4 bytes with the given byte order
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> [u8; 4] {
+        let len_bytes = match order {
+            ByteOrder::LittleEndian => self.len.to_le_bytes(),
+            ByteOrder::BigEndian => self.len.to_be_bytes(),
+        };
+        [len_bytes[0], len_bytes[1], self.typ, self.sub]
+    }
 }
 
 impl FAR {
@@ -1337,6 +1456,14 @@ impl FAR {
         self.cpu_type = read_uint8(raw_data, pos);
         self.stdf_ver = read_uint8(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.cpu_type);
+        write_uint8(&mut buf, self.stdf_ver);
+        buf
+    }
 }
 
 impl ATR {
@@ -1351,6 +1478,14 @@ impl ATR {
         self.mod_tim = read_u4(raw_data, pos, order);
         self.cmd_line = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.mod_tim, order);
+        write_cn(&mut buf, &self.cmd_line);
+        buf
+    }
 }
 
 impl VUR {
@@ -1364,6 +1499,13 @@ impl VUR {
         let pos = &mut 0;
         self.upd_nam = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_cn(&mut buf, &self.upd_nam);
+        buf
+    }
 }
 
 impl MIR {
@@ -1426,6 +1568,50 @@ impl MIR {
         self.serl_num = read_cn(raw_data, pos);
         self.supr_nam = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.setup_t, order);
+        write_u4(&mut buf, self.start_t, order);
+        write_uint8(&mut buf, self.stat_num);
+        write_uint8(&mut buf, self.mode_cod as u8);
+        write_uint8(&mut buf, self.rtst_cod as u8);
+        write_uint8(&mut buf, self.prot_cod as u8);
+        write_u2(&mut buf, self.burn_tim, order);
+        write_uint8(&mut buf, self.cmod_cod as u8);
+        write_cn(&mut buf, &self.lot_id);
+        write_cn(&mut buf, &self.part_typ);
+        write_cn(&mut buf, &self.node_nam);
+        write_cn(&mut buf, &self.tstr_typ);
+        write_cn(&mut buf, &self.job_nam);
+        write_cn(&mut buf, &self.job_rev);
+        write_cn(&mut buf, &self.sblot_id);
+        write_cn(&mut buf, &self.oper_nam);
+        write_cn(&mut buf, &self.exec_typ);
+        write_cn(&mut buf, &self.exec_ver);
+        write_cn(&mut buf, &self.test_cod);
+        write_cn(&mut buf, &self.tst_temp);
+        write_cn(&mut buf, &self.user_txt);
+        write_cn(&mut buf, &self.aux_file);
+        write_cn(&mut buf, &self.pkg_typ);
+        write_cn(&mut buf, &self.famly_id);
+        write_cn(&mut buf, &self.date_cod);
+        write_cn(&mut buf, &self.facil_id);
+        write_cn(&mut buf, &self.floor_id);
+        write_cn(&mut buf, &self.proc_id);
+        write_cn(&mut buf, &self.oper_frq);
+        write_cn(&mut buf, &self.spec_nam);
+        write_cn(&mut buf, &self.spec_ver);
+        write_cn(&mut buf, &self.flow_id);
+        write_cn(&mut buf, &self.setup_id);
+        write_cn(&mut buf, &self.dsgn_rev);
+        write_cn(&mut buf, &self.eng_id);
+        write_cn(&mut buf, &self.rom_cod);
+        write_cn(&mut buf, &self.serl_num);
+        write_cn(&mut buf, &self.supr_nam);
+        buf
+    }
 }
 
 impl MRR {
@@ -1444,6 +1630,16 @@ impl MRR {
         self.usr_desc = read_cn(raw_data, pos);
         self.exc_desc = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.finish_t, order);
+        write_uint8(&mut buf, self.disp_cod as u8);
+        write_cn(&mut buf, &self.usr_desc);
+        write_cn(&mut buf, &self.exc_desc);
+        buf
+    }
 }
 
 impl PCR {
@@ -1471,6 +1667,19 @@ impl PCR {
             self.func_cnt = read_u4(raw_data, pos, order);
         }
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_u4(&mut buf, self.part_cnt, order);
+        write_u4(&mut buf, self.rtst_cnt, order);
+        write_u4(&mut buf, self.abrt_cnt, order);
+        write_u4(&mut buf, self.good_cnt, order);
+        write_u4(&mut buf, self.func_cnt, order);
+        buf
+    }
 }
 
 impl HBR {
@@ -1491,6 +1700,18 @@ impl HBR {
         }
         self.hbin_nam = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_u2(&mut buf, self.hbin_num, order);
+        write_u4(&mut buf, self.hbin_cnt, order);
+        write_uint8(&mut buf, self.hbin_pf as u8);
+        write_cn(&mut buf, &self.hbin_nam);
+        buf
+    }
 }
 
 impl SBR {
@@ -1511,6 +1732,18 @@ impl SBR {
         }
         self.sbin_nam = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_u2(&mut buf, self.sbin_num, order);
+        write_u4(&mut buf, self.sbin_cnt, order);
+        write_uint8(&mut buf, self.sbin_pf as u8);
+        write_cn(&mut buf, &self.sbin_nam);
+        buf
+    }
 }
 
 impl PMR {
@@ -1536,6 +1769,19 @@ impl PMR {
             self.site_num = read_uint8(raw_data, pos)
         };
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.pmr_indx, order);
+        write_u2(&mut buf, self.chan_typ, order);
+        write_cn(&mut buf, &self.chan_nam);
+        write_cn(&mut buf, &self.phy_nam);
+        write_cn(&mut buf, &self.log_nam);
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        buf
+    }
 }
 
 impl PGR {
@@ -1552,6 +1798,16 @@ impl PGR {
         self.indx_cnt = read_u2(raw_data, pos, order);
         self.pmr_indx = read_kx_u2(raw_data, pos, order, self.indx_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.grp_indx, order);
+        write_cn(&mut buf, &self.grp_nam);
+        write_u2(&mut buf, self.indx_cnt, order);
+        write_kx_u2(&mut buf, &self.pmr_indx, order, self.indx_cnt);
+        buf
+    }
 }
 
 impl PLR {
@@ -1572,6 +1828,20 @@ impl PLR {
         self.pgm_chal = read_kx_cn(raw_data, pos, self.grp_cnt);
         self.rtn_chal = read_kx_cn(raw_data, pos, self.grp_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.grp_cnt, order);
+        write_kx_u2(&mut buf, &self.grp_indx, order, self.grp_cnt);
+        write_kx_u2(&mut buf, &self.grp_mode, order, self.grp_cnt);
+        write_kx_u1(&mut buf, &self.grp_radx, self.grp_cnt);
+        write_kx_cn(&mut buf, &self.pgm_char, self.grp_cnt);
+        write_kx_cn(&mut buf, &self.rtn_char, self.grp_cnt);
+        write_kx_cn(&mut buf, &self.pgm_chal, self.grp_cnt);
+        write_kx_cn(&mut buf, &self.rtn_chal, self.grp_cnt);
+        buf
+    }
 }
 
 impl RDR {
@@ -1586,6 +1856,14 @@ impl RDR {
         self.num_bins = read_u2(raw_data, pos, order);
         self.rtst_bin = read_kx_u2(raw_data, pos, order, self.num_bins);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.num_bins, order);
+        write_kx_u2(&mut buf, &self.rtst_bin, order, self.num_bins);
+        buf
+    }
 }
 
 impl SDR {
@@ -1618,6 +1896,32 @@ impl SDR {
         self.extr_typ = read_cn(raw_data, pos);
         self.extr_id = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_grp);
+        write_uint8(&mut buf, self.site_cnt);
+        write_kx_u1(&mut buf, &self.site_num, self.site_cnt as u16);
+        write_cn(&mut buf, &self.hand_typ);
+        write_cn(&mut buf, &self.hand_id);
+        write_cn(&mut buf, &self.card_typ);
+        write_cn(&mut buf, &self.card_id);
+        write_cn(&mut buf, &self.load_typ);
+        write_cn(&mut buf, &self.load_id);
+        write_cn(&mut buf, &self.dib_typ);
+        write_cn(&mut buf, &self.dib_id);
+        write_cn(&mut buf, &self.cabl_typ);
+        write_cn(&mut buf, &self.cabl_id);
+        write_cn(&mut buf, &self.cont_typ);
+        write_cn(&mut buf, &self.cont_id);
+        write_cn(&mut buf, &self.lasr_typ);
+        write_cn(&mut buf, &self.lasr_id);
+        write_cn(&mut buf, &self.extr_typ);
+        write_cn(&mut buf, &self.extr_id);
+        buf
+    }
 }
 
 impl PSR {
@@ -1643,6 +1947,25 @@ impl PSR {
         self.atpg_dsc = read_kx_cn(raw_data, pos, self.locp_cnt);
         self.src_id = read_kx_cn(raw_data, pos, self.locp_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.cont_flg[0]);
+        write_u2(&mut buf, self.psr_indx, order);
+        write_cn(&mut buf, &self.psr_nam);
+        write_uint8(&mut buf, self.opt_flg[0]);
+        write_u2(&mut buf, self.totp_cnt, order);
+        write_u2(&mut buf, self.locp_cnt, order);
+        write_kx_u8(&mut buf, &self.pat_bgn, order, self.locp_cnt);
+        write_kx_u8(&mut buf, &self.pat_end, order, self.locp_cnt);
+        write_kx_cn(&mut buf, &self.pat_file, self.locp_cnt);
+        write_kx_cn(&mut buf, &self.pat_lbl, self.locp_cnt);
+        write_kx_cn(&mut buf, &self.file_uid, self.locp_cnt);
+        write_kx_cn(&mut buf, &self.atpg_dsc, self.locp_cnt);
+        write_kx_cn(&mut buf, &self.src_id, self.locp_cnt);
+        buf
+    }
 }
 
 impl NMR {
@@ -1660,6 +1983,17 @@ impl NMR {
         self.pmr_indx = read_kx_u2(raw_data, pos, order, self.locm_cnt);
         self.atpg_nam = read_kx_cn(raw_data, pos, self.locm_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.cont_flg[0]);
+        write_u2(&mut buf, self.totm_cnt, order);
+        write_u2(&mut buf, self.locm_cnt, order);
+        write_kx_u2(&mut buf, &self.pmr_indx, order, self.locm_cnt);
+        write_kx_cn(&mut buf, &self.atpg_nam, self.locm_cnt);
+        buf
+    }
 }
 
 impl CNR {
@@ -1675,6 +2009,15 @@ impl CNR {
         self.bit_pos = read_u4(raw_data, pos, order);
         self.cell_nam = read_sn(raw_data, pos, order);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.chn_num, order);
+        write_u4(&mut buf, self.bit_pos, order);
+        write_sn(&mut buf, &self.cell_nam, order);
+        buf
+    }
 }
 
 impl SSR {
@@ -1690,6 +2033,15 @@ impl SSR {
         self.chn_cnt = read_u2(raw_data, pos, order);
         self.chn_list = read_kx_u2(raw_data, pos, order, self.chn_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_cn(&mut buf, &self.ssr_nam);
+        write_u2(&mut buf, self.chn_cnt, order);
+        write_kx_u2(&mut buf, &self.chn_list, order, self.chn_cnt);
+        buf
+    }
 }
 
 impl CDR {
@@ -1717,6 +2069,25 @@ impl CDR {
         self.lst_cnt = read_u2(raw_data, pos, order);
         self.cell_lst = read_kx_sn(raw_data, pos, order, self.lst_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.cont_flg[0]);
+        write_u2(&mut buf, self.cdr_indx, order);
+        write_cn(&mut buf, &self.chn_nam);
+        write_u4(&mut buf, self.chn_len, order);
+        write_u2(&mut buf, self.sin_pin, order);
+        write_u2(&mut buf, self.sout_pin, order);
+        write_uint8(&mut buf, self.mstr_cnt);
+        write_kx_u2(&mut buf, &self.m_clks, order, self.mstr_cnt as u16);
+        write_uint8(&mut buf, self.slav_cnt);
+        write_kx_u2(&mut buf, &self.s_clks, order, self.slav_cnt as u16);
+        write_uint8(&mut buf, self.inv_val);
+        write_u2(&mut buf, self.lst_cnt, order);
+        write_kx_sn(&mut buf, &self.cell_lst, order, self.lst_cnt);
+        buf
+    }
 }
 
 impl WIR {
@@ -1735,6 +2106,16 @@ impl WIR {
         self.start_t = read_u4(raw_data, pos, order);
         self.wafer_id = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_grp);
+        write_u4(&mut buf, self.start_t, order);
+        write_cn(&mut buf, &self.wafer_id);
+        buf
+    }
 }
 
 impl WRR {
@@ -1771,6 +2152,26 @@ impl WRR {
         self.usr_desc = read_cn(raw_data, pos);
         self.exc_desc = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_grp);
+        write_u4(&mut buf, self.finish_t, order);
+        write_u4(&mut buf, self.part_cnt, order);
+        write_u4(&mut buf, self.rtst_cnt, order);
+        write_u4(&mut buf, self.abrt_cnt, order);
+        write_u4(&mut buf, self.good_cnt, order);
+        write_u4(&mut buf, self.func_cnt, order);
+        write_cn(&mut buf, &self.wafer_id);
+        write_cn(&mut buf, &self.fabwf_id);
+        write_cn(&mut buf, &self.frame_id);
+        write_cn(&mut buf, &self.mask_id);
+        write_cn(&mut buf, &self.usr_desc);
+        write_cn(&mut buf, &self.exc_desc);
+        buf
+    }
 }
 
 impl WCR {
@@ -1802,6 +2203,21 @@ impl WCR {
             self.pos_y = read_uint8(raw_data, pos) as char;
         }
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_r4(&mut buf, self.wafr_siz, order);
+        write_r4(&mut buf, self.die_ht, order);
+        write_r4(&mut buf, self.die_wid, order);
+        write_uint8(&mut buf, self.wf_units);
+        write_uint8(&mut buf, self.wf_flat as u8);
+        write_i2(&mut buf, self.center_x, order);
+        write_i2(&mut buf, self.center_y, order);
+        write_uint8(&mut buf, self.pos_x as u8);
+        write_uint8(&mut buf, self.pos_y as u8);
+        buf
+    }
 }
 
 impl PIR {
@@ -1816,6 +2232,14 @@ impl PIR {
         self.head_num = read_uint8(raw_data, pos);
         self.site_num = read_uint8(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        buf
+    }
 }
 
 impl PRR {
@@ -1848,6 +2272,24 @@ impl PRR {
         self.part_txt = read_cn(raw_data, pos);
         self.part_fix = read_bn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_uint8(&mut buf, self.part_flg[0]);
+        write_u2(&mut buf, self.num_test, order);
+        write_u2(&mut buf, self.hard_bin, order);
+        write_u2(&mut buf, self.soft_bin, order);
+        write_i2(&mut buf, self.x_coord, order);
+        write_i2(&mut buf, self.y_coord, order);
+        write_u4(&mut buf, self.test_t, order);
+        write_cn(&mut buf, &self.part_id);
+        write_cn(&mut buf, &self.part_txt);
+        write_bn(&mut buf, &self.part_fix);
+        buf
+    }
 }
 
 impl TSR {
@@ -1884,6 +2326,28 @@ impl TSR {
         self.tst_sums = read_r4(raw_data, pos, order);
         self.tst_sqrs = read_r4(raw_data, pos, order);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_uint8(&mut buf, self.test_typ as u8);
+        write_u4(&mut buf, self.test_num, order);
+        write_u4(&mut buf, self.exec_cnt, order);
+        write_u4(&mut buf, self.fail_cnt, order);
+        write_u4(&mut buf, self.alrm_cnt, order);
+        write_cn(&mut buf, &self.test_nam);
+        write_cn(&mut buf, &self.seq_name);
+        write_cn(&mut buf, &self.test_lbl);
+        write_uint8(&mut buf, self.opt_flag[0]);
+        write_r4(&mut buf, self.test_tim, order);
+        write_r4(&mut buf, self.test_min, order);
+        write_r4(&mut buf, self.test_max, order);
+        write_r4(&mut buf, self.tst_sums, order);
+        write_r4(&mut buf, self.tst_sqrs, order);
+        buf
+    }
 }
 
 impl PTR {
@@ -1916,6 +2380,78 @@ impl PTR {
         read_optional!(self.lo_spec, read_r4(raw_data, pos, order), 4);
         read_optional!(self.hi_spec, read_r4(raw_data, pos, order), 4);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.test_num, order);
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_uint8(&mut buf, self.test_flg[0]);
+        write_uint8(&mut buf, self.parm_flg[0]);
+        write_r4(&mut buf, self.result, order);
+        write_cn(&mut buf, &self.test_txt);
+        write_cn(&mut buf, &self.alarm_id);
+        // optional fields can only be omitted
+        // if all the following fields are omitted
+        let remains = [
+            self.opt_flag.is_some(),
+            self.res_scal.is_some(),
+            self.llm_scal.is_some(),
+            self.hlm_scal.is_some(),
+            self.lo_limit.is_some(),
+            self.hi_limit.is_some(),
+            self.units.is_some(),
+            self.c_resfmt.is_some(),
+            self.c_llmfmt.is_some(),
+            self.c_hlmfmt.is_some(),
+            self.lo_spec.is_some(),
+            self.hi_spec.is_some(),
+        ]
+        .iter()
+        .rposition(|&x| x)
+        .map_or(0, |p| p + 1);
+        write_optional!(
+            remains > 0,
+            write_uint8(&mut buf, self.opt_flag.unwrap_or([0])[0])
+        );
+        write_optional!(remains > 1, write_i1(&mut buf, self.res_scal.unwrap_or(0)));
+        write_optional!(remains > 2, write_i1(&mut buf, self.llm_scal.unwrap_or(0)));
+        write_optional!(remains > 3, write_i1(&mut buf, self.hlm_scal.unwrap_or(0)));
+        write_optional!(
+            remains > 4,
+            write_r4(&mut buf, self.lo_limit.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 5,
+            write_r4(&mut buf, self.hi_limit.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 6,
+            write_cn(&mut buf, self.units.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 7,
+            write_cn(&mut buf, self.c_resfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 8,
+            write_cn(&mut buf, self.c_llmfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 9,
+            write_cn(&mut buf, self.c_hlmfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 10,
+            write_r4(&mut buf, self.lo_spec.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 11,
+            write_r4(&mut buf, self.hi_spec.unwrap_or(0.0), order)
+        );
+        buf
+    }
 }
 
 impl MPR {
@@ -1959,6 +2495,106 @@ impl MPR {
         read_optional!(self.lo_spec, read_r4(raw_data, pos, order), 4);
         read_optional!(self.hi_spec, read_r4(raw_data, pos, order), 4);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.test_num, order);
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_uint8(&mut buf, self.test_flg[0]);
+        write_uint8(&mut buf, self.parm_flg[0]);
+        write_u2(&mut buf, self.rtn_icnt, order);
+        write_u2(&mut buf, self.rslt_cnt, order);
+        write_kx_n1(&mut buf, &self.rtn_stat, self.rtn_icnt);
+        write_kx_r4(&mut buf, &self.rtn_rslt, order, self.rslt_cnt);
+        write_cn(&mut buf, &self.test_txt);
+        write_cn(&mut buf, &self.alarm_id);
+        // optional fields can only be omitted
+        // if all the following fields are omitted
+        let remains = [
+            self.opt_flag.is_some(),
+            self.res_scal.is_some(),
+            self.llm_scal.is_some(),
+            self.hlm_scal.is_some(),
+            self.lo_limit.is_some(),
+            self.hi_limit.is_some(),
+            self.start_in.is_some(),
+            self.incr_in.is_some(),
+            self.rtn_indx.is_some(),
+            self.units.is_some(),
+            self.units_in.is_some(),
+            self.c_resfmt.is_some(),
+            self.c_llmfmt.is_some(),
+            self.c_hlmfmt.is_some(),
+            self.lo_spec.is_some(),
+            self.hi_spec.is_some(),
+        ]
+        .iter()
+        .rposition(|&x| x)
+        .map_or(0, |p| p + 1);
+        write_optional!(
+            remains > 0,
+            write_uint8(&mut buf, self.opt_flag.unwrap_or([0])[0])
+        );
+        write_optional!(remains > 1, write_i1(&mut buf, self.res_scal.unwrap_or(0)));
+        write_optional!(remains > 2, write_i1(&mut buf, self.llm_scal.unwrap_or(0)));
+        write_optional!(remains > 3, write_i1(&mut buf, self.hlm_scal.unwrap_or(0)));
+        write_optional!(
+            remains > 4,
+            write_r4(&mut buf, self.lo_limit.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 5,
+            write_r4(&mut buf, self.hi_limit.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 6,
+            write_r4(&mut buf, self.start_in.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 7,
+            write_r4(&mut buf, self.incr_in.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 8,
+            write_kx_u2(
+                &mut buf,
+                self.rtn_indx.as_deref().unwrap_or(&[]),
+                order,
+                self.rtn_icnt
+            )
+        );
+        write_optional!(
+            remains > 9,
+            write_cn(&mut buf, self.units.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 10,
+            write_cn(&mut buf, self.units_in.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 11,
+            write_cn(&mut buf, self.c_resfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 12,
+            write_cn(&mut buf, self.c_llmfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 13,
+            write_cn(&mut buf, self.c_hlmfmt.as_deref().unwrap_or(""))
+        );
+        write_optional!(
+            remains > 14,
+            write_r4(&mut buf, self.lo_spec.unwrap_or(0.0), order)
+        );
+        write_optional!(
+            remains > 15,
+            write_r4(&mut buf, self.hi_spec.unwrap_or(0.0), order)
+        );
+        buf
+    }
 }
 
 impl FTR {
@@ -2001,6 +2637,40 @@ impl FTR {
         }
         self.spin_map = read_dn(raw_data, pos, order);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u4(&mut buf, self.test_num, order);
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_uint8(&mut buf, self.test_flg[0]);
+        write_uint8(&mut buf, self.opt_flag[0]);
+        write_u4(&mut buf, self.cycl_cnt, order);
+        write_u4(&mut buf, self.rel_vadr, order);
+        write_u4(&mut buf, self.rept_cnt, order);
+        write_u4(&mut buf, self.num_fail, order);
+        write_i4(&mut buf, self.xfail_ad, order);
+        write_i4(&mut buf, self.yfail_ad, order);
+        write_i2(&mut buf, self.vect_off, order);
+        write_u2(&mut buf, self.rtn_icnt, order);
+        write_u2(&mut buf, self.pgm_icnt, order);
+        write_kx_u2(&mut buf, &self.rtn_indx, order, self.rtn_icnt);
+        write_kx_n1(&mut buf, &self.rtn_stat, self.rtn_icnt);
+        write_kx_u2(&mut buf, &self.pgm_indx, order, self.pgm_icnt);
+        write_kx_n1(&mut buf, &self.pgm_stat, self.pgm_icnt);
+        write_dn(&mut buf, &self.fail_pin, order);
+        write_cn(&mut buf, &self.vect_nam);
+        write_cn(&mut buf, &self.time_set);
+        write_cn(&mut buf, &self.op_code);
+        write_cn(&mut buf, &self.test_txt);
+        write_cn(&mut buf, &self.alarm_id);
+        write_cn(&mut buf, &self.prog_txt);
+        write_cn(&mut buf, &self.rslt_txt);
+        write_uint8(&mut buf, self.patg_num);
+        write_dn(&mut buf, &self.spin_map, order);
+        buf
+    }
 }
 
 impl STR {
@@ -2086,6 +2756,85 @@ impl STR {
         // k: TXT_CNT
         self.user_txt = read_kx_cf(raw_data, pos, self.txt_cnt, self.utx_size);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_uint8(&mut buf, self.cont_flg[0]);
+        write_u4(&mut buf, self.test_num, order);
+        write_uint8(&mut buf, self.head_num);
+        write_uint8(&mut buf, self.site_num);
+        write_u2(&mut buf, self.psr_ref, order);
+        write_uint8(&mut buf, self.test_flg[0]);
+        write_cn(&mut buf, &self.log_typ);
+        write_cn(&mut buf, &self.test_txt);
+        write_cn(&mut buf, &self.alarm_id);
+        write_cn(&mut buf, &self.prog_txt);
+        write_cn(&mut buf, &self.rslt_txt);
+        write_uint8(&mut buf, self.z_val);
+        write_uint8(&mut buf, self.fmu_flg[0]);
+        write_dn(&mut buf, &self.mask_map, order);
+        write_dn(&mut buf, &self.fal_map, order);
+        write_u8(&mut buf, self.cyc_cnt_t, order);
+        write_u4(&mut buf, self.totf_cnt, order);
+        write_u4(&mut buf, self.totl_cnt, order);
+        write_u8(&mut buf, self.cyc_base, order);
+        write_u4(&mut buf, self.bit_base, order);
+        write_u2(&mut buf, self.cond_cnt, order);
+        write_u2(&mut buf, self.lim_cnt, order);
+        write_uint8(&mut buf, self.cyc_size);
+        write_uint8(&mut buf, self.pmr_size);
+        write_uint8(&mut buf, self.chn_size);
+        write_uint8(&mut buf, self.pat_size);
+        write_uint8(&mut buf, self.bit_size);
+        write_uint8(&mut buf, self.u1_size);
+        write_uint8(&mut buf, self.u2_size);
+        write_uint8(&mut buf, self.u3_size);
+        write_uint8(&mut buf, self.utx_size);
+        write_u2(&mut buf, self.cap_bgn, order);
+        // k: LIM_CNT
+        write_kx_u2(&mut buf, &self.lim_indx, order, self.lim_cnt);
+        write_kx_u4(&mut buf, &self.lim_spec, order, self.lim_cnt);
+        // k: COND_CNT
+        write_kx_cn(&mut buf, &self.cond_lst, self.cond_cnt);
+        write_u2(&mut buf, self.cyc_cnt, order);
+        // k: CYC_CNT, f: CYC_SIZE
+        write_kx_uf(&mut buf, &self.cyc_ofst, order, self.cyc_cnt, self.cyc_size);
+        write_u2(&mut buf, self.pmr_cnt, order);
+        // k: PMR_CNT, f: PMR_SIZE
+        write_kx_uf(&mut buf, &self.pmr_indx, order, self.pmr_cnt, self.pmr_size);
+        write_u2(&mut buf, self.chn_cnt, order);
+        // k: CHN_CNT, f: CHN_SIZE
+        write_kx_uf(&mut buf, &self.chn_num, order, self.chn_cnt, self.chn_size);
+        write_u2(&mut buf, self.exp_cnt, order);
+        // k: EXP_CNT
+        write_kx_u1(&mut buf, &self.exp_data, self.exp_cnt);
+        write_u2(&mut buf, self.cap_cnt, order);
+        // k: CAP_CNT
+        write_kx_u1(&mut buf, &self.cap_data, self.cap_cnt);
+        write_u2(&mut buf, self.new_cnt, order);
+        // k: NEW_CNT
+        write_kx_u1(&mut buf, &self.new_data, self.new_cnt);
+        write_u2(&mut buf, self.pat_cnt, order);
+        // k: PAT_CNT, f: PAT_SIZE
+        write_kx_uf(&mut buf, &self.pat_num, order, self.pat_cnt, self.pat_size);
+        write_u2(&mut buf, self.bpos_cnt, order);
+        // k: BPOS_CNT, f: BIT_SIZE
+        write_kx_uf(&mut buf, &self.bit_pos, order, self.bpos_cnt, self.bit_size);
+        write_u2(&mut buf, self.usr1_cnt, order);
+        // k: USR1_CNT, f: U1_SIZE
+        write_kx_uf(&mut buf, &self.usr1, order, self.usr1_cnt, self.u1_size);
+        write_u2(&mut buf, self.usr2_cnt, order);
+        // k: USR2_CNT, f: U2_SIZE
+        write_kx_uf(&mut buf, &self.usr2, order, self.usr2_cnt, self.u2_size);
+        write_u2(&mut buf, self.usr3_cnt, order);
+        // k: USR3_CNT, f: U3_SIZE
+        write_kx_uf(&mut buf, &self.usr3, order, self.usr3_cnt, self.u3_size);
+        write_u2(&mut buf, self.txt_cnt, order);
+        // k: TXT_CNT
+        write_kx_cf(&mut buf, &self.user_txt, self.txt_cnt, self.utx_size);
+        buf
+    }
 }
 
 impl BPS {
@@ -2099,6 +2848,13 @@ impl BPS {
         let pos = &mut 0;
         self.seq_name = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_cn(&mut buf, &self.seq_name);
+        buf
+    }
 }
 
 impl EPS {
@@ -2107,6 +2863,10 @@ impl EPS {
     }
 
     pub fn read_from_bytes(&mut self, _raw_data: &[u8], _order: &ByteOrder) {}
+
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        vec![]
+    }
 }
 
 impl GDR {
@@ -2121,6 +2881,14 @@ impl GDR {
         self.fld_cnt = read_u2(raw_data, pos, order);
         self.gen_data = read_vn(raw_data, pos, order, self.fld_cnt);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u2(&mut buf, self.fld_cnt, order);
+        write_vn(&mut buf, &self.gen_data, order, self.fld_cnt);
+        buf
+    }
 }
 
 impl DTR {
@@ -2134,6 +2902,13 @@ impl DTR {
         let pos = &mut 0;
         self.text_dat = read_cn(raw_data, pos);
     }
+
+    #[inline(always)]
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_cn(&mut buf, &self.text_dat);
+        buf
+    }
 }
 
 impl ReservedRec {
@@ -2146,6 +2921,10 @@ impl ReservedRec {
         dataclone.extend_from_slice(raw_data);
         self.raw_data = dataclone;
     }
+
+    pub fn write_to_bytes(&self, _order: &ByteOrder) -> Vec<u8> {
+        self.raw_data.clone()
+    }
 }
 
 impl StdfRecord {
@@ -2485,6 +3264,114 @@ impl StdfRecord {
         rec.read_from_bytes(data_slice, order);
         Ok(rec)
     }
+
+    /// serialize StdfRecord to byte data which **DOES NOT**
+    /// contain the record header (len, typ, sub)
+    ///
+    /// it is the reverse of `read_from_bytes`, fields that
+    /// are missing in the original data will be written
+    /// with their default values.
+    ///
+    /// ```
+    /// use rust_stdf::{StdfRecord, ByteOrder, stdf_record_type::*};
+    ///
+    /// let mut new_rec = StdfRecord::new(REC_FAR);
+    /// new_rec.read_from_bytes(&[2, 4], &ByteOrder::LittleEndian);
+    ///
+    /// assert_eq!(vec![2, 4], new_rec.write_to_bytes(&ByteOrder::LittleEndian));
+    /// ```
+    #[inline(always)]
+    pub fn write_to_bytes(&self, order: &ByteOrder) -> Vec<u8> {
+        match self {
+            // rec type 15
+            StdfRecord::PTR(ptr_rec) => ptr_rec.write_to_bytes(order),
+            StdfRecord::MPR(mpr_rec) => mpr_rec.write_to_bytes(order),
+            StdfRecord::FTR(ftr_rec) => ftr_rec.write_to_bytes(order),
+            StdfRecord::STR(str_rec) => str_rec.write_to_bytes(order),
+            // rec type 5
+            StdfRecord::PIR(pir_rec) => pir_rec.write_to_bytes(order),
+            StdfRecord::PRR(prr_rec) => prr_rec.write_to_bytes(order),
+            // rec type 2
+            StdfRecord::WIR(wir_rec) => wir_rec.write_to_bytes(order),
+            StdfRecord::WRR(wrr_rec) => wrr_rec.write_to_bytes(order),
+            StdfRecord::WCR(wcr_rec) => wcr_rec.write_to_bytes(order),
+            // rec type 50
+            StdfRecord::GDR(gdr_rec) => gdr_rec.write_to_bytes(order),
+            StdfRecord::DTR(dtr_rec) => dtr_rec.write_to_bytes(order),
+            // rec type 10
+            StdfRecord::TSR(tsr_rec) => tsr_rec.write_to_bytes(order),
+            // rec type 1
+            StdfRecord::MIR(mir_rec) => mir_rec.write_to_bytes(order),
+            StdfRecord::MRR(mrr_rec) => mrr_rec.write_to_bytes(order),
+            StdfRecord::PCR(pcr_rec) => pcr_rec.write_to_bytes(order),
+            StdfRecord::HBR(hbr_rec) => hbr_rec.write_to_bytes(order),
+            StdfRecord::SBR(sbr_rec) => sbr_rec.write_to_bytes(order),
+            StdfRecord::PMR(pmr_rec) => pmr_rec.write_to_bytes(order),
+            StdfRecord::PGR(pgr_rec) => pgr_rec.write_to_bytes(order),
+            StdfRecord::PLR(plr_rec) => plr_rec.write_to_bytes(order),
+            StdfRecord::RDR(rdr_rec) => rdr_rec.write_to_bytes(order),
+            StdfRecord::SDR(sdr_rec) => sdr_rec.write_to_bytes(order),
+            StdfRecord::PSR(psr_rec) => psr_rec.write_to_bytes(order),
+            StdfRecord::NMR(nmr_rec) => nmr_rec.write_to_bytes(order),
+            StdfRecord::CNR(cnr_rec) => cnr_rec.write_to_bytes(order),
+            StdfRecord::SSR(ssr_rec) => ssr_rec.write_to_bytes(order),
+            StdfRecord::CDR(cdr_rec) => cdr_rec.write_to_bytes(order),
+            // rec type 0
+            StdfRecord::FAR(far_rec) => far_rec.write_to_bytes(order),
+            StdfRecord::ATR(atr_rec) => atr_rec.write_to_bytes(order),
+            StdfRecord::VUR(vur_rec) => vur_rec.write_to_bytes(order),
+            // rec type 20
+            StdfRecord::BPS(bps_rec) => bps_rec.write_to_bytes(order),
+            StdfRecord::EPS(eps_rec) => eps_rec.write_to_bytes(order),
+            // rec type 180: Reserved
+            // rec type 181: Reserved
+            StdfRecord::ReservedRec(reserve_rec) => reserve_rec.write_to_bytes(order),
+            // not matched, invalid rec has no data
+            StdfRecord::InvalidRec(_) => vec![],
+        }
+    }
+
+    /// serialize StdfRecord to byte data which
+    /// **contains** the record header (len, typ, sub).
+    ///
+    /// ## Error
+    /// `ReservedRec` and `InvalidRec` cannot be serialized, since
+    /// their (typ, sub) is unknown, and the field data cannot exceed
+    /// 65535 bytes. `StdfError` will be returned in these cases.
+    ///
+    /// ```
+    /// use rust_stdf::{StdfRecord, ByteOrder, stdf_record_type::*};
+    ///
+    /// let raw_with_header: [u8; 6] = [0, 2, 0, 10, 1, 4];
+    /// let rec = StdfRecord::read_from_bytes_with_header(&raw_with_header, &ByteOrder::BigEndian).unwrap();
+    /// let new_raw = rec.write_to_bytes_with_header(&ByteOrder::BigEndian).unwrap();
+    ///
+    /// assert_eq!(raw_with_header.to_vec(), new_raw);
+    /// ```
+    #[inline(always)]
+    pub fn write_to_bytes_with_header(&self, order: &ByteOrder) -> Result<Vec<u8>, StdfError> {
+        let (typ, sub) = stdf_record_type::get_typ_sub_from_code(self.get_type())?;
+        let data = self.write_to_bytes(order);
+        if data.len() > u16::MAX as usize {
+            return Err(StdfError {
+                code: 9,
+                msg: format!(
+                    "{} field data is {} bytes long, which exceeds the maximum record length 65535",
+                    stdf_record_type::get_rec_name_from_code(self.get_type()),
+                    data.len()
+                ),
+            });
+        }
+        let header = RecordHeader {
+            len: data.len() as u16,
+            typ,
+            sub,
+        };
+        let mut buf = Vec::with_capacity(4 + data.len());
+        buf.extend_from_slice(&header.write_to_bytes(order));
+        buf.extend_from_slice(&data);
+        Ok(buf)
+    }
 }
 
 impl RawDataElement {
@@ -2828,3 +3715,283 @@ pub(crate) fn read_vn(raw_data: &[u8], pos: &mut usize, order: &ByteOrder, k: u1
 pub(crate) fn bytes_to_string(data: &[u8]) -> String {
     data.iter().map(|&x| x as char).collect()
 }
+
+// data type write functions
+macro_rules! write_multi_byte_num {
+    ($buf:ident, $value:expr, $order:expr) => {{
+        match $order {
+            ByteOrder::LittleEndian => $buf.extend_from_slice(&$value.to_le_bytes()),
+            ByteOrder::BigEndian => $buf.extend_from_slice(&$value.to_be_bytes()),
+        }
+    }};
+}
+
+macro_rules! write_multi_element {
+    ($count:expr, $value:expr, $func:ident($buf:ident, _ $(, $arg:expr)*)) => {{
+        // pad with default value if vector is shorter than count
+        for i in 0..$count as usize {
+            let v = $value.get(i).copied().unwrap_or_default();
+            $func($buf, v $(, $arg)*);
+        }
+    }};
+}
+
+/// Write uint8 to byte vector, compatible with B1, C1 and U1
+#[inline(always)]
+pub(crate) fn write_uint8(buf: &mut Vec<u8>, value: u8) {
+    buf.push(value);
+}
+
+/// Write U2 (u16) to byte vector
+#[inline(always)]
+pub(crate) fn write_u2(buf: &mut Vec<u8>, value: U2, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write U4 (u32) to byte vector
+#[inline(always)]
+pub(crate) fn write_u4(buf: &mut Vec<u8>, value: U4, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write U8 (u64) to byte vector
+#[inline(always)]
+pub(crate) fn write_u8(buf: &mut Vec<u8>, value: U8, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write I1 (i8) to byte vector
+#[inline(always)]
+pub(crate) fn write_i1(buf: &mut Vec<u8>, value: I1) {
+    buf.push(value as u8);
+}
+
+/// Write I2 (i16) to byte vector
+#[inline(always)]
+pub(crate) fn write_i2(buf: &mut Vec<u8>, value: I2, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write I4 (i32) to byte vector
+#[inline(always)]
+pub(crate) fn write_i4(buf: &mut Vec<u8>, value: I4, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write R4 (f32) to byte vector
+#[inline(always)]
+pub(crate) fn write_r4(buf: &mut Vec<u8>, value: R4, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write R8 (f64) to byte vector
+#[inline(always)]
+pub(crate) fn write_r8(buf: &mut Vec<u8>, value: R8, order: &ByteOrder) {
+    write_multi_byte_num!(buf, value, order)
+}
+
+/// Write Cn (u8 + String) to byte vector, string longer than 255 bytes is truncated
+#[inline(always)]
+pub(crate) fn write_cn(buf: &mut Vec<u8>, value: &str) {
+    let mut data = string_to_bytes(value);
+    data.truncate(u8::MAX as usize);
+    buf.push(data.len() as u8);
+    buf.extend_from_slice(&data);
+}
+
+/// Write Sn (u16 + String) to byte vector, string longer than 65535 bytes is truncated
+#[inline(always)]
+pub(crate) fn write_sn(buf: &mut Vec<u8>, value: &str, order: &ByteOrder) {
+    let mut data = string_to_bytes(value);
+    data.truncate(u16::MAX as usize);
+    write_u2(buf, data.len() as u16, order);
+    buf.extend_from_slice(&data);
+}
+
+/// Write Cf (String) to byte vector, string is truncated or padded with space to "f" bytes
+#[inline(always)]
+pub(crate) fn write_cf(buf: &mut Vec<u8>, value: &str, f: u8) {
+    let mut data = string_to_bytes(value);
+    data.resize(f as usize, b' ');
+    buf.extend_from_slice(&data);
+}
+
+/// Write Bn (u8 + Vec<u8>) to byte vector, data longer than 255 bytes is truncated
+#[inline(always)]
+pub(crate) fn write_bn(buf: &mut Vec<u8>, value: &[u8]) {
+    let count = std::cmp::min(value.len(), u8::MAX as usize);
+    buf.push(count as u8);
+    buf.extend_from_slice(&value[..count]);
+}
+
+/// Write Dn (u16 + Vec<u8>) to byte vector, u16 is bit counts
+///
+/// since `Dn` only stores bytes, bit count is always a multiple of 8
+#[inline(always)]
+pub(crate) fn write_dn(buf: &mut Vec<u8>, value: &[u8], order: &ByteOrder) {
+    let bytecount = std::cmp::min(value.len(), u16::MAX as usize / 8);
+    write_u2(buf, (bytecount * 8) as u16, order);
+    buf.extend_from_slice(&value[..bytecount]);
+}
+
+/// Write KxCn (Vec<Cn>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_cn(buf: &mut Vec<u8>, value: &[Cn], k: u16) {
+    for i in 0..k as usize {
+        write_cn(buf, value.get(i).map_or("", |s| s.as_str()));
+    }
+}
+
+/// Write KxSn (Vec<Sn>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_sn(buf: &mut Vec<u8>, value: &[Sn], order: &ByteOrder, k: u16) {
+    for i in 0..k as usize {
+        write_sn(buf, value.get(i).map_or("", |s| s.as_str()), order);
+    }
+}
+
+/// Write KxCf (Vec<Cf>) to byte vector, vector size is provide by "k", String size is "f"
+#[inline(always)]
+pub(crate) fn write_kx_cf(buf: &mut Vec<u8>, value: &[Cf], k: u16, f: u8) {
+    for i in 0..k as usize {
+        write_cf(buf, value.get(i).map_or("", |s| s.as_str()), f);
+    }
+}
+
+/// Write KxU1 (Vec<u8>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_u1(buf: &mut Vec<u8>, value: &[U1], k: u16) {
+    write_multi_element!(k, value, write_uint8(buf, _))
+}
+
+/// Write KxU2 (Vec<u16>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_u2(buf: &mut Vec<u8>, value: &[U2], order: &ByteOrder, k: u16) {
+    write_multi_element!(k, value, write_u2(buf, _, order))
+}
+
+/// Write KxU4 (Vec<u32>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_u4(buf: &mut Vec<u8>, value: &[U4], order: &ByteOrder, k: u16) {
+    write_multi_element!(k, value, write_u4(buf, _, order))
+}
+
+/// Write KxU8 (Vec<u64>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_u8(buf: &mut Vec<u8>, value: &[U8], order: &ByteOrder, k: u16) {
+    write_multi_element!(k, value, write_u8(buf, _, order))
+}
+
+/// Write KxUf (Vec<u8|u16|u32|u64>) to byte vector, vector size is provide by "k", size of number is "f"
+///
+/// numbers are converted to the size "f" if the nested type is different
+#[inline(always)]
+pub(crate) fn write_kx_uf(buf: &mut Vec<u8>, value: &KxUf, order: &ByteOrder, k: u16, f: u8) {
+    if k == 0 {
+        return;
+    }
+    let value = value.to_u64_vec();
+    for i in 0..k as usize {
+        let v = value.get(i).copied().unwrap_or(0);
+        match f {
+            1 => write_uint8(buf, v as u8),
+            2 => write_u2(buf, v as u16, order),
+            4 => write_u4(buf, v as u32, order),
+            8 => write_u8(buf, v, order),
+            _ => {}
+        }
+    }
+}
+
+/// Write KxR4 (Vec<f32>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_kx_r4(buf: &mut Vec<u8>, value: &[R4], order: &ByteOrder, k: u16) {
+    write_multi_element!(k, value, write_r4(buf, _, order))
+}
+
+/// Write KxN1 (Vec<u8>) to byte vector, vector size is provide by "k"
+///
+/// two nibbles are packed into one byte, the first nibble is stored in the low 4 bits
+#[inline(always)]
+pub(crate) fn write_kx_n1(buf: &mut Vec<u8>, value: &[U1], k: u16) {
+    let nibble = |i: usize| value.get(i).copied().unwrap_or(0) & 0x0F;
+    for i in (0..k as usize).step_by(2) {
+        let high = if i + 1 < k as usize { nibble(i + 1) } else { 0 };
+        buf.push(nibble(i) | (high << 4));
+    }
+}
+
+/// Write V1 (u8 + generic value) to byte vector
+#[inline(always)]
+pub(crate) fn write_v1(buf: &mut Vec<u8>, value: &V1, order: &ByteOrder) {
+    match value {
+        V1::B0 => write_uint8(buf, 0),
+        V1::U1(v) => {
+            write_uint8(buf, 1);
+            write_uint8(buf, *v);
+        }
+        V1::U2(v) => {
+            write_uint8(buf, 2);
+            write_u2(buf, *v, order);
+        }
+        V1::U4(v) => {
+            write_uint8(buf, 3);
+            write_u4(buf, *v, order);
+        }
+        V1::I1(v) => {
+            write_uint8(buf, 4);
+            write_i1(buf, *v);
+        }
+        V1::I2(v) => {
+            write_uint8(buf, 5);
+            write_i2(buf, *v, order);
+        }
+        V1::I4(v) => {
+            write_uint8(buf, 6);
+            write_i4(buf, *v, order);
+        }
+        V1::R4(v) => {
+            write_uint8(buf, 7);
+            write_r4(buf, *v, order);
+        }
+        V1::R8(v) => {
+            write_uint8(buf, 8);
+            write_r8(buf, *v, order);
+        }
+        V1::Cn(v) => {
+            write_uint8(buf, 10);
+            write_cn(buf, v);
+        }
+        V1::Bn(v) => {
+            write_uint8(buf, 11);
+            write_bn(buf, v);
+        }
+        V1::Dn(v) => {
+            write_uint8(buf, 12);
+            write_dn(buf, v, order);
+        }
+        V1::N1(v) => {
+            write_uint8(buf, 13);
+            write_uint8(buf, *v & 0x0F);
+        }
+        // invalid data has no type code, skip it
+        V1::Invalid => {}
+    }
+}
+
+/// Write Vn (Vec<V1>) to byte vector, vector size is provide by "k"
+#[inline(always)]
+pub(crate) fn write_vn(buf: &mut Vec<u8>, value: &[V1], order: &ByteOrder, k: u16) {
+    for i in 0..k as usize {
+        write_v1(buf, value.get(i).unwrap_or(&V1::B0), order);
+    }
+}
+
+/// Convert String to bytes, chars that
+/// cannot be represented in latin1 are replaced by `?`
+#[inline(always)]
+pub(crate) fn string_to_bytes(data: &str) -> Vec<u8> {
+    data.chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
@@ -0,0 +1,1129 @@
+//
+// stdf_v3.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_types::{self as v4, *};
+use std::collections::HashMap;
+use std::io::{BufRead, Seek, Write};
+use std::path::Path;
+
+// V3 records that share the layout with V4
+// are parsed by `StdfRecord` directly:
+// FAR, PMR, BPS, EPS, GDR and DTR
+
+/// read an optional field, `None` if the
+/// record ends before the field
+macro_rules! read_optional {
+    ($func:ident($raw:expr, $pos:expr $(, $arg:expr)*), $min_bytes:expr) => {{
+        if *$pos + $min_bytes > $raw.len() {
+            None
+        } else {
+            Some($func($raw, $pos $(, $arg)*))
+        }
+    }};
+}
+
+/// C*n of fixed length in V3, e.g. UNITS (C*7),
+/// trailing spaces and nulls are removed
+#[inline(always)]
+fn read_fixed_text(raw_data: &[u8], pos: &mut usize, f: u8) -> Cn {
+    read_cf(raw_data, pos, f)
+        .trim_end_matches([' ', '\0'])
+        .to_string()
+}
+
+/// Master Information Record (1, 10)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MIR {
+    pub cpu_type: U1,
+    pub stdf_ver: U1,
+    pub mode_cod: C1,
+    pub stat_num: U1,
+    pub test_cod: Cn, // C*3
+    pub rtst_cod: C1,
+    pub prot_cod: C1,
+    pub cmod_cod: C1,
+    pub setup_t: U4,
+    pub start_t: U4,
+    pub lot_id: Cn,
+    pub part_typ: Cn,
+    pub job_nam: Cn,
+    pub oper_nam: Cn,
+    pub node_nam: Cn,
+    pub tstr_typ: Cn,
+    pub exec_typ: Cn,
+    pub supr_nam: Cn,
+    pub hand_id: Cn,
+    pub sblot_id: Cn,
+    pub job_rev: Cn,
+    pub proc_id: Cn,
+    pub prb_card: Cn,
+}
+
+/// Master Results Record (1, 20)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MRR {
+    pub finish_t: U4,
+    pub part_cnt: U4,
+    pub rtst_cnt: U4,
+    pub abrt_cnt: U4,
+    pub good_cnt: U4,
+    pub func_cnt: U4,
+    pub disp_cod: C1,
+    pub usr_desc: Cn,
+    pub exc_desc: Cn,
+}
+
+/// Hardware Bin Record (1, 40) of all sites
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HBR {
+    pub hbin_num: U2,
+    pub hbin_cnt: U4,
+    pub hbin_nam: Cn,
+}
+
+/// Software Bin Record (1, 50) of all sites
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SBR {
+    pub sbin_num: U2,
+    pub sbin_cnt: U4,
+    pub sbin_nam: Cn,
+}
+
+/// Wafer Information Record (2, 10)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WIR {
+    pub head_num: U1,
+    pub pad_byte: B1,
+    pub start_t: U4,
+    pub wafer_id: Cn,
+}
+
+/// Wafer Results Record (2, 20)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WRR {
+    pub finish_t: U4,
+    pub head_num: U1,
+    pub pad_byte: B1,
+    pub part_cnt: U4,
+    pub rtst_cnt: U4,
+    pub abrt_cnt: U4,
+    pub good_cnt: U4,
+    pub func_cnt: U4,
+    pub wafer_id: Cn,
+    pub hand_id: Cn,
+    pub prb_card: Cn,
+}
+
+/// Wafer Configuration Record (2, 30)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WCR {
+    pub wf_flat: C1,
+    pub pos_x: C1,
+    pub pos_y: C1,
+    pub wafr_siz: R4,
+    pub die_ht: R4,
+    pub die_wid: R4,
+    pub wf_units: U1,
+    pub center_x: I2,
+    pub center_y: I2,
+}
+
+/// Part Information Record (5, 10)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PIR {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub x_coord: I2,
+    pub y_coord: I2,
+    pub part_id: Cn,
+}
+
+/// Part Results Record (5, 20)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PRR {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub num_test: U2,
+    pub hard_bin: U2,
+    pub soft_bin: U2,
+    pub part_flg: B1,
+    pub pad_byte: B1,
+    pub x_coord: I2,
+    pub y_coord: I2,
+    pub part_id: Cn,
+    pub part_txt: Cn,
+    pub part_fix: Bn,
+}
+
+/// Parametric Test Description (10, 10), V3 only,
+/// provides the default descriptive fields of PTR
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PDR {
+    pub test_num: U4,
+    pub desc_flg: B1,
+    pub opt_flag: B1,
+    pub res_scal: I1,
+    pub units: Cn, // C*7
+    pub res_ldig: U1,
+    pub res_rdig: U1,
+    pub llm_scal: I1,
+    pub hlm_scal: I1,
+    pub llm_ldig: U1,
+    pub llm_rdig: U1,
+    pub hlm_ldig: U1,
+    pub hlm_rdig: U1,
+    pub lo_limit: R4,
+    pub hi_limit: R4,
+    pub test_nam: Cn,
+    pub seq_name: Cn,
+}
+
+/// Functional Test Description (10, 20), V3 only,
+/// provides the default descriptive fields of FTR
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FDR {
+    pub test_num: U4,
+    pub desc_flg: B1,
+    pub test_nam: Cn,
+    pub seq_name: Cn,
+}
+
+/// Test Synopsis Record (10, 30)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TSR {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_num: U4,
+    pub exec_cnt: U4,
+    pub fail_cnt: U4,
+    pub alrm_cnt: U4,
+    pub opt_flag: B1,
+    pub pad_byte: B1,
+    pub test_min: R4,
+    pub test_max: R4,
+    pub tst_mean: R4,
+    pub tst_sdev: R4,
+    pub tst_sums: R4,
+    pub tst_sqrs: R4,
+    pub test_nam: Cn,
+    pub seq_name: Cn,
+}
+
+/// Parametric Test Record (15, 10),
+/// fields after `RESULT` are optional,
+/// defaults are taken from PDR
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PTR {
+    pub test_num: U4,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_flg: B1,
+    pub parm_flg: B1,
+    pub result: R4,
+    pub opt_flag: Option<B1>,
+    pub res_scal: Option<I1>,
+    pub res_ldig: Option<U1>,
+    pub res_rdig: Option<U1>,
+    pub desc_flg: Option<B1>,
+    pub units: Option<Cn>, // C*7
+    pub llm_scal: Option<I1>,
+    pub hlm_scal: Option<I1>,
+    pub llm_ldig: Option<U1>,
+    pub llm_rdig: Option<U1>,
+    pub hlm_ldig: Option<U1>,
+    pub hlm_rdig: Option<U1>,
+    pub lo_limit: Option<R4>,
+    pub hi_limit: Option<R4>,
+    pub test_nam: Option<Cn>,
+    pub seq_name: Option<Cn>,
+    pub test_txt: Option<Cn>,
+}
+
+/// Functional Test Record (15, 20)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FTR {
+    pub test_num: U4,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_flg: B1,
+    pub desc_flg: B1,
+    pub opt_flag: B1,
+    pub time_set: U1,
+    pub vect_adr: U4,
+    pub cycl_cnt: U4,
+    pub rept_cnt: U2,
+    pub pcp_addr: U2,
+    pub num_fail: U4,
+    pub fail_pin: Bn,
+    pub vect_dat: Bn,
+    pub dev_dat: Bn,
+    pub rpin_map: Bn,
+    pub test_nam: Cn,
+    pub seq_name: Cn,
+    pub test_txt: Cn,
+}
+
+/// Site specific Hardware Bin record (25, 10), V3 only
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SHB {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub hbin_num: U2,
+    pub hbin_cnt: U4,
+    pub hbin_nam: Cn,
+}
+
+/// Site specific Software Bin record (25, 20), V3 only
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SSB {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub sbin_num: U2,
+    pub sbin_cnt: U4,
+    pub sbin_nam: Cn,
+}
+
+/// Site specific Test Synopsis record (25, 30), V3 only,
+/// negative counts are invalid
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct STS {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_num: U4,
+    pub exec_cnt: I4,
+    pub fail_cnt: I4,
+    pub alrm_cnt: I4,
+    pub opt_flag: B1,
+    pub pad_byte: B1,
+    pub test_min: R4,
+    pub test_max: R4,
+    pub tst_mean: R4,
+    pub tst_sdev: R4,
+    pub tst_sums: R4,
+    pub tst_sqrs: R4,
+    pub test_nam: Cn,
+    pub seq_name: Cn,
+    pub test_lbl: Cn,
+}
+
+/// Site specific part Count Record (25, 40), V3 only,
+/// negative counts are invalid
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SCR {
+    pub head_num: U1,
+    pub site_num: U1,
+    pub finish_t: U4,
+    pub part_cnt: U4,
+    pub rtst_cnt: I4,
+    pub abrt_cnt: I4,
+    pub good_cnt: I4,
+    pub func_cnt: I4,
+}
+
+/// STDF V3 records
+///
+/// Records that share the layout with V4 are
+/// wrapped in `V4`, records that are unknown in V3
+/// are kept in `Unknown`.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_file::*, stdf_v3::StdfV3Record};
+///
+/// if let Ok(mut reader) = StdfReader::new("demo_v3_file.stdf") {
+///     if reader.get_stdf_version() == 3 {
+///         for raw in reader.get_rawdata_iter().flatten() {
+///             println!("{:?}", StdfV3Record::from(&raw));
+///         }
+///     }
+/// }
+/// ```
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StdfV3Record {
+    MIR(MIR),
+    MRR(MRR),
+    HBR(HBR),
+    SBR(SBR),
+    WIR(WIR),
+    WRR(WRR),
+    WCR(WCR),
+    PIR(PIR),
+    PRR(PRR),
+    PDR(PDR),
+    FDR(FDR),
+    TSR(TSR),
+    PTR(PTR),
+    FTR(FTR),
+    SHB(SHB),
+    SSB(SSB),
+    STS(STS),
+    SCR(SCR),
+    /// FAR, PMR, BPS, EPS, GDR and DTR
+    V4(StdfRecord),
+    Unknown(RawDataElement),
+}
+
+impl MIR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.cpu_type = read_uint8(raw_data, pos);
+        self.stdf_ver = read_uint8(raw_data, pos);
+        self.mode_cod = read_uint8(raw_data, pos) as char;
+        self.stat_num = read_uint8(raw_data, pos);
+        self.test_cod = read_fixed_text(raw_data, pos, 3);
+        self.rtst_cod = read_uint8(raw_data, pos) as char;
+        self.prot_cod = read_uint8(raw_data, pos) as char;
+        self.cmod_cod = read_uint8(raw_data, pos) as char;
+        self.setup_t = read_u4(raw_data, pos, order);
+        self.start_t = read_u4(raw_data, pos, order);
+        self.lot_id = read_cn(raw_data, pos);
+        self.part_typ = read_cn(raw_data, pos);
+        self.job_nam = read_cn(raw_data, pos);
+        self.oper_nam = read_cn(raw_data, pos);
+        self.node_nam = read_cn(raw_data, pos);
+        self.tstr_typ = read_cn(raw_data, pos);
+        self.exec_typ = read_cn(raw_data, pos);
+        self.supr_nam = read_cn(raw_data, pos);
+        self.hand_id = read_cn(raw_data, pos);
+        self.sblot_id = read_cn(raw_data, pos);
+        self.job_rev = read_cn(raw_data, pos);
+        self.proc_id = read_cn(raw_data, pos);
+        self.prb_card = read_cn(raw_data, pos);
+    }
+}
+
+impl MRR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.finish_t = read_u4(raw_data, pos, order);
+        self.part_cnt = read_u4(raw_data, pos, order);
+        self.rtst_cnt = read_u4(raw_data, pos, order);
+        self.abrt_cnt = read_u4(raw_data, pos, order);
+        self.good_cnt = read_u4(raw_data, pos, order);
+        self.func_cnt = read_u4(raw_data, pos, order);
+        self.disp_cod = read_uint8(raw_data, pos) as char;
+        self.usr_desc = read_cn(raw_data, pos);
+        self.exc_desc = read_cn(raw_data, pos);
+    }
+}
+
+impl HBR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.hbin_num = read_u2(raw_data, pos, order);
+        self.hbin_cnt = read_u4(raw_data, pos, order);
+        self.hbin_nam = read_cn(raw_data, pos);
+    }
+}
+
+impl SBR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.sbin_num = read_u2(raw_data, pos, order);
+        self.sbin_cnt = read_u4(raw_data, pos, order);
+        self.sbin_nam = read_cn(raw_data, pos);
+    }
+}
+
+impl WIR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.pad_byte = [read_uint8(raw_data, pos)];
+        self.start_t = read_u4(raw_data, pos, order);
+        self.wafer_id = read_cn(raw_data, pos);
+    }
+}
+
+impl WRR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.finish_t = read_u4(raw_data, pos, order);
+        self.head_num = read_uint8(raw_data, pos);
+        self.pad_byte = [read_uint8(raw_data, pos)];
+        self.part_cnt = read_u4(raw_data, pos, order);
+        self.rtst_cnt = read_u4(raw_data, pos, order);
+        self.abrt_cnt = read_u4(raw_data, pos, order);
+        self.good_cnt = read_u4(raw_data, pos, order);
+        self.func_cnt = read_u4(raw_data, pos, order);
+        self.wafer_id = read_cn(raw_data, pos);
+        self.hand_id = read_cn(raw_data, pos);
+        self.prb_card = read_cn(raw_data, pos);
+    }
+}
+
+impl WCR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.wf_flat = read_uint8(raw_data, pos) as char;
+        self.pos_x = read_uint8(raw_data, pos) as char;
+        self.pos_y = read_uint8(raw_data, pos) as char;
+        self.wafr_siz = read_r4(raw_data, pos, order);
+        self.die_ht = read_r4(raw_data, pos, order);
+        self.die_wid = read_r4(raw_data, pos, order);
+        self.wf_units = read_uint8(raw_data, pos);
+        self.center_x = read_i2(raw_data, pos, order);
+        self.center_y = read_i2(raw_data, pos, order);
+    }
+}
+
+impl PIR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.x_coord = read_i2(raw_data, pos, order);
+        self.y_coord = read_i2(raw_data, pos, order);
+        self.part_id = read_cn(raw_data, pos);
+    }
+}
+
+impl PRR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.num_test = read_u2(raw_data, pos, order);
+        self.hard_bin = read_u2(raw_data, pos, order);
+        self.soft_bin = read_u2(raw_data, pos, order);
+        self.part_flg = [read_uint8(raw_data, pos)];
+        self.pad_byte = [read_uint8(raw_data, pos)];
+        self.x_coord = read_i2(raw_data, pos, order);
+        self.y_coord = read_i2(raw_data, pos, order);
+        self.part_id = read_cn(raw_data, pos);
+        self.part_txt = read_cn(raw_data, pos);
+        self.part_fix = read_bn(raw_data, pos);
+    }
+}
+
+impl PDR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.test_num = read_u4(raw_data, pos, order);
+        self.desc_flg = [read_uint8(raw_data, pos)];
+        self.opt_flag = [read_uint8(raw_data, pos)];
+        self.res_scal = read_i1(raw_data, pos);
+        self.units = read_fixed_text(raw_data, pos, 7);
+        self.res_ldig = read_uint8(raw_data, pos);
+        self.res_rdig = read_uint8(raw_data, pos);
+        self.llm_scal = read_i1(raw_data, pos);
+        self.hlm_scal = read_i1(raw_data, pos);
+        self.llm_ldig = read_uint8(raw_data, pos);
+        self.llm_rdig = read_uint8(raw_data, pos);
+        self.hlm_ldig = read_uint8(raw_data, pos);
+        self.hlm_rdig = read_uint8(raw_data, pos);
+        self.lo_limit = read_r4(raw_data, pos, order);
+        self.hi_limit = read_r4(raw_data, pos, order);
+        self.test_nam = read_cn(raw_data, pos);
+        self.seq_name = read_cn(raw_data, pos);
+    }
+}
+
+impl FDR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.test_num = read_u4(raw_data, pos, order);
+        self.desc_flg = [read_uint8(raw_data, pos)];
+        self.test_nam = read_cn(raw_data, pos);
+        self.seq_name = read_cn(raw_data, pos);
+    }
+}
+
+impl TSR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.test_num = read_u4(raw_data, pos, order);
+        self.exec_cnt = read_u4(raw_data, pos, order);
+        self.fail_cnt = read_u4(raw_data, pos, order);
+        self.alrm_cnt = read_u4(raw_data, pos, order);
+        self.opt_flag = [read_uint8(raw_data, pos)];
+        self.pad_byte = [read_uint8(raw_data, pos)];
+        self.test_min = read_r4(raw_data, pos, order);
+        self.test_max = read_r4(raw_data, pos, order);
+        self.tst_mean = read_r4(raw_data, pos, order);
+        self.tst_sdev = read_r4(raw_data, pos, order);
+        self.tst_sums = read_r4(raw_data, pos, order);
+        self.tst_sqrs = read_r4(raw_data, pos, order);
+        self.test_nam = read_cn(raw_data, pos);
+        self.seq_name = read_cn(raw_data, pos);
+    }
+}
+
+impl PTR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.test_num = read_u4(raw_data, pos, order);
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.test_flg = [read_uint8(raw_data, pos)];
+        self.parm_flg = [read_uint8(raw_data, pos)];
+        self.result = read_r4(raw_data, pos, order);
+        self.opt_flag = read_optional!(read_uint8(raw_data, pos), 1).map(|f| [f]);
+        self.res_scal = read_optional!(read_i1(raw_data, pos), 1);
+        self.res_ldig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.res_rdig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.desc_flg = read_optional!(read_uint8(raw_data, pos), 1).map(|f| [f]);
+        self.units = read_optional!(read_fixed_text(raw_data, pos, 7), 7);
+        self.llm_scal = read_optional!(read_i1(raw_data, pos), 1);
+        self.hlm_scal = read_optional!(read_i1(raw_data, pos), 1);
+        self.llm_ldig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.llm_rdig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.hlm_ldig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.hlm_rdig = read_optional!(read_uint8(raw_data, pos), 1);
+        self.lo_limit = read_optional!(read_r4(raw_data, pos, order), 4);
+        self.hi_limit = read_optional!(read_r4(raw_data, pos, order), 4);
+        self.test_nam = read_optional!(read_cn(raw_data, pos), 1);
+        self.seq_name = read_optional!(read_cn(raw_data, pos), 1);
+        self.test_txt = read_optional!(read_cn(raw_data, pos), 1);
+    }
+}
+
+impl FTR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.test_num = read_u4(raw_data, pos, order);
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.test_flg = [read_uint8(raw_data, pos)];
+        self.desc_flg = [read_uint8(raw_data, pos)];
+        self.opt_flag = [read_uint8(raw_data, pos)];
+        self.time_set = read_uint8(raw_data, pos);
+        self.vect_adr = read_u4(raw_data, pos, order);
+        self.cycl_cnt = read_u4(raw_data, pos, order);
+        self.rept_cnt = read_u2(raw_data, pos, order);
+        self.pcp_addr = read_u2(raw_data, pos, order);
+        self.num_fail = read_u4(raw_data, pos, order);
+        self.fail_pin = read_bn(raw_data, pos);
+        self.vect_dat = read_bn(raw_data, pos);
+        self.dev_dat = read_bn(raw_data, pos);
+        self.rpin_map = read_bn(raw_data, pos);
+        self.test_nam = read_cn(raw_data, pos);
+        self.seq_name = read_cn(raw_data, pos);
+        self.test_txt = read_cn(raw_data, pos);
+    }
+}
+
+impl SHB {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.hbin_num = read_u2(raw_data, pos, order);
+        self.hbin_cnt = read_u4(raw_data, pos, order);
+        self.hbin_nam = read_cn(raw_data, pos);
+    }
+}
+
+impl SSB {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.sbin_num = read_u2(raw_data, pos, order);
+        self.sbin_cnt = read_u4(raw_data, pos, order);
+        self.sbin_nam = read_cn(raw_data, pos);
+    }
+}
+
+impl STS {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.test_num = read_u4(raw_data, pos, order);
+        self.exec_cnt = read_i4(raw_data, pos, order);
+        self.fail_cnt = read_i4(raw_data, pos, order);
+        self.alrm_cnt = read_i4(raw_data, pos, order);
+        self.opt_flag = [read_uint8(raw_data, pos)];
+        self.pad_byte = [read_uint8(raw_data, pos)];
+        self.test_min = read_r4(raw_data, pos, order);
+        self.test_max = read_r4(raw_data, pos, order);
+        self.tst_mean = read_r4(raw_data, pos, order);
+        self.tst_sdev = read_r4(raw_data, pos, order);
+        self.tst_sums = read_r4(raw_data, pos, order);
+        self.tst_sqrs = read_r4(raw_data, pos, order);
+        self.test_nam = read_cn(raw_data, pos);
+        self.seq_name = read_cn(raw_data, pos);
+        self.test_lbl = read_cn(raw_data, pos);
+    }
+}
+
+impl SCR {
+    pub fn read_from_bytes(&mut self, raw_data: &[u8], order: &ByteOrder) {
+        let pos = &mut 0;
+        self.head_num = read_uint8(raw_data, pos);
+        self.site_num = read_uint8(raw_data, pos);
+        self.finish_t = read_u4(raw_data, pos, order);
+        self.part_cnt = read_u4(raw_data, pos, order);
+        self.rtst_cnt = read_i4(raw_data, pos, order);
+        self.abrt_cnt = read_i4(raw_data, pos, order);
+        self.good_cnt = read_i4(raw_data, pos, order);
+        self.func_cnt = read_i4(raw_data, pos, order);
+    }
+}
+
+impl From<&RawDataElement> for StdfV3Record {
+    fn from(raw_element: &RawDataElement) -> Self {
+        macro_rules! parse {
+            ($variant:ident) => {{
+                let mut rec = $variant::default();
+                rec.read_from_bytes(&raw_element.raw_data, &raw_element.byte_order);
+                StdfV3Record::$variant(rec)
+            }};
+        }
+        match (raw_element.header.typ, raw_element.header.sub) {
+            (1, 10) => parse!(MIR),
+            (1, 20) => parse!(MRR),
+            (1, 40) => parse!(HBR),
+            (1, 50) => parse!(SBR),
+            (2, 10) => parse!(WIR),
+            (2, 20) => parse!(WRR),
+            (2, 30) => parse!(WCR),
+            (5, 10) => parse!(PIR),
+            (5, 20) => parse!(PRR),
+            (10, 10) => parse!(PDR),
+            (10, 20) => parse!(FDR),
+            (10, 30) => parse!(TSR),
+            (15, 10) => parse!(PTR),
+            (15, 20) => parse!(FTR),
+            (25, 10) => parse!(SHB),
+            (25, 20) => parse!(SSB),
+            (25, 30) => parse!(STS),
+            (25, 40) => parse!(SCR),
+            (0, 10) | (1, 60) | (20, 10) | (20, 20) | (50, 10) | (50, 30) => {
+                StdfV3Record::V4(StdfRecord::from(raw_element))
+            }
+            _ => StdfV3Record::Unknown(raw_element.clone()),
+        }
+    }
+}
+
+/// Convert STDF V3 records to V4 `StdfRecord`s
+///
+/// The records should be fed in the order of the file,
+/// PTR and FTR take the test name, limits and units
+/// from the PDR and FDR of the same test number
+/// if the fields are omitted.
+///
+/// | V3                    | V4                              |
+/// |-----------------------|---------------------------------|
+/// | FAR                   | FAR, `STDF_VER` = 4             |
+/// | MIR                   | MIR, SDR if `HAND_ID` or `PRB_CARD` is not empty |
+/// | MRR                   | PCR of head 255, MRR            |
+/// | HBR / SBR             | HBR / SBR of head 255           |
+/// | SHB / SSB             | HBR / SBR                       |
+/// | TSR / STS             | TSR                             |
+/// | SCR                   | PCR                             |
+/// | PDR / FDR             | none, merged into PTR / FTR     |
+/// | PTR / FTR             | PTR / FTR, `TEST_NAM` as `TEST_TXT` |
+/// | WIR, WRR, WCR, PIR, PRR | records of the same name      |
+/// | PMR, BPS, EPS, GDR, DTR | unchanged                     |
+/// | unknown records       | none                            |
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_v3::*, StdfRecord};
+///
+/// let mut converter = V3Converter::new();
+/// converter.convert(&StdfV3Record::PDR(PDR {
+///     test_num: 1,
+///     units: "V".to_string(),
+///     lo_limit: 0.5,
+///     hi_limit: 1.5,
+///     test_nam: "VOH".to_string(),
+///     ..Default::default()
+/// }));
+/// // a PTR without descriptive fields
+/// let recs = converter.convert(&StdfV3Record::PTR(PTR {
+///     test_num: 1,
+///     result: 1.2,
+///     ..Default::default()
+/// }));
+/// if let StdfRecord::PTR(ptr_rec) = &recs[0] {
+///     assert_eq!(ptr_rec.test_txt, "VOH");
+///     assert_eq!(ptr_rec.units, Some("V".to_string()));
+///     assert_eq!(ptr_rec.hi_limit, Some(1.5));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct V3Converter {
+    pdrs: HashMap<U4, PDR>,
+    fdrs: HashMap<U4, FDR>,
+}
+
+impl V3Converter {
+    #[inline(always)]
+    pub fn new() -> Self {
+        V3Converter::default()
+    }
+
+    /// convert a V3 record to V4 records,
+    /// the list is empty if there is no V4 equivalent
+    pub fn convert(&mut self, rec: &StdfV3Record) -> Vec<StdfRecord> {
+        match rec {
+            StdfV3Record::V4(StdfRecord::FAR(far_rec)) => vec![StdfRecord::FAR(v4::FAR {
+                cpu_type: far_rec.cpu_type,
+                stdf_ver: 4,
+            })],
+            StdfV3Record::V4(v4_rec) => vec![v4_rec.clone()],
+            StdfV3Record::MIR(mir_rec) => {
+                let mut rec_list = vec![StdfRecord::MIR(v4::MIR {
+                    setup_t: mir_rec.setup_t,
+                    start_t: mir_rec.start_t,
+                    stat_num: mir_rec.stat_num,
+                    mode_cod: mir_rec.mode_cod,
+                    rtst_cod: mir_rec.rtst_cod,
+                    prot_cod: mir_rec.prot_cod,
+                    cmod_cod: mir_rec.cmod_cod,
+                    lot_id: mir_rec.lot_id.clone(),
+                    part_typ: mir_rec.part_typ.clone(),
+                    node_nam: mir_rec.node_nam.clone(),
+                    tstr_typ: mir_rec.tstr_typ.clone(),
+                    job_nam: mir_rec.job_nam.clone(),
+                    job_rev: mir_rec.job_rev.clone(),
+                    sblot_id: mir_rec.sblot_id.clone(),
+                    oper_nam: mir_rec.oper_nam.clone(),
+                    exec_typ: mir_rec.exec_typ.clone(),
+                    test_cod: mir_rec.test_cod.clone(),
+                    proc_id: mir_rec.proc_id.clone(),
+                    supr_nam: mir_rec.supr_nam.clone(),
+                    ..Default::default()
+                })];
+                // handler and probe card are moved to SDR in V4
+                if !mir_rec.hand_id.is_empty() || !mir_rec.prb_card.is_empty() {
+                    rec_list.push(StdfRecord::SDR(v4::SDR {
+                        head_num: 255,
+                        site_grp: 255,
+                        hand_id: mir_rec.hand_id.clone(),
+                        card_id: mir_rec.prb_card.clone(),
+                        ..Default::default()
+                    }));
+                }
+                rec_list
+            }
+            StdfV3Record::MRR(mrr_rec) => vec![
+                StdfRecord::PCR(v4::PCR {
+                    head_num: 255,
+                    site_num: 0,
+                    part_cnt: mrr_rec.part_cnt,
+                    rtst_cnt: mrr_rec.rtst_cnt,
+                    abrt_cnt: mrr_rec.abrt_cnt,
+                    good_cnt: mrr_rec.good_cnt,
+                    func_cnt: mrr_rec.func_cnt,
+                }),
+                StdfRecord::MRR(v4::MRR {
+                    finish_t: mrr_rec.finish_t,
+                    disp_cod: mrr_rec.disp_cod,
+                    usr_desc: mrr_rec.usr_desc.clone(),
+                    exc_desc: mrr_rec.exc_desc.clone(),
+                }),
+            ],
+            StdfV3Record::HBR(hbr_rec) => vec![StdfRecord::HBR(v4::HBR {
+                head_num: 255,
+                site_num: 0,
+                hbin_num: hbr_rec.hbin_num,
+                hbin_cnt: hbr_rec.hbin_cnt,
+                hbin_pf: ' ',
+                hbin_nam: hbr_rec.hbin_nam.clone(),
+            })],
+            StdfV3Record::SBR(sbr_rec) => vec![StdfRecord::SBR(v4::SBR {
+                head_num: 255,
+                site_num: 0,
+                sbin_num: sbr_rec.sbin_num,
+                sbin_cnt: sbr_rec.sbin_cnt,
+                sbin_pf: ' ',
+                sbin_nam: sbr_rec.sbin_nam.clone(),
+            })],
+            StdfV3Record::SHB(shb_rec) => vec![StdfRecord::HBR(v4::HBR {
+                head_num: shb_rec.head_num,
+                site_num: shb_rec.site_num,
+                hbin_num: shb_rec.hbin_num,
+                hbin_cnt: shb_rec.hbin_cnt,
+                hbin_pf: ' ',
+                hbin_nam: shb_rec.hbin_nam.clone(),
+            })],
+            StdfV3Record::SSB(ssb_rec) => vec![StdfRecord::SBR(v4::SBR {
+                head_num: ssb_rec.head_num,
+                site_num: ssb_rec.site_num,
+                sbin_num: ssb_rec.sbin_num,
+                sbin_cnt: ssb_rec.sbin_cnt,
+                sbin_pf: ' ',
+                sbin_nam: ssb_rec.sbin_nam.clone(),
+            })],
+            StdfV3Record::TSR(tsr_rec) => vec![StdfRecord::TSR(self.to_tsr(
+                (tsr_rec.head_num, tsr_rec.site_num, tsr_rec.test_num),
+                (tsr_rec.exec_cnt, tsr_rec.fail_cnt, tsr_rec.alrm_cnt),
+                tsr_rec.opt_flag,
+                [
+                    tsr_rec.test_min,
+                    tsr_rec.test_max,
+                    tsr_rec.tst_sums,
+                    tsr_rec.tst_sqrs,
+                ],
+                (&tsr_rec.test_nam, &tsr_rec.seq_name, ""),
+            ))],
+            StdfV3Record::STS(sts_rec) => vec![StdfRecord::TSR(self.to_tsr(
+                (sts_rec.head_num, sts_rec.site_num, sts_rec.test_num),
+                (
+                    valid_count(sts_rec.exec_cnt),
+                    valid_count(sts_rec.fail_cnt),
+                    valid_count(sts_rec.alrm_cnt),
+                ),
+                sts_rec.opt_flag,
+                [
+                    sts_rec.test_min,
+                    sts_rec.test_max,
+                    sts_rec.tst_sums,
+                    sts_rec.tst_sqrs,
+                ],
+                (&sts_rec.test_nam, &sts_rec.seq_name, &sts_rec.test_lbl),
+            ))],
+            StdfV3Record::SCR(scr_rec) => vec![StdfRecord::PCR(v4::PCR {
+                head_num: scr_rec.head_num,
+                site_num: scr_rec.site_num,
+                part_cnt: scr_rec.part_cnt,
+                rtst_cnt: valid_count(scr_rec.rtst_cnt),
+                abrt_cnt: valid_count(scr_rec.abrt_cnt),
+                good_cnt: valid_count(scr_rec.good_cnt),
+                func_cnt: valid_count(scr_rec.func_cnt),
+            })],
+            StdfV3Record::WIR(wir_rec) => vec![StdfRecord::WIR(v4::WIR {
+                head_num: wir_rec.head_num,
+                site_grp: 255,
+                start_t: wir_rec.start_t,
+                wafer_id: wir_rec.wafer_id.clone(),
+            })],
+            StdfV3Record::WRR(wrr_rec) => vec![StdfRecord::WRR(v4::WRR {
+                head_num: wrr_rec.head_num,
+                site_grp: 255,
+                finish_t: wrr_rec.finish_t,
+                part_cnt: wrr_rec.part_cnt,
+                rtst_cnt: wrr_rec.rtst_cnt,
+                abrt_cnt: wrr_rec.abrt_cnt,
+                good_cnt: wrr_rec.good_cnt,
+                func_cnt: wrr_rec.func_cnt,
+                wafer_id: wrr_rec.wafer_id.clone(),
+                ..Default::default()
+            })],
+            StdfV3Record::WCR(wcr_rec) => vec![StdfRecord::WCR(v4::WCR {
+                wafr_siz: wcr_rec.wafr_siz,
+                die_ht: wcr_rec.die_ht,
+                die_wid: wcr_rec.die_wid,
+                wf_units: wcr_rec.wf_units,
+                wf_flat: wcr_rec.wf_flat,
+                center_x: wcr_rec.center_x,
+                center_y: wcr_rec.center_y,
+                pos_x: wcr_rec.pos_x,
+                pos_y: wcr_rec.pos_y,
+            })],
+            StdfV3Record::PIR(pir_rec) => vec![StdfRecord::PIR(v4::PIR {
+                head_num: pir_rec.head_num,
+                site_num: pir_rec.site_num,
+            })],
+            StdfV3Record::PRR(prr_rec) => vec![StdfRecord::PRR(v4::PRR {
+                head_num: prr_rec.head_num,
+                site_num: prr_rec.site_num,
+                part_flg: prr_rec.part_flg,
+                num_test: prr_rec.num_test,
+                hard_bin: prr_rec.hard_bin,
+                soft_bin: prr_rec.soft_bin,
+                x_coord: prr_rec.x_coord,
+                y_coord: prr_rec.y_coord,
+                test_t: 0,
+                part_id: prr_rec.part_id.clone(),
+                part_txt: prr_rec.part_txt.clone(),
+                part_fix: prr_rec.part_fix.clone(),
+            })],
+            StdfV3Record::PDR(pdr_rec) => {
+                self.pdrs.insert(pdr_rec.test_num, pdr_rec.clone());
+                vec![]
+            }
+            StdfV3Record::FDR(fdr_rec) => {
+                self.fdrs.insert(fdr_rec.test_num, fdr_rec.clone());
+                vec![]
+            }
+            StdfV3Record::PTR(ptr_rec) => vec![StdfRecord::PTR(self.to_ptr(ptr_rec))],
+            StdfV3Record::FTR(ftr_rec) => {
+                let test_txt = match (
+                    ftr_rec.test_nam.is_empty(),
+                    self.fdrs.get(&ftr_rec.test_num),
+                ) {
+                    (true, Some(fdr)) => fdr.test_nam.clone(),
+                    _ => ftr_rec.test_nam.clone(),
+                };
+                vec![StdfRecord::FTR(v4::FTR {
+                    test_num: ftr_rec.test_num,
+                    head_num: ftr_rec.head_num,
+                    site_num: ftr_rec.site_num,
+                    test_flg: ftr_rec.test_flg,
+                    // bit 4, 5: XFAIL_AD, YFAIL_AD and
+                    // VECT_OFF are not available in V3
+                    opt_flag: [0b0011_0000],
+                    cycl_cnt: ftr_rec.cycl_cnt,
+                    rel_vadr: ftr_rec.vect_adr,
+                    rept_cnt: ftr_rec.rept_cnt as U4,
+                    num_fail: ftr_rec.num_fail,
+                    fail_pin: ftr_rec.fail_pin.clone(),
+                    time_set: if ftr_rec.time_set == 0 {
+                        String::new()
+                    } else {
+                        ftr_rec.time_set.to_string()
+                    },
+                    test_txt,
+                    ..Default::default()
+                })]
+            }
+            StdfV3Record::Unknown(_) => vec![],
+        }
+    }
+
+    fn to_ptr(&self, ptr: &PTR) -> v4::PTR {
+        let pdr = self.pdrs.get(&ptr.test_num);
+        // the field of PTR, or the default from PDR
+        macro_rules! field_or_pdr {
+            ($field:ident) => {
+                ptr.$field.clone().or_else(|| pdr.map(|p| p.$field.clone()))
+            };
+        }
+        let test_nam = ptr
+            .test_nam
+            .clone()
+            .filter(|n| !n.is_empty())
+            .or_else(|| pdr.map(|p| p.test_nam.clone()))
+            .unwrap_or_default();
+        // bit 0, 4, 5, 6, 7 are the same in V3 and V4,
+        // bit 1 must be 1 in V4, bit 2, 3: no spec limits in V3
+        let opt_flag = field_or_pdr!(opt_flag).map(|f| [(f[0] & 0b1111_0001) | 0b0000_1110]);
+        let has_desc = opt_flag.is_some();
+        v4::PTR {
+            test_num: ptr.test_num,
+            head_num: ptr.head_num,
+            site_num: ptr.site_num,
+            test_flg: ptr.test_flg,
+            parm_flg: ptr.parm_flg,
+            result: ptr.result,
+            test_txt: test_nam,
+            alarm_id: String::new(),
+            opt_flag,
+            res_scal: field_or_pdr!(res_scal).filter(|_| has_desc),
+            llm_scal: field_or_pdr!(llm_scal).filter(|_| has_desc),
+            hlm_scal: field_or_pdr!(hlm_scal).filter(|_| has_desc),
+            lo_limit: field_or_pdr!(lo_limit).filter(|_| has_desc),
+            hi_limit: field_or_pdr!(hi_limit).filter(|_| has_desc),
+            units: field_or_pdr!(units).filter(|_| has_desc),
+            ..Default::default()
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn to_tsr(
+        &self,
+        (head_num, site_num, test_num): (U1, U1, U4),
+        (exec_cnt, fail_cnt, alrm_cnt): (U4, U4, U4),
+        opt_flag: B1,
+        [test_min, test_max, tst_sums, tst_sqrs]: [R4; 4],
+        (test_nam, seq_name, test_lbl): (&str, &str, &str),
+    ) -> v4::TSR {
+        let test_typ = if self.pdrs.contains_key(&test_num) {
+            'P'
+        } else if self.fdrs.contains_key(&test_num) {
+            'F'
+        } else {
+            ' '
+        };
+        v4::TSR {
+            head_num,
+            site_num,
+            test_typ,
+            test_num,
+            exec_cnt,
+            fail_cnt,
+            alrm_cnt,
+            test_nam: test_nam.to_string(),
+            seq_name: seq_name.to_string(),
+            test_lbl: test_lbl.to_string(),
+            // bit 0, 1, 4, 5 are the same in V3 and V4,
+            // bit 2: TEST_TIM is not available in V3
+            opt_flag: [(opt_flag[0] & 0b0011_0011) | 0b0000_0100],
+            test_tim: 0.0,
+            test_min,
+            test_max,
+            tst_sums,
+            tst_sqrs,
+        }
+    }
+}
+
+/// negative count is invalid in V3,
+/// which is 4294967295 in V4
+#[inline(always)]
+fn valid_count(cnt: I4) -> U4 {
+    U4::try_from(cnt).unwrap_or(4_294_967_295)
+}
+
+/// Convert the V3 records from `reader` to V4
+/// and write them to `writer`, return the count of
+/// the records written.
+///
+/// Reading stops at the first error, the writer
+/// is not finished, caller should call `finish` on it.
+pub fn upconvert_stream<R, W>(
+    reader: &mut StdfReader<R>,
+    writer: &mut StdfWriter<W>,
+) -> Result<u64, StdfError>
+where
+    R: BufRead + Seek,
+    W: Write,
+{
+    if reader.get_stdf_version() != 3 {
+        return Err(StdfError {
+            code: 1,
+            msg: format!(
+                "STDF V3 expected, but STDF_VER is {}",
+                reader.get_stdf_version()
+            ),
+        });
+    }
+    let mut converter = V3Converter::new();
+    let mut record_count = 0;
+    for raw_element in reader.get_rawdata_iter() {
+        let v3_rec = StdfV3Record::from(&raw_element?);
+        for rec in converter.convert(&v3_rec) {
+            writer.write_record(&rec)?;
+            record_count += 1;
+        }
+    }
+    Ok(record_count)
+}
+
+/// Convert the STDF V3 file at `src` to a V4 file at `dst`,
+/// byte order of the source file is kept.
+///
+/// compression of both files are determined
+/// by the file extension
+pub fn upconvert_file<P, Q>(src: P, dst: Q) -> Result<u64, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = StdfReader::new(src)?;
+    let mut writer = StdfWriter::new(dst, reader.get_byte_order())?;
+    let record_count = upconvert_stream(&mut reader, &mut writer)?;
+    writer.finish()?;
+    Ok(record_count)
+}
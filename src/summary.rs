@@ -0,0 +1,975 @@
+//
+// summary.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::flags::PartFlags;
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// head number of the summary records
+/// that contain data of all heads and sites
+pub const OVERALL_HEAD: U1 = 255;
+
+/// Part counts of a head/site or a wafer,
+/// accumulated from PRR records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartCount {
+    pub part_cnt: U4,
+    pub rtst_cnt: U4,
+    pub abrt_cnt: U4,
+    pub good_cnt: U4,
+}
+
+/// Part count of a hardware or software bin,
+/// `pass_cnt` and `fail_cnt` are used for
+/// guessing the pass/fail indication of the bin
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinCount {
+    pub count: U4,
+    pub pass_cnt: U4,
+    pub fail_cnt: U4,
+}
+
+/// Execution statistics of a test,
+/// accumulated from PTR, MPR and FTR records.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCount {
+    pub test_typ: C1,
+    pub test_nam: Cn,
+    pub exec_cnt: U4,
+    pub fail_cnt: U4,
+    pub alrm_cnt: U4,
+    /// count of the valid results in min/max/sums/squares
+    pub rslt_cnt: U4,
+    pub test_min: R8,
+    pub test_max: R8,
+    pub tst_sums: R8,
+    pub tst_sqrs: R8,
+}
+
+/// Accumulate part, bin and test counts from
+/// records and generate summary records
+/// (PCR, HBR, SBR and TSR) for every head/site
+/// and all sites (head 255).
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_file::*, summary::SummaryBuilder};
+///
+/// let mut builder = SummaryBuilder::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         builder.feed(&rec);
+///     }
+/// }
+/// for pcr in builder.pcr_records() {
+///     println!("{:?}", pcr);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SummaryBuilder {
+    parts: BTreeMap<(U1, U1), PartCount>,
+    hbins: BTreeMap<(U1, U1, U2), BinCount>,
+    sbins: BTreeMap<(U1, U1, U2), BinCount>,
+    tests: BTreeMap<(U1, U1, U4), TestCount>,
+    // bin pass/fail & name from HBR/SBR, if any
+    hbin_info: HashMap<U2, (C1, Cn)>,
+    sbin_info: HashMap<U2, (C1, Cn)>,
+    // seq_name & test_lbl from TSR, if any
+    test_info: HashMap<U4, (Cn, Cn, Cn)>,
+    // summary records in the file, for cross-check
+    file_pcrs: BTreeMap<(U1, U1), PCR>,
+    file_hbrs: BTreeMap<(U1, U1, U2), HBR>,
+    file_sbrs: BTreeMap<(U1, U1, U2), SBR>,
+    file_tsrs: BTreeMap<(U1, U1, U4), TSR>,
+}
+
+/// A difference between a summary record in the
+/// file and the value recomputed from part data
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryMismatch {
+    /// REC_PCR, REC_HBR, REC_SBR or REC_TSR
+    pub rec_type: u64,
+    pub head_num: U1,
+    /// site number, always 0 if head is 255
+    pub site_num: U1,
+    /// bin number of HBR/SBR, test number of TSR, 0 for PCR
+    pub number: U4,
+    /// field name in lowercase, e.g. `hbin_cnt`
+    pub field: &'static str,
+    /// value in the file, `None` if the record is absent
+    pub in_file: Option<R8>,
+    pub computed: R8,
+}
+
+/// Where the counts of a `BinSummary` come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinSource {
+    /// HBR or SBR records in the file
+    #[default]
+    SummaryRecords,
+    /// hard_bin or soft_bin of PRR records,
+    /// used if summary records are absent
+    PartResults,
+}
+
+/// Count, pass/fail and name of a bin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinInfo {
+    pub bin_num: U2,
+    pub bin_cnt: U4,
+    /// 'P', 'F' or ' ' if unknown
+    pub bin_pf: C1,
+    pub bin_nam: Cn,
+}
+
+/// Hardware and software bins of every head/site
+/// and of all sites, merged from HBR/SBR records.
+///
+/// The overall bins are taken from the head 255 records,
+/// or summed from the head/site records if there are none.
+/// Bin counts are counted from PRR records if the file
+/// contains no HBR (or SBR) at the corresponding level.
+///
+/// Pass/fail and name of a bin are the same for all
+/// heads and sites, the first valid value in the records
+/// is used; pass/fail is guessed from `PRR.part_flg`
+/// if it's neither 'P' nor 'F' in the records.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_file::*, summary::SummaryBuilder};
+///
+/// let mut builder = SummaryBuilder::new();
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         builder.feed(&rec);
+///     }
+/// }
+/// let bins = builder.bin_summary();
+/// for bin in bins.get_overall_hbins().values().filter(|b| b.is_pass()) {
+///     println!("{} {}: {}", bin.bin_num, bin.bin_nam, bin.bin_cnt);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BinSummary {
+    hbin_source: BinSource,
+    sbin_source: BinSource,
+    site_hbins: BTreeMap<(U1, U1), BTreeMap<U2, BinInfo>>,
+    site_sbins: BTreeMap<(U1, U1), BTreeMap<U2, BinInfo>>,
+    overall_hbins: BTreeMap<U2, BinInfo>,
+    overall_sbins: BTreeMap<U2, BinInfo>,
+}
+
+impl PartCount {
+    /// count a part from PRR
+    #[inline(always)]
+    pub fn add_prr(&mut self, prr: &PRR) {
+        let flag = prr.part_flg[0];
+        self.part_cnt += 1;
+        // bit 0 or bit 1: part is retested
+        if flag & 0b0000_0011 != 0 {
+            self.rtst_cnt += 1;
+        }
+        // bit 2: abnormal end of testing
+        if flag & 0b0000_0100 != 0 {
+            self.abrt_cnt += 1;
+        }
+        if part_passed(prr) {
+            self.good_cnt += 1;
+        }
+    }
+
+    #[inline(always)]
+    fn merge(&mut self, other: &PartCount) {
+        self.part_cnt += other.part_cnt;
+        self.rtst_cnt += other.rtst_cnt;
+        self.abrt_cnt += other.abrt_cnt;
+        self.good_cnt += other.good_cnt;
+    }
+}
+
+impl BinCount {
+    #[inline(always)]
+    fn merge(&mut self, other: &BinCount) {
+        self.count += other.count;
+        self.pass_cnt += other.pass_cnt;
+        self.fail_cnt += other.fail_cnt;
+    }
+
+    /// 'P' if all parts in the bin passed,
+    /// 'F' if all failed, otherwise ' '
+    #[inline(always)]
+    pub fn pass_fail(&self) -> C1 {
+        if self.count == 0 {
+            ' '
+        } else if self.pass_cnt == self.count {
+            'P'
+        } else if self.fail_cnt == self.count {
+            'F'
+        } else {
+            ' '
+        }
+    }
+}
+
+impl TestCount {
+    #[inline(always)]
+    fn new(test_typ: C1, test_nam: &str) -> Self {
+        TestCount {
+            test_typ,
+            test_nam: test_nam.to_string(),
+            exec_cnt: 0,
+            fail_cnt: 0,
+            alrm_cnt: 0,
+            rslt_cnt: 0,
+            test_min: f64::INFINITY,
+            test_max: f64::NEG_INFINITY,
+            tst_sums: 0.0,
+            tst_sqrs: 0.0,
+        }
+    }
+
+    /// count an execution from the test flag,
+    /// return false if the test is not executed
+    #[inline(always)]
+    fn add_execution(&mut self, test_flg: B1) -> bool {
+        let flag = test_flg[0];
+        // bit 4: test not executed
+        if flag & 0b0001_0000 != 0 {
+            return false;
+        }
+        self.exec_cnt += 1;
+        // bit 0: alarm detected
+        if flag & 0b0000_0001 != 0 {
+            self.alrm_cnt += 1;
+        }
+        // bit 7: test failed, only valid if bit 6 is 0
+        if flag & 0b1100_0000 == 0b1000_0000 {
+            self.fail_cnt += 1;
+        }
+        true
+    }
+
+    #[inline(always)]
+    fn add_result(&mut self, result: R4) {
+        let result = result as f64;
+        self.rslt_cnt += 1;
+        self.test_min = self.test_min.min(result);
+        self.test_max = self.test_max.max(result);
+        self.tst_sums += result;
+        self.tst_sqrs += result * result;
+    }
+
+    #[inline(always)]
+    fn merge(&mut self, other: &TestCount) {
+        self.exec_cnt += other.exec_cnt;
+        self.fail_cnt += other.fail_cnt;
+        self.alrm_cnt += other.alrm_cnt;
+        self.rslt_cnt += other.rslt_cnt;
+        self.test_min = self.test_min.min(other.test_min);
+        self.test_max = self.test_max.max(other.test_max);
+        self.tst_sums += other.tst_sums;
+        self.tst_sqrs += other.tst_sqrs;
+    }
+}
+
+impl SummaryBuilder {
+    #[inline(always)]
+    pub fn new() -> Self {
+        SummaryBuilder::default()
+    }
+
+    /// accumulate the counts from a record,
+    /// records that are irrelevant are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::PRR(prr_rec) => self.add_prr(prr_rec),
+            StdfRecord::PTR(ptr_rec) => {
+                let test = self
+                    .tests
+                    .entry((ptr_rec.head_num, ptr_rec.site_num, ptr_rec.test_num))
+                    .or_insert_with(|| TestCount::new('P', &ptr_rec.test_txt));
+                // bit 1, 3, 4, 5: result is not valid, timeout,
+                // not executed or aborted
+                if test.add_execution(ptr_rec.test_flg) && ptr_rec.test_flg[0] & 0b0011_1010 == 0 {
+                    test.add_result(ptr_rec.result);
+                }
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                let test = self
+                    .tests
+                    .entry((mpr_rec.head_num, mpr_rec.site_num, mpr_rec.test_num))
+                    .or_insert_with(|| TestCount::new('M', &mpr_rec.test_txt));
+                if test.add_execution(mpr_rec.test_flg) && mpr_rec.test_flg[0] & 0b0011_1010 == 0 {
+                    mpr_rec.rtn_rslt.iter().for_each(|&r| test.add_result(r));
+                }
+            }
+            StdfRecord::FTR(ftr_rec) => {
+                self.tests
+                    .entry((ftr_rec.head_num, ftr_rec.site_num, ftr_rec.test_num))
+                    .or_insert_with(|| TestCount::new('F', &ftr_rec.test_txt))
+                    .add_execution(ftr_rec.test_flg);
+            }
+            StdfRecord::PCR(pcr_rec) => {
+                let (head_num, site_num) = summary_key(pcr_rec.head_num, pcr_rec.site_num);
+                self.file_pcrs
+                    .entry((head_num, site_num))
+                    .or_insert_with(|| pcr_rec.clone());
+            }
+            StdfRecord::HBR(hbr_rec) => {
+                let (head_num, site_num) = summary_key(hbr_rec.head_num, hbr_rec.site_num);
+                self.file_hbrs
+                    .entry((head_num, site_num, hbr_rec.hbin_num))
+                    .or_insert_with(|| hbr_rec.clone());
+                self.hbin_info
+                    .entry(hbr_rec.hbin_num)
+                    .or_insert_with(|| (hbr_rec.hbin_pf, hbr_rec.hbin_nam.clone()));
+            }
+            StdfRecord::SBR(sbr_rec) => {
+                let (head_num, site_num) = summary_key(sbr_rec.head_num, sbr_rec.site_num);
+                self.file_sbrs
+                    .entry((head_num, site_num, sbr_rec.sbin_num))
+                    .or_insert_with(|| sbr_rec.clone());
+                self.sbin_info
+                    .entry(sbr_rec.sbin_num)
+                    .or_insert_with(|| (sbr_rec.sbin_pf, sbr_rec.sbin_nam.clone()));
+            }
+            StdfRecord::TSR(tsr_rec) => {
+                let (head_num, site_num) = summary_key(tsr_rec.head_num, tsr_rec.site_num);
+                self.file_tsrs
+                    .entry((head_num, site_num, tsr_rec.test_num))
+                    .or_insert_with(|| tsr_rec.clone());
+                self.test_info.entry(tsr_rec.test_num).or_insert_with(|| {
+                    (
+                        tsr_rec.test_nam.clone(),
+                        tsr_rec.seq_name.clone(),
+                        tsr_rec.test_lbl.clone(),
+                    )
+                });
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    fn add_prr(&mut self, prr: &PRR) {
+        let passed = part_passed(prr);
+        let failed = part_failed(prr);
+        let add_bin = |bin: &mut BinCount| {
+            bin.count += 1;
+            if passed {
+                bin.pass_cnt += 1;
+            } else if failed {
+                bin.fail_cnt += 1;
+            }
+        };
+        self.parts
+            .entry((prr.head_num, prr.site_num))
+            .or_default()
+            .add_prr(prr);
+        add_bin(
+            self.hbins
+                .entry((prr.head_num, prr.site_num, prr.hard_bin))
+                .or_default(),
+        );
+        // 65535 indicates soft bin is not used
+        if prr.soft_bin != 65535 {
+            add_bin(
+                self.sbins
+                    .entry((prr.head_num, prr.site_num, prr.soft_bin))
+                    .or_default(),
+            );
+        }
+    }
+
+    /// part counts of every (head, site)
+    #[inline(always)]
+    pub fn get_part_counts(&self) -> &BTreeMap<(U1, U1), PartCount> {
+        &self.parts
+    }
+
+    /// hardware bin counts of every (head, site, hbin_num)
+    #[inline(always)]
+    pub fn get_hbin_counts(&self) -> &BTreeMap<(U1, U1, U2), BinCount> {
+        &self.hbins
+    }
+
+    /// software bin counts of every (head, site, sbin_num)
+    #[inline(always)]
+    pub fn get_sbin_counts(&self) -> &BTreeMap<(U1, U1, U2), BinCount> {
+        &self.sbins
+    }
+
+    /// test counts of every (head, site, test_num)
+    #[inline(always)]
+    pub fn get_test_counts(&self) -> &BTreeMap<(U1, U1, U4), TestCount> {
+        &self.tests
+    }
+
+    /// total part counts of all heads and sites
+    pub fn get_overall_part_count(&self) -> PartCount {
+        self.parts
+            .values()
+            .fold(PartCount::default(), |mut total, cnt| {
+                total.merge(cnt);
+                total
+            })
+    }
+
+    /// generate PCR of every head/site,
+    /// followed by the overall PCR (head 255)
+    pub fn pcr_records(&self) -> Vec<PCR> {
+        let mut pcr_list: Vec<PCR> = self
+            .parts
+            .iter()
+            .map(|(&(head_num, site_num), cnt)| to_pcr(head_num, site_num, cnt))
+            .collect();
+        pcr_list.push(to_pcr(OVERALL_HEAD, 0, &self.get_overall_part_count()));
+        pcr_list
+    }
+
+    /// generate HBR of every head/site,
+    /// followed by the overall HBRs (head 255)
+    pub fn hbr_records(&self) -> Vec<HBR> {
+        merge_with_overall(&self.hbins, BinCount::merge)
+            .into_iter()
+            .map(|((head_num, site_num, hbin_num), cnt)| {
+                let (hbin_pf, hbin_nam) = match self.hbin_info.get(&hbin_num) {
+                    Some((pf, name)) => (*pf, name.clone()),
+                    None => (cnt.pass_fail(), String::new()),
+                };
+                HBR {
+                    head_num,
+                    site_num,
+                    hbin_num,
+                    hbin_cnt: cnt.count,
+                    hbin_pf,
+                    hbin_nam,
+                }
+            })
+            .collect()
+    }
+
+    /// generate SBR of every head/site,
+    /// followed by the overall SBRs (head 255)
+    pub fn sbr_records(&self) -> Vec<SBR> {
+        merge_with_overall(&self.sbins, BinCount::merge)
+            .into_iter()
+            .map(|((head_num, site_num, sbin_num), cnt)| {
+                let (sbin_pf, sbin_nam) = match self.sbin_info.get(&sbin_num) {
+                    Some((pf, name)) => (*pf, name.clone()),
+                    None => (cnt.pass_fail(), String::new()),
+                };
+                SBR {
+                    head_num,
+                    site_num,
+                    sbin_num,
+                    sbin_cnt: cnt.count,
+                    sbin_pf,
+                    sbin_nam,
+                }
+            })
+            .collect()
+    }
+
+    /// generate TSR of every head/site,
+    /// followed by the overall TSRs (head 255)
+    pub fn tsr_records(&self) -> Vec<TSR> {
+        merge_with_overall(&self.tests, TestCount::merge)
+            .into_iter()
+            .map(|((head_num, site_num, test_num), cnt)| {
+                let (test_nam, seq_name, test_lbl) = match self.test_info.get(&test_num) {
+                    Some(info) => info.clone(),
+                    None => (cnt.test_nam.clone(), String::new(), String::new()),
+                };
+                // bit 2: test_tim is always invalid
+                let mut opt_flag = 0b0000_0100;
+                if cnt.rslt_cnt == 0 {
+                    // bit 0, 1, 4, 5: min, max, sums and squares are invalid
+                    opt_flag |= 0b0011_0011;
+                }
+                let valid = cnt.rslt_cnt != 0;
+                TSR {
+                    head_num,
+                    site_num,
+                    test_typ: cnt.test_typ,
+                    test_num,
+                    exec_cnt: cnt.exec_cnt,
+                    fail_cnt: cnt.fail_cnt,
+                    alrm_cnt: cnt.alrm_cnt,
+                    test_nam,
+                    seq_name,
+                    test_lbl,
+                    opt_flag: [opt_flag],
+                    test_tim: 0.0,
+                    test_min: if valid { cnt.test_min as f32 } else { 0.0 },
+                    test_max: if valid { cnt.test_max as f32 } else { 0.0 },
+                    tst_sums: cnt.tst_sums as f32,
+                    tst_sqrs: cnt.tst_sqrs as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+impl SummaryBuilder {
+    /// compare the summary records (PCR, HBR, SBR and TSR)
+    /// in the file against the recomputed counts.
+    ///
+    /// head 255 records are compared with the overall counts.
+    /// A record is reported absent only if the file contains
+    /// records of the same type for that head/site, or for
+    /// that head in case of PCR;
+    /// fields that are marked invalid in the file are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_stdf::{stdf_file::*, summary::SummaryBuilder};
+    ///
+    /// let mut builder = SummaryBuilder::new();
+    /// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+    ///     for rec in reader.get_record_iter().flatten() {
+    ///         builder.feed(&rec);
+    ///     }
+    /// }
+    /// for mismatch in builder.cross_check() {
+    ///     println!("{:?}", mismatch);
+    /// }
+    /// ```
+    pub fn cross_check(&self) -> Vec<SummaryMismatch> {
+        let mut mismatches = vec![];
+
+        // PCR
+        let mut computed_parts: Vec<((U1, U1), PartCount)> =
+            self.parts.iter().map(|(k, v)| (*k, *v)).collect();
+        computed_parts.push(((OVERALL_HEAD, 0), self.get_overall_part_count()));
+        let mut check = Checker::new(REC_PCR, &mut mismatches);
+        for (key, pcr) in self.file_pcrs.iter() {
+            let cnt = self.parts.get(key).copied().unwrap_or_else(|| {
+                if key.0 == OVERALL_HEAD {
+                    self.get_overall_part_count()
+                } else {
+                    PartCount::default()
+                }
+            });
+            check.key(key.0, key.1, 0);
+            check.count("part_cnt", pcr.part_cnt, cnt.part_cnt);
+            check.count("rtst_cnt", pcr.rtst_cnt, cnt.rtst_cnt);
+            check.count("abrt_cnt", pcr.abrt_cnt, cnt.abrt_cnt);
+            check.count("good_cnt", pcr.good_cnt, cnt.good_cnt);
+        }
+        let file_heads: BTreeSet<U1> = self.file_pcrs.keys().map(|k| k.0).collect();
+        for (key, cnt) in computed_parts {
+            if !self.file_pcrs.contains_key(&key) && file_heads.contains(&key.0) {
+                check.key(key.0, key.1, 0);
+                check.absent("part_cnt", cnt.part_cnt);
+            }
+        }
+
+        // HBR & SBR
+        for (rec_type, field, bins) in [
+            (REC_HBR, "hbin_cnt", &self.hbins),
+            (REC_SBR, "sbin_cnt", &self.sbins),
+        ] {
+            let file_bins: BTreeMap<(U1, U1, U2), U4> = if rec_type == REC_HBR {
+                self.file_hbrs
+                    .iter()
+                    .map(|(k, v)| (*k, v.hbin_cnt))
+                    .collect()
+            } else {
+                self.file_sbrs
+                    .iter()
+                    .map(|(k, v)| (*k, v.sbin_cnt))
+                    .collect()
+            };
+            let computed: BTreeMap<(U1, U1, U2), BinCount> =
+                merge_with_overall(bins, BinCount::merge)
+                    .into_iter()
+                    .collect();
+            let file_sites: BTreeSet<(U1, U1)> = file_bins.keys().map(|k| (k.0, k.1)).collect();
+            let mut check = Checker::new(rec_type, &mut mismatches);
+            for (key, &file_cnt) in file_bins.iter() {
+                let cnt = computed.get(key).map_or(0, |c| c.count);
+                check.key(key.0, key.1, key.2 as U4);
+                check.count(field, file_cnt, cnt);
+            }
+            for (key, cnt) in computed.iter() {
+                if !file_bins.contains_key(key) && file_sites.contains(&(key.0, key.1)) {
+                    check.key(key.0, key.1, key.2 as U4);
+                    check.absent(field, cnt.count);
+                }
+            }
+        }
+
+        // TSR
+        let computed: BTreeMap<(U1, U1, U4), TestCount> =
+            merge_with_overall(&self.tests, TestCount::merge)
+                .into_iter()
+                .collect();
+        let file_sites: BTreeSet<(U1, U1)> = self.file_tsrs.keys().map(|k| (k.0, k.1)).collect();
+        let mut check = Checker::new(REC_TSR, &mut mismatches);
+        for (key, tsr) in self.file_tsrs.iter() {
+            check.key(key.0, key.1, key.2);
+            let cnt = match computed.get(key) {
+                Some(cnt) => cnt.clone(),
+                None => TestCount::new(tsr.test_typ, &tsr.test_nam),
+            };
+            check.count("exec_cnt", tsr.exec_cnt, cnt.exec_cnt);
+            check.count("fail_cnt", tsr.fail_cnt, cnt.fail_cnt);
+            check.count("alrm_cnt", tsr.alrm_cnt, cnt.alrm_cnt);
+            if cnt.rslt_cnt == 0 {
+                continue;
+            }
+            let opt_flag = tsr.opt_flag[0];
+            // bit 0, 1, 4, 5: min, max, sums and squares are invalid
+            if opt_flag & 0b0000_0001 == 0 {
+                check.value("test_min", tsr.test_min, cnt.test_min);
+            }
+            if opt_flag & 0b0000_0010 == 0 {
+                check.value("test_max", tsr.test_max, cnt.test_max);
+            }
+            if opt_flag & 0b0001_0000 == 0 {
+                check.value("tst_sums", tsr.tst_sums, cnt.tst_sums);
+            }
+            if opt_flag & 0b0010_0000 == 0 {
+                check.value("tst_sqrs", tsr.tst_sqrs, cnt.tst_sqrs);
+            }
+        }
+        for (key, cnt) in computed.iter() {
+            if !self.file_tsrs.contains_key(key) && file_sites.contains(&(key.0, key.1)) {
+                check.key(key.0, key.1, key.2);
+                check.absent("exec_cnt", cnt.exec_cnt);
+            }
+        }
+        mismatches
+    }
+}
+
+impl BinInfo {
+    #[inline(always)]
+    pub fn is_pass(&self) -> bool {
+        self.bin_pf == 'P'
+    }
+
+    #[inline(always)]
+    pub fn is_fail(&self) -> bool {
+        self.bin_pf == 'F'
+    }
+}
+
+impl BinSummary {
+    /// source of the overall hardware bin counts
+    #[inline(always)]
+    pub fn hbin_source(&self) -> BinSource {
+        self.hbin_source
+    }
+
+    /// source of the overall software bin counts
+    #[inline(always)]
+    pub fn sbin_source(&self) -> BinSource {
+        self.sbin_source
+    }
+
+    /// (head, site) that have bin counts,
+    /// the overall head 255 is not included
+    pub fn get_sites(&self) -> Vec<(U1, U1)> {
+        let sites: BTreeSet<(U1, U1)> = self
+            .site_hbins
+            .keys()
+            .chain(self.site_sbins.keys())
+            .copied()
+            .collect();
+        sites.into_iter().collect()
+    }
+
+    /// hardware bins of all sites
+    #[inline(always)]
+    pub fn get_overall_hbins(&self) -> &BTreeMap<U2, BinInfo> {
+        &self.overall_hbins
+    }
+
+    /// software bins of all sites
+    #[inline(always)]
+    pub fn get_overall_sbins(&self) -> &BTreeMap<U2, BinInfo> {
+        &self.overall_sbins
+    }
+
+    /// hardware bins of a head/site
+    #[inline(always)]
+    pub fn get_site_hbins(&self, head_num: U1, site_num: U1) -> Option<&BTreeMap<U2, BinInfo>> {
+        self.site_hbins.get(&(head_num, site_num))
+    }
+
+    /// software bins of a head/site
+    #[inline(always)]
+    pub fn get_site_sbins(&self, head_num: U1, site_num: U1) -> Option<&BTreeMap<U2, BinInfo>> {
+        self.site_sbins.get(&(head_num, site_num))
+    }
+
+    /// overall hardware bin of `hbin_num`
+    #[inline(always)]
+    pub fn get_hbin(&self, hbin_num: U2) -> Option<&BinInfo> {
+        self.overall_hbins.get(&hbin_num)
+    }
+
+    /// overall software bin of `sbin_num`
+    #[inline(always)]
+    pub fn get_sbin(&self, sbin_num: U2) -> Option<&BinInfo> {
+        self.overall_sbins.get(&sbin_num)
+    }
+}
+
+impl SummaryBuilder {
+    /// merge the HBR/SBR records in the file into
+    /// a `BinSummary`, counts are from PRR records if
+    /// HBR/SBR records are absent.
+    pub fn bin_summary(&self) -> BinSummary {
+        let file_hbins: BTreeMap<(U1, U1, U2), (U4, C1, &str)> = self
+            .file_hbrs
+            .iter()
+            .map(|(k, v)| (*k, (v.hbin_cnt, v.hbin_pf, v.hbin_nam.as_str())))
+            .collect();
+        let file_sbins: BTreeMap<(U1, U1, U2), (U4, C1, &str)> = self
+            .file_sbrs
+            .iter()
+            .map(|(k, v)| (*k, (v.sbin_cnt, v.sbin_pf, v.sbin_nam.as_str())))
+            .collect();
+        let (hbin_source, site_hbins, overall_hbins) = merge_bins(&file_hbins, &self.hbins);
+        let (sbin_source, site_sbins, overall_sbins) = merge_bins(&file_sbins, &self.sbins);
+        BinSummary {
+            hbin_source,
+            sbin_source,
+            site_hbins,
+            site_sbins,
+            overall_hbins,
+            overall_sbins,
+        }
+    }
+}
+
+/// helper for collecting the mismatches
+/// of a summary record type
+struct Checker<'a> {
+    rec_type: u64,
+    key: (U1, U1, U4),
+    mismatches: &'a mut Vec<SummaryMismatch>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(rec_type: u64, mismatches: &'a mut Vec<SummaryMismatch>) -> Self {
+        Checker {
+            rec_type,
+            key: (0, 0, 0),
+            mismatches,
+        }
+    }
+
+    #[inline(always)]
+    fn key(&mut self, head_num: U1, site_num: U1, number: U4) {
+        self.key = (head_num, site_num, number);
+    }
+
+    #[inline(always)]
+    fn push(&mut self, field: &'static str, in_file: Option<R8>, computed: R8) {
+        self.mismatches.push(SummaryMismatch {
+            rec_type: self.rec_type,
+            head_num: self.key.0,
+            site_num: self.key.1,
+            number: self.key.2,
+            field,
+            in_file,
+            computed,
+        });
+    }
+
+    /// 4,294,967,295 in the file indicates missing data
+    #[inline(always)]
+    fn count(&mut self, field: &'static str, in_file: U4, computed: U4) {
+        if in_file != 4_294_967_295 && in_file != computed {
+            self.push(field, Some(in_file as R8), computed as R8);
+        }
+    }
+
+    /// compare with a relative tolerance, since
+    /// values in the file are single precision
+    #[inline(always)]
+    fn value(&mut self, field: &'static str, in_file: R4, computed: R8) {
+        let in_file = in_file as R8;
+        let tolerance = 1e-4 * in_file.abs().max(computed.abs()).max(1.0);
+        if (in_file - computed).abs() > tolerance {
+            self.push(field, Some(in_file), computed);
+        }
+    }
+
+    #[inline(always)]
+    fn absent(&mut self, field: &'static str, computed: U4) {
+        self.push(field, None, computed as R8);
+    }
+}
+
+/// head/site key of the summary records,
+/// site is ignored if head is 255
+#[inline(always)]
+fn summary_key(head_num: U1, site_num: U1) -> (U1, U1) {
+    if head_num == OVERALL_HEAD {
+        (OVERALL_HEAD, 0)
+    } else {
+        (head_num, site_num)
+    }
+}
+
+/// returns true if the part is passed,
+/// according to bit 3 & 4 of `PRR.part_flg`
+#[inline(always)]
+pub(crate) fn part_passed(prr: &PRR) -> bool {
+    prr.passed() == Some(true)
+}
+
+/// returns true if the part is failed,
+/// according to bit 3 & 4 of `PRR.part_flg`
+#[inline(always)]
+pub(crate) fn part_failed(prr: &PRR) -> bool {
+    PartFlags::from(prr.part_flg).is_fail()
+}
+
+#[inline(always)]
+fn to_pcr(head_num: U1, site_num: U1, cnt: &PartCount) -> PCR {
+    PCR {
+        head_num,
+        site_num,
+        part_cnt: cnt.part_cnt,
+        rtst_cnt: cnt.rtst_cnt,
+        abrt_cnt: cnt.abrt_cnt,
+        good_cnt: cnt.good_cnt,
+        func_cnt: 4_294_967_295,
+    }
+}
+
+/// merge bins from summary records (count, pf, name)
+/// and bins counted from PRR, see `BinSummary`
+#[allow(clippy::type_complexity)]
+fn merge_bins(
+    file_bins: &BTreeMap<(U1, U1, U2), (U4, C1, &str)>,
+    part_bins: &BTreeMap<(U1, U1, U2), BinCount>,
+) -> (
+    BinSource,
+    BTreeMap<(U1, U1), BTreeMap<U2, BinInfo>>,
+    BTreeMap<U2, BinInfo>,
+) {
+    // pass/fail and name of every bin
+    let mut bin_defs: BTreeMap<U2, (C1, &str)> = BTreeMap::new();
+    for (&(_, _, bin_num), &(_, pf, name)) in file_bins.iter() {
+        let def = bin_defs.entry(bin_num).or_insert((' ', ""));
+        if def.0 != 'P' && def.0 != 'F' && (pf == 'P' || pf == 'F') {
+            def.0 = pf;
+        }
+        if def.1.is_empty() {
+            def.1 = name;
+        }
+    }
+    let part_overall: BTreeMap<U2, BinCount> = merge_with_overall(part_bins, BinCount::merge)
+        .into_iter()
+        .filter(|((head_num, _, _), _)| *head_num == OVERALL_HEAD)
+        .map(|((_, _, bin_num), cnt)| (bin_num, cnt))
+        .collect();
+    let to_info = |bin_num: U2, bin_cnt: U4| {
+        let (mut bin_pf, bin_nam) = bin_defs.get(&bin_num).copied().unwrap_or((' ', ""));
+        if bin_pf != 'P' && bin_pf != 'F' {
+            bin_pf = part_overall
+                .get(&bin_num)
+                .map_or(' ', |cnt| cnt.pass_fail());
+        }
+        BinInfo {
+            bin_num,
+            bin_cnt,
+            bin_pf,
+            bin_nam: bin_nam.to_string(),
+        }
+    };
+
+    // head/site bins
+    let mut site_bins: BTreeMap<(U1, U1), BTreeMap<U2, BinInfo>> = BTreeMap::new();
+    let has_site_records = file_bins.keys().any(|k| k.0 != OVERALL_HEAD);
+    if has_site_records {
+        for (&(head_num, site_num, bin_num), &(cnt, _, _)) in file_bins.iter() {
+            if head_num != OVERALL_HEAD {
+                site_bins
+                    .entry((head_num, site_num))
+                    .or_default()
+                    .insert(bin_num, to_info(bin_num, cnt));
+            }
+        }
+    } else {
+        for (&(head_num, site_num, bin_num), cnt) in part_bins.iter() {
+            site_bins
+                .entry((head_num, site_num))
+                .or_default()
+                .insert(bin_num, to_info(bin_num, cnt.count));
+        }
+    }
+
+    // overall bins
+    let overall_records: BTreeMap<U2, U4> = file_bins
+        .iter()
+        .filter(|(k, _)| k.0 == OVERALL_HEAD)
+        .map(|(k, v)| (k.2, v.0))
+        .collect();
+    let (source, overall_counts) = if !overall_records.is_empty() {
+        (BinSource::SummaryRecords, overall_records)
+    } else if has_site_records {
+        let mut counts: BTreeMap<U2, U4> = BTreeMap::new();
+        for bins in site_bins.values() {
+            for (&bin_num, info) in bins.iter() {
+                let total = counts.entry(bin_num).or_default();
+                *total = total.saturating_add(info.bin_cnt);
+            }
+        }
+        (BinSource::SummaryRecords, counts)
+    } else {
+        let counts = part_overall
+            .iter()
+            .map(|(&bin_num, cnt)| (bin_num, cnt.count))
+            .collect();
+        (BinSource::PartResults, counts)
+    };
+    let overall = overall_counts
+        .into_iter()
+        .map(|(bin_num, cnt)| (bin_num, to_info(bin_num, cnt)))
+        .collect();
+    (source, site_bins, overall)
+}
+
+/// append the sum of all (head, site) to the
+/// end of the list, where head = 255 and site = 0
+fn merge_with_overall<K: Copy + Ord, T: Clone>(
+    counts: &BTreeMap<(U1, U1, K), T>,
+    merge: fn(&mut T, &T),
+) -> Vec<((U1, U1, K), T)> {
+    let mut overall: BTreeMap<K, T> = BTreeMap::new();
+    let mut merged = Vec::with_capacity(counts.len());
+    for (&(head_num, site_num, key), cnt) in counts.iter() {
+        merged.push(((head_num, site_num, key), cnt.clone()));
+        overall
+            .entry(key)
+            .and_modify(|total| merge(total, cnt))
+            .or_insert_with(|| cnt.clone());
+    }
+    merged.extend(
+        overall
+            .into_iter()
+            .map(|(key, cnt)| ((OVERALL_HEAD, 0, key), cnt)),
+    );
+    merged
+}
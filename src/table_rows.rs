@@ -0,0 +1,392 @@
+//
+// table_rows.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::flags::PtrTestFlags;
+use crate::stats::LimitTracker;
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use std::collections::{HashMap, HashSet};
+
+/// A test result, one per PTR, FTR or pin of MPR
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultRow {
+    /// index of the part in the order of PIR
+    pub part_index: u64,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub test_num: U4,
+    /// 'P', 'M' or 'F'
+    pub test_typ: C1,
+    /// PMR index of the MPR pin
+    pub pmr_indx: Option<U2>,
+    /// `None` if not valid, always `None` for FTR
+    pub result: Option<R4>,
+    pub passed: Option<bool>,
+    pub test_flg: U1,
+}
+
+/// A part, one per PRR
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartRow {
+    pub part_index: u64,
+    /// `LOT_ID` of MIR
+    pub lot_id: Cn,
+    /// `WAFER_ID` of the open WIR of the head
+    pub wafer_id: Option<Cn>,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub part_id: Cn,
+    pub x_coord: Option<I2>,
+    pub y_coord: Option<I2>,
+    pub hard_bin: U2,
+    pub soft_bin: Option<U2>,
+    pub num_test: U2,
+    pub test_t: U4,
+    pub passed: Option<bool>,
+}
+
+/// A test definition, from the first PTR/MPR/FTR
+/// of the test number and test type
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestRow {
+    pub test_num: U4,
+    pub test_typ: C1,
+    pub test_txt: Cn,
+    pub units: Cn,
+    pub res_scal: I1,
+    pub lo_limit: Option<R4>,
+    pub hi_limit: Option<R4>,
+}
+
+/// A bin, one per HBR and SBR
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinRow {
+    /// 'H' or 'S'
+    pub bin_typ: C1,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub bin_num: U2,
+    pub bin_cnt: U4,
+    pub bin_pf: Option<C1>,
+    pub bin_nam: Cn,
+}
+
+/// A wafer, one per WRR
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaferRow {
+    pub head_num: U1,
+    pub wafer_id: Cn,
+    /// `START_T` of the WIR
+    pub start_t: Option<U4>,
+    pub finish_t: U4,
+    pub part_cnt: U4,
+    pub rtst_cnt: Option<U4>,
+    pub abrt_cnt: Option<U4>,
+    pub good_cnt: Option<U4>,
+    pub func_cnt: Option<U4>,
+}
+
+/// A row of one of the tables
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableRow {
+    Result(ResultRow),
+    Part(PartRow),
+    Test(TestRow),
+    Bin(BinRow),
+    Wafer(WaferRow),
+}
+
+/// Normalize STDF records into rows of the
+/// results, parts, tests, bins and wafers tables,
+/// shared by the table exporters.
+///
+/// Parts are numbered by `part_index` in the order of PIR,
+/// results of the part share the same index. Results that
+/// are not valid are `None`, tests that are not executed
+/// are skipped. Optional fields with their missing values,
+/// e.g. `X_COORD` -32768, are `None`.
+///
+/// `RTN_INDX` of MPR is taken from the record, or from the
+/// first MPR of the test if it is omitted.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{table_rows::*, PIR, PRR, PTR, StdfRecord};
+///
+/// let mut normalizer = RowNormalizer::new();
+/// let mut rows = vec![];
+/// for rec in [
+///     StdfRecord::PIR(PIR::default()),
+///     StdfRecord::PTR(PTR {
+///         test_num: 100,
+///         result: 1.5,
+///         ..Default::default()
+///     }),
+///     StdfRecord::PRR(PRR::default()),
+/// ] {
+///     normalizer.feed(&rec, &mut rows);
+/// }
+/// // test, result and part
+/// assert_eq!(rows.len(), 3);
+/// if let TableRow::Result(result) = &rows[1] {
+///     assert_eq!(result.result, Some(1.5));
+/// }
+/// if let TableRow::Part(part) = &rows[2] {
+///     assert_eq!(part.soft_bin, None);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct RowNormalizer {
+    limits: LimitTracker,
+    lot_id: Cn,
+    part_count: u64,
+    open_parts: HashMap<(U1, U1), u64>,
+    open_wafers: HashMap<U1, (Cn, U4)>,
+    mpr_indexes: HashMap<U4, KxU2>,
+    test_keys: HashSet<(U4, C1)>,
+}
+
+impl RowNormalizer {
+    /// record types that produce rows or affect them
+    pub const REC_TYPES: u64 = REC_MIR
+        | REC_WIR
+        | REC_WRR
+        | REC_PIR
+        | REC_PRR
+        | REC_PTR
+        | REC_MPR
+        | REC_FTR
+        | REC_HBR
+        | REC_SBR;
+
+    pub fn new() -> Self {
+        RowNormalizer::default()
+    }
+
+    /// collect MIR, WIR, WRR, PIR, PRR, PTR, MPR, FTR,
+    /// HBR and SBR, and append the rows to `rows`,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord, rows: &mut Vec<TableRow>) {
+        match rec {
+            StdfRecord::MIR(mir_rec) => self.lot_id = mir_rec.lot_id.clone(),
+            StdfRecord::WIR(wir_rec) => {
+                self.open_wafers.insert(
+                    wir_rec.head_num,
+                    (wir_rec.wafer_id.clone(), wir_rec.start_t),
+                );
+            }
+            StdfRecord::WRR(wrr_rec) => rows.push(TableRow::Wafer(self.wafer_row(wrr_rec))),
+            StdfRecord::PIR(pir_rec) => {
+                self.open_parts
+                    .insert((pir_rec.head_num, pir_rec.site_num), self.part_count);
+                self.part_count += 1;
+            }
+            StdfRecord::PRR(prr_rec) => rows.push(TableRow::Part(self.part_row(prr_rec))),
+            StdfRecord::PTR(ptr_rec) => {
+                let limits = self.limits.resolve_ptr(ptr_rec);
+                self.push_test(
+                    rows,
+                    TestRow {
+                        test_num: ptr_rec.test_num,
+                        test_typ: 'P',
+                        test_txt: ptr_rec.test_txt.clone(),
+                        units: limits.units,
+                        res_scal: limits.res_scal,
+                        lo_limit: limits.lo_limit,
+                        hi_limit: limits.hi_limit,
+                    },
+                );
+                self.push_result(
+                    rows,
+                    (ptr_rec.head_num, ptr_rec.site_num),
+                    ptr_rec.test_num,
+                    'P',
+                    ptr_rec.test_flg,
+                    None,
+                    Some(ptr_rec.result),
+                );
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                let limits = self.limits.resolve_mpr(mpr_rec);
+                self.push_test(
+                    rows,
+                    TestRow {
+                        test_num: mpr_rec.test_num,
+                        test_typ: 'M',
+                        test_txt: mpr_rec.test_txt.clone(),
+                        units: limits.units,
+                        res_scal: limits.res_scal,
+                        lo_limit: limits.lo_limit,
+                        hi_limit: limits.hi_limit,
+                    },
+                );
+                // RTN_INDX may be omitted after the first MPR,
+                // every site has its own indexes otherwise
+                let rtn_indx = match &mpr_rec.rtn_indx {
+                    Some(rtn_indx) if !rtn_indx.is_empty() => {
+                        self.mpr_indexes
+                            .entry(mpr_rec.test_num)
+                            .or_insert_with(|| rtn_indx.clone());
+                        rtn_indx.clone()
+                    }
+                    _ => self
+                        .mpr_indexes
+                        .get(&mpr_rec.test_num)
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                for (pos, &result) in mpr_rec.rtn_rslt.iter().enumerate() {
+                    self.push_result(
+                        rows,
+                        (mpr_rec.head_num, mpr_rec.site_num),
+                        mpr_rec.test_num,
+                        'M',
+                        mpr_rec.test_flg,
+                        rtn_indx.get(pos).copied(),
+                        Some(result),
+                    );
+                }
+            }
+            StdfRecord::FTR(ftr_rec) => {
+                self.push_test(
+                    rows,
+                    TestRow {
+                        test_num: ftr_rec.test_num,
+                        test_typ: 'F',
+                        test_txt: ftr_rec.test_txt.clone(),
+                        units: String::new(),
+                        res_scal: 0,
+                        lo_limit: None,
+                        hi_limit: None,
+                    },
+                );
+                self.push_result(
+                    rows,
+                    (ftr_rec.head_num, ftr_rec.site_num),
+                    ftr_rec.test_num,
+                    'F',
+                    ftr_rec.test_flg,
+                    None,
+                    None,
+                );
+            }
+            StdfRecord::HBR(hbr_rec) => rows.push(TableRow::Bin(BinRow {
+                bin_typ: 'H',
+                head_num: hbr_rec.head_num,
+                site_num: hbr_rec.site_num,
+                bin_num: hbr_rec.hbin_num,
+                bin_cnt: hbr_rec.hbin_cnt,
+                bin_pf: Some(hbr_rec.hbin_pf).filter(|&pf| pf != ' '),
+                bin_nam: hbr_rec.hbin_nam.clone(),
+            })),
+            StdfRecord::SBR(sbr_rec) => rows.push(TableRow::Bin(BinRow {
+                bin_typ: 'S',
+                head_num: sbr_rec.head_num,
+                site_num: sbr_rec.site_num,
+                bin_num: sbr_rec.sbin_num,
+                bin_cnt: sbr_rec.sbin_cnt,
+                bin_pf: Some(sbr_rec.sbin_pf).filter(|&pf| pf != ' '),
+                bin_nam: sbr_rec.sbin_nam.clone(),
+            })),
+            _ => {}
+        }
+    }
+
+    /// push the test row if it's the first of the test
+    fn push_test(&mut self, rows: &mut Vec<TableRow>, test: TestRow) {
+        if self.test_keys.insert((test.test_num, test.test_typ)) {
+            rows.push(TableRow::Test(test));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_result(
+        &mut self,
+        rows: &mut Vec<TableRow>,
+        site: (U1, U1),
+        test_num: U4,
+        test_typ: C1,
+        test_flg: B1,
+        pmr_indx: Option<U2>,
+        result: Option<R4>,
+    ) {
+        let flags = PtrTestFlags::from(test_flg);
+        if flags.not_executed() {
+            return;
+        }
+        let part_index = match self.open_parts.get(&site) {
+            Some(&index) => index,
+            // results without PIR
+            None => {
+                self.open_parts.insert(site, self.part_count);
+                self.part_count += 1;
+                self.part_count - 1
+            }
+        };
+        rows.push(TableRow::Result(ResultRow {
+            part_index,
+            head_num: site.0,
+            site_num: site.1,
+            test_num,
+            test_typ,
+            pmr_indx,
+            result: result.filter(|_| flags.is_valid()),
+            passed: flags.passed(),
+            test_flg: test_flg[0],
+        }));
+    }
+
+    fn part_row(&mut self, prr: &PRR) -> PartRow {
+        let part_index = match self.open_parts.remove(&(prr.head_num, prr.site_num)) {
+            Some(index) => index,
+            None => {
+                self.part_count += 1;
+                self.part_count - 1
+            }
+        };
+        PartRow {
+            part_index,
+            lot_id: self.lot_id.clone(),
+            wafer_id: self.open_wafers.get(&prr.head_num).map(|w| w.0.clone()),
+            head_num: prr.head_num,
+            site_num: prr.site_num,
+            part_id: prr.part_id.clone(),
+            x_coord: Some(prr.x_coord).filter(|&x| x != -32768),
+            y_coord: Some(prr.y_coord).filter(|&y| y != -32768),
+            hard_bin: prr.hard_bin,
+            soft_bin: Some(prr.soft_bin).filter(|&b| b != 65535),
+            num_test: prr.num_test,
+            test_t: prr.test_t,
+            passed: prr.passed(),
+        }
+    }
+
+    fn wafer_row(&mut self, wrr: &WRR) -> WaferRow {
+        let start_t = self
+            .open_wafers
+            .remove(&wrr.head_num)
+            .map(|(_, start_t)| start_t);
+        // 4,294,967,295 indicates missing data
+        let valid = |cnt: U4| Some(cnt).filter(|&c| c != u32::MAX);
+        WaferRow {
+            head_num: wrr.head_num,
+            wafer_id: wrr.wafer_id.clone(),
+            start_t,
+            finish_t: wrr.finish_t,
+            part_cnt: wrr.part_cnt,
+            rtst_cnt: valid(wrr.rtst_cnt),
+            abrt_cnt: valid(wrr.abrt_cnt),
+            good_cnt: valid(wrr.good_cnt),
+            func_cnt: valid(wrr.func_cnt),
+        }
+    }
+}
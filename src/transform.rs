@@ -0,0 +1,268 @@
+//
+// transform.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::{StdfReader, StdfWriter};
+use crate::stdf_types::*;
+use smart_default::SmartDefault;
+use std::io::{BufRead, Seek, Write};
+use std::path::Path;
+
+/// What to do with a record after a step
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// pass the (modified) record to the next step
+    Keep,
+    /// remove the record
+    Drop,
+    /// replace the record by a list of records,
+    /// which are passed to the next step in order.
+    ///
+    /// use `Replace(vec![new_rec, rec.clone()])` to
+    /// insert a record before the current one
+    Replace(Vec<StdfRecord>),
+}
+
+/// Options of `Transform`
+#[derive(SmartDefault, Debug, Clone)]
+pub struct TransformOptions {
+    /// byte order of the output file in `run_file`,
+    /// `None` to keep the byte order of the source file
+    pub byte_order: Option<ByteOrder>,
+}
+
+/// Result of `Transform::run_stream` or `Transform::run_file`
+#[derive(Debug, Default)]
+pub struct TransformReport {
+    /// count of the records read from the source
+    pub read_count: u64,
+    /// count of the records written to the output
+    pub write_count: u64,
+}
+
+type StepFn<'a> = Box<dyn FnMut(&mut StdfRecord) -> Action + 'a>;
+
+struct Step<'a> {
+    rec_types: u64,
+    func: StepFn<'a>,
+}
+
+/// A STDF -> STDF pipeline of user closures
+///
+/// Every step is registered with the record types
+/// it is interested in, a record is passed to the steps
+/// in the order they are added, and the step can keep,
+/// modify, drop or replace it.
+///
+/// Records that are not handled by any step are copied
+/// without parsing, the record length of the modified
+/// records is updated automatically. `CPU_TYPE` of FAR is
+/// updated to the output byte order.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_record_type::*, transform::*, StdfRecord};
+///
+/// let mut transform = Transform::new()
+///     // strip the noise
+///     .drop_types(REC_DTR | REC_GDR)
+///     // rename a test
+///     .modify(REC_PTR, |rec| {
+///         if let StdfRecord::PTR(ptr_rec) = rec {
+///             if ptr_rec.test_num == 100 {
+///                 ptr_rec.test_txt = "VDD_LEAKAGE".to_string();
+///             }
+///         }
+///     });
+///
+/// let mut ptr = StdfRecord::new(REC_PTR);
+/// if let StdfRecord::PTR(ref mut ptr_rec) = ptr {
+///     ptr_rec.test_num = 100;
+/// }
+/// let recs = transform.apply(ptr);
+/// assert!(matches!(&recs[0], StdfRecord::PTR(p) if p.test_txt == "VDD_LEAKAGE"));
+/// assert!(transform.apply(StdfRecord::new(REC_DTR)).is_empty());
+///
+/// // or run it on files
+/// // transform.run_file("input.stdf", "output.stdf").unwrap();
+/// ```
+#[derive(Default)]
+pub struct Transform<'a> {
+    options: TransformOptions,
+    steps: Vec<Step<'a>>,
+}
+
+impl<'a> Transform<'a> {
+    pub fn new() -> Self {
+        Transform::default()
+    }
+
+    pub fn with_options(options: TransformOptions) -> Self {
+        Transform {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// add a step that is applied to the records
+    /// of `rec_types`, e.g. `REC_PTR | REC_MPR`
+    pub fn step<F>(mut self, rec_types: u64, func: F) -> Self
+    where
+        F: FnMut(&mut StdfRecord) -> Action + 'a,
+    {
+        self.steps.push(Step {
+            rec_types,
+            func: Box::new(func),
+        });
+        self
+    }
+
+    /// add a step that modifies the records of `rec_types` in place
+    pub fn modify<F>(self, rec_types: u64, mut func: F) -> Self
+    where
+        F: FnMut(&mut StdfRecord) + 'a,
+    {
+        self.step(rec_types, move |rec| {
+            func(rec);
+            Action::Keep
+        })
+    }
+
+    /// add a step that keeps the records of `rec_types`
+    /// only if `predicate` returns true
+    pub fn filter<F>(self, rec_types: u64, mut predicate: F) -> Self
+    where
+        F: FnMut(&StdfRecord) -> bool + 'a,
+    {
+        self.step(rec_types, move |rec| {
+            if predicate(rec) {
+                Action::Keep
+            } else {
+                Action::Drop
+            }
+        })
+    }
+
+    /// add a step that drops all the records of `rec_types`
+    pub fn drop_types(self, rec_types: u64) -> Self {
+        self.step(rec_types, |_| Action::Drop)
+    }
+
+    /// return true if any step handles the record type
+    #[inline(always)]
+    pub fn handles(&self, rec_type: u64) -> bool {
+        self.steps.iter().any(|s| s.rec_types & rec_type != 0)
+    }
+
+    /// pass a record through all the steps,
+    /// return the records to be written
+    pub fn apply(&mut self, rec: StdfRecord) -> Vec<StdfRecord> {
+        let mut rec_list = vec![rec];
+        for step in self.steps.iter_mut() {
+            let mut next_list = Vec::with_capacity(rec_list.len());
+            for mut rec in rec_list {
+                if !rec.is_type(step.rec_types) {
+                    next_list.push(rec);
+                    continue;
+                }
+                match (step.func)(&mut rec) {
+                    Action::Keep => next_list.push(rec),
+                    Action::Drop => {}
+                    Action::Replace(new_recs) => next_list.extend(new_recs),
+                }
+            }
+            rec_list = next_list;
+        }
+        rec_list
+    }
+
+    /// transform the records from `reader` and write to `writer`.
+    ///
+    /// Reading stops at the first error, the writer
+    /// is not finished, caller should call `finish` on it.
+    pub fn run_stream<R, W>(
+        &mut self,
+        reader: &mut StdfReader<R>,
+        writer: &mut StdfWriter<W>,
+    ) -> Result<TransformReport, StdfError>
+    where
+        R: BufRead + Seek,
+        W: Write,
+    {
+        let mut report = TransformReport::default();
+        for raw_element in reader.get_rawdata_iter() {
+            let raw_element = raw_element?;
+            report.read_count += 1;
+            if !self.handles(raw_element.header.get_type()) {
+                writer.write_raw_data(&raw_element)?;
+                report.write_count += 1;
+                continue;
+            }
+            for rec in self.apply(StdfRecord::from(&raw_element)) {
+                writer.write_record(&rec)?;
+                report.write_count += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// transform the STDF file at `src` and write to `dst`,
+    /// compression of both files are determined
+    /// by the file extension
+    pub fn run_file<P, Q>(&mut self, src: P, dst: Q) -> Result<TransformReport, StdfError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let mut reader = StdfReader::new(src)?;
+        let order = self
+            .options
+            .byte_order
+            .unwrap_or_else(|| reader.get_byte_order());
+        let mut writer = StdfWriter::new(dst, order)?;
+        let report = self.run_stream(&mut reader, &mut writer)?;
+        writer.finish()?;
+        Ok(report)
+    }
+}
+
+/// Rewrite the STDF file at `src` in the byte order
+/// of `order` to `dst`, `CPU_TYPE` of FAR is updated
+/// accordingly, e.g. for converting the big endian files
+/// of Sun/SPARC testers to little endian.
+///
+/// compression of both files are determined
+/// by the file extension
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{transform::convert_byte_order, ByteOrder};
+///
+/// if let Ok(report) = convert_byte_order("sun.stdf", "pc.stdf", ByteOrder::LittleEndian) {
+///     println!("{} records converted", report.write_count);
+/// }
+/// ```
+pub fn convert_byte_order<P, Q>(
+    src: P,
+    dst: Q,
+    order: ByteOrder,
+) -> Result<TransformReport, StdfError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    Transform::with_options(TransformOptions {
+        byte_order: Some(order),
+    })
+    .run_file(src, dst)
+}
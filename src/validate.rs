@@ -0,0 +1,531 @@
+//
+// validate.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_error::StdfError;
+use crate::stdf_file::StdfReader;
+use crate::stdf_record_type::*;
+use crate::stdf_types::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{BufRead, Seek};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Category of the violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// the first record is not FAR, or FAR is repeated
+    FarNotFirst,
+    /// MIR is absent, or not right after FAR, ATRs and VURs
+    MirMisplaced,
+    /// MRR is absent
+    MissingMrr,
+    /// records found after MRR
+    RecordAfterMrr,
+    /// PIR is not closed by a PRR of the same head/site
+    PirWithoutPrr,
+    /// PRR without a PIR of the same head/site
+    PrrWithoutPir,
+    /// WIR is not closed by a WRR of the same head
+    WirWithoutWrr,
+    /// WRR without a WIR of the same head
+    WrrWithoutWir,
+    /// HBR or SBR of the same head/site/bin
+    /// appears more than once
+    DuplicatedBin,
+    /// contradictory bits in `test_flg`,
+    /// `parm_flg` or `part_flg`
+    FlagInconsistent,
+    /// counts in MPR does not match the array length
+    CountMismatch,
+    /// file cannot be read further
+    ReadError,
+}
+
+/// A violation of STDF V4 specification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// file offset of the record header,
+    /// or the end of the readable data if
+    /// the violation is detected at the end of file
+    pub offset: u64,
+    /// record type of the record that violates the spec,
+    /// `REC_INVALID` if not related to any record
+    pub rec_type: u64,
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+/// STDF specification conformance validator
+///
+/// Records are fed in file order along with their offsets,
+/// `finish` returns the diagnostics sorted by offset.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_record_type::*, validate::*, StdfRecord};
+///
+/// let mut validator = Validator::new();
+/// validator.feed(&StdfRecord::new(REC_MIR), 0);
+/// let diagnostics = validator.finish();
+/// // FAR is not the first record, and MRR is absent
+/// assert_eq!(diagnostics[0].kind, DiagnosticKind::FarNotFirst);
+/// assert_eq!(diagnostics[1].kind, DiagnosticKind::MissingMrr);
+/// ```
+#[derive(Debug, Default)]
+pub struct Validator {
+    diagnostics: Vec<Diagnostic>,
+    rec_count: u64,
+    end_offset: u64,
+    mir_seen: bool,
+    mir_misplaced: bool,
+    mrr_seen: bool,
+    open_parts: HashMap<(U1, U1), u64>,
+    open_wafers: HashMap<U1, u64>,
+    hbins: HashSet<(U1, U1, U2)>,
+    sbins: HashSet<(U1, U1, U2)>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    /// validate a record at the given header offset
+    pub fn feed(&mut self, rec: &StdfRecord, offset: u64) {
+        let rec_type = rec.get_type();
+        self.rec_count += 1;
+        self.check_order(rec_type, offset);
+
+        match rec {
+            StdfRecord::PIR(pir_rec) => {
+                let key = (pir_rec.head_num, pir_rec.site_num);
+                if let Some(pir_offset) = self.open_parts.insert(key, offset) {
+                    self.report(
+                        pir_offset,
+                        REC_PIR,
+                        Severity::Error,
+                        DiagnosticKind::PirWithoutPrr,
+                        format!(
+                            "PIR of head {} site {} is not closed before the next PIR",
+                            key.0, key.1
+                        ),
+                    );
+                }
+            }
+            StdfRecord::PRR(prr_rec) => {
+                let key = (prr_rec.head_num, prr_rec.site_num);
+                if self.open_parts.remove(&key).is_none() {
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Error,
+                        DiagnosticKind::PrrWithoutPir,
+                        format!("PRR of head {} site {} has no PIR", key.0, key.1),
+                    );
+                }
+                // bit 0 & 1 cannot be set at the same time
+                if prr_rec.part_flg[0] & 0b0000_0011 == 0b0000_0011 {
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Error,
+                        DiagnosticKind::FlagInconsistent,
+                        format!(
+                            "part_flg {:#010b}: retested by PART_ID and by X/Y at the same time",
+                            prr_rec.part_flg[0]
+                        ),
+                    );
+                }
+            }
+            StdfRecord::WIR(wir_rec) => {
+                if let Some(wir_offset) = self.open_wafers.insert(wir_rec.head_num, offset) {
+                    self.report(
+                        wir_offset,
+                        REC_WIR,
+                        Severity::Error,
+                        DiagnosticKind::WirWithoutWrr,
+                        format!(
+                            "WIR of head {} is not closed before the next WIR",
+                            wir_rec.head_num
+                        ),
+                    );
+                }
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                let wir_offset = self.open_wafers.remove(&wrr_rec.head_num);
+                if wir_offset.is_none() {
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Error,
+                        DiagnosticKind::WrrWithoutWir,
+                        format!("WRR of head {} has no WIR", wrr_rec.head_num),
+                    );
+                }
+            }
+            StdfRecord::HBR(hbr_rec) => {
+                let key = bin_key(hbr_rec.head_num, hbr_rec.site_num, hbr_rec.hbin_num);
+                if !self.hbins.insert(key) {
+                    self.report_duplicated_bin(offset, rec_type, key);
+                }
+            }
+            StdfRecord::SBR(sbr_rec) => {
+                let key = bin_key(sbr_rec.head_num, sbr_rec.site_num, sbr_rec.sbin_num);
+                if !self.sbins.insert(key) {
+                    self.report_duplicated_bin(offset, rec_type, key);
+                }
+            }
+            StdfRecord::PTR(ptr_rec) => {
+                self.check_test_flg(offset, rec_type, ptr_rec.test_flg[0]);
+                self.check_parm_flg(offset, rec_type, ptr_rec.test_flg[0], ptr_rec.parm_flg[0]);
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                self.check_test_flg(offset, rec_type, mpr_rec.test_flg[0]);
+                self.check_parm_flg(offset, rec_type, mpr_rec.test_flg[0], mpr_rec.parm_flg[0]);
+                if mpr_rec.rtn_icnt != 0
+                    && mpr_rec.rslt_cnt != 0
+                    && mpr_rec.rtn_icnt != mpr_rec.rslt_cnt
+                {
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Warning,
+                        DiagnosticKind::CountMismatch,
+                        format!(
+                            "MPR {}: rtn_icnt ({}) is different from rslt_cnt ({})",
+                            mpr_rec.test_num, mpr_rec.rtn_icnt, mpr_rec.rslt_cnt
+                        ),
+                    );
+                }
+            }
+            StdfRecord::FTR(ftr_rec) => {
+                self.check_test_flg(offset, rec_type, ftr_rec.test_flg[0]);
+            }
+            _ => {}
+        }
+    }
+
+    /// validate an unprocessed record, in addition to `feed`,
+    /// array counts of MPR are checked against the record length
+    pub fn feed_raw(&mut self, raw_element: &RawDataElement) {
+        let offset = raw_element.offset.saturating_sub(4);
+        let rec = StdfRecord::from(raw_element);
+        self.feed(&rec, offset);
+        self.end_offset = raw_element.offset + raw_element.raw_data.len() as u64;
+
+        if let StdfRecord::MPR(ref mpr_rec) = rec {
+            // missing array elements are filled by default values
+            // in parsing, so the record gets longer after serialization
+            let expected_len = mpr_rec.write_to_bytes(&raw_element.byte_order).len();
+            if expected_len > raw_element.raw_data.len() {
+                self.report(
+                    offset,
+                    REC_MPR,
+                    Severity::Error,
+                    DiagnosticKind::CountMismatch,
+                    format!(
+                        "MPR {}: rtn_icnt ({}) or rslt_cnt ({}) exceeds the array length, \
+                        record length is {} bytes, expected {} bytes",
+                        mpr_rec.test_num,
+                        mpr_rec.rtn_icnt,
+                        mpr_rec.rslt_cnt,
+                        raw_element.raw_data.len(),
+                        expected_len
+                    ),
+                );
+            }
+        }
+    }
+
+    /// report an error that stops the reading
+    pub fn feed_error(&mut self, error: &StdfError) {
+        self.report(
+            self.end_offset,
+            REC_INVALID,
+            Severity::Error,
+            DiagnosticKind::ReadError,
+            format!("{}", error),
+        );
+    }
+
+    /// finish the validation and
+    /// return the diagnostics sorted by offset
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        let end_offset = self.end_offset;
+        if self.rec_count != 0 && !self.mir_seen {
+            self.report(
+                end_offset,
+                REC_INVALID,
+                Severity::Error,
+                DiagnosticKind::MirMisplaced,
+                String::from("MIR is absent"),
+            );
+        }
+        if !self.mrr_seen {
+            self.report(
+                end_offset,
+                REC_INVALID,
+                Severity::Error,
+                DiagnosticKind::MissingMrr,
+                String::from("MRR is absent"),
+            );
+        }
+        let open_parts = std::mem::take(&mut self.open_parts);
+        for ((head_num, site_num), pir_offset) in open_parts {
+            self.report(
+                pir_offset,
+                REC_PIR,
+                Severity::Error,
+                DiagnosticKind::PirWithoutPrr,
+                format!(
+                    "PIR of head {} site {} is not closed at the end of file",
+                    head_num, site_num
+                ),
+            );
+        }
+        let open_wafers = std::mem::take(&mut self.open_wafers);
+        for (head_num, wir_offset) in open_wafers {
+            self.report(
+                wir_offset,
+                REC_WIR,
+                Severity::Error,
+                DiagnosticKind::WirWithoutWrr,
+                format!("WIR of head {} is not closed at the end of file", head_num),
+            );
+        }
+        // stable sort, keeps the detection order
+        // of the diagnostics at the same offset
+        self.diagnostics.sort_by_key(|d| d.offset);
+        self.diagnostics
+    }
+
+    #[inline(always)]
+    fn check_order(&mut self, rec_type: u64, offset: u64) {
+        if rec_type == REC_FAR {
+            if self.rec_count != 1 {
+                self.report(
+                    offset,
+                    rec_type,
+                    Severity::Error,
+                    DiagnosticKind::FarNotFirst,
+                    String::from("FAR is not the first record"),
+                );
+            }
+        } else if self.rec_count == 1 {
+            self.report(
+                offset,
+                rec_type,
+                Severity::Error,
+                DiagnosticKind::FarNotFirst,
+                format!(
+                    "the first record is {}, not FAR",
+                    get_rec_name_from_code(rec_type)
+                ),
+            );
+        }
+
+        if self.mrr_seen {
+            self.report(
+                offset,
+                rec_type,
+                Severity::Warning,
+                DiagnosticKind::RecordAfterMrr,
+                format!("{} found after MRR", get_rec_name_from_code(rec_type)),
+            );
+        }
+
+        match rec_type {
+            REC_FAR | REC_ATR | REC_VUR => {}
+            REC_MIR => {
+                if self.mir_seen {
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Error,
+                        DiagnosticKind::MirMisplaced,
+                        String::from("MIR is repeated"),
+                    );
+                }
+                self.mir_seen = true;
+            }
+            REC_MRR => self.mrr_seen = true,
+            _ => {
+                // report only once
+                if !self.mir_seen && !self.mir_misplaced {
+                    self.mir_misplaced = true;
+                    self.report(
+                        offset,
+                        rec_type,
+                        Severity::Error,
+                        DiagnosticKind::MirMisplaced,
+                        format!(
+                            "{} found before MIR, MIR must follow FAR and ATRs",
+                            get_rec_name_from_code(rec_type)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn check_test_flg(&mut self, offset: u64, rec_type: u64, test_flg: u8) {
+        // bit 7 must be 0 if bit 6 (no pass/fail indication) is set
+        if test_flg & 0b1100_0000 == 0b1100_0000 {
+            self.report(
+                offset,
+                rec_type,
+                Severity::Error,
+                DiagnosticKind::FlagInconsistent,
+                format!(
+                    "test_flg {:#010b}: failed without pass/fail indication",
+                    test_flg
+                ),
+            );
+        }
+    }
+
+    #[inline(always)]
+    fn check_parm_flg(&mut self, offset: u64, rec_type: u64, test_flg: u8, parm_flg: u8) {
+        // bit 3: higher than high limit, bit 4: lower than low limit
+        if parm_flg & 0b0001_1000 == 0b0001_1000 {
+            self.report(
+                offset,
+                rec_type,
+                Severity::Error,
+                DiagnosticKind::FlagInconsistent,
+                format!(
+                    "parm_flg {:#010b}: higher than high limit and lower than low limit",
+                    parm_flg
+                ),
+            );
+        } else if parm_flg & 0b0001_1000 != 0
+            // bit 5: passed alternate limits
+            && parm_flg & 0b0010_0000 == 0
+            // test passed with valid pass/fail indication
+            && test_flg & 0b1100_0000 == 0
+        {
+            self.report(
+                offset,
+                rec_type,
+                Severity::Warning,
+                DiagnosticKind::FlagInconsistent,
+                format!(
+                    "test_flg {:#010b}, parm_flg {:#010b}: passed but out of limits",
+                    test_flg, parm_flg
+                ),
+            );
+        }
+    }
+
+    #[inline(always)]
+    fn report_duplicated_bin(&mut self, offset: u64, rec_type: u64, key: (U1, U1, U2)) {
+        self.report(
+            offset,
+            rec_type,
+            Severity::Warning,
+            DiagnosticKind::DuplicatedBin,
+            format!(
+                "{} of head {} site {} bin {} is duplicated",
+                get_rec_name_from_code(rec_type),
+                key.0,
+                key.1,
+                key.2
+            ),
+        );
+    }
+
+    #[inline(always)]
+    fn report(
+        &mut self,
+        offset: u64,
+        rec_type: u64,
+        severity: Severity,
+        kind: DiagnosticKind,
+        message: String,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            offset,
+            rec_type,
+            severity,
+            kind,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        if self.rec_type == REC_INVALID {
+            write!(
+                f,
+                "{} at offset {}: {}",
+                severity, self.offset, self.message
+            )
+        } else {
+            write!(
+                f,
+                "{} at offset {} ({}): {}",
+                severity,
+                self.offset,
+                get_rec_name_from_code(self.rec_type),
+                self.message
+            )
+        }
+    }
+}
+
+/// key of HBR/SBR, site number is ignored
+/// if head number is 255 (all sites)
+#[inline(always)]
+fn bin_key(head_num: U1, site_num: U1, bin_num: U2) -> (U1, U1, U2) {
+    if head_num == 255 {
+        (head_num, 0, bin_num)
+    } else {
+        (head_num, site_num, bin_num)
+    }
+}
+
+/// validate all the records of a `StdfReader`,
+/// reading stops at the first error, which is
+/// reported as `DiagnosticKind::ReadError`
+pub fn validate_stream<R: BufRead + Seek>(reader: &mut StdfReader<R>) -> Vec<Diagnostic> {
+    let mut validator = Validator::new();
+    for raw_element in reader.get_rawdata_iter() {
+        match raw_element {
+            Ok(raw_element) => validator.feed_raw(&raw_element),
+            Err(e) => {
+                validator.feed_error(&e);
+                break;
+            }
+        }
+    }
+    validator.finish()
+}
+
+/// validate the STDF file at the given path,
+/// error is returned only if the file cannot be opened
+pub fn validate_file<P: AsRef<Path>>(path: P) -> Result<Vec<Diagnostic>, StdfError> {
+    let mut reader = StdfReader::new(path)?;
+    Ok(validate_stream(&mut reader))
+}
@@ -0,0 +1,423 @@
+//
+// wafermap.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use crate::stdf_types::*;
+use crate::summary::part_passed;
+use std::collections::{BTreeMap, HashMap};
+
+/// Which result is kept if a die
+/// is tested more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetestPolicy {
+    /// the last test result overwrites the previous ones
+    #[default]
+    LastWins,
+    /// the first test result is kept
+    FirstWins,
+}
+
+/// A die on the wafer, from PRR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Die {
+    pub x_coord: I2,
+    pub y_coord: I2,
+    pub head_num: U1,
+    pub site_num: U1,
+    pub hard_bin: U2,
+    pub soft_bin: U2,
+    pub part_flg: B1,
+    pub part_id: Cn,
+    pub test_t: U4,
+    /// passed according to `part_flg`
+    pub passed: bool,
+    /// how many times the die is tested
+    pub test_cnt: u32,
+}
+
+/// Wafer map of a wafer
+///
+/// Wafer configuration is from WCR, wafer
+/// information is from WIR/WRR, and dies
+/// are from PRRs with valid coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaferMap {
+    pub wafer_id: Cn,
+    pub head_num: U1,
+    pub site_grp: U1,
+    pub start_t: U4,
+    /// `finish_t` of the last WRR, 0 if absent
+    pub finish_t: U4,
+    /// wafer configuration, default values if WCR is absent
+    pub wcr: WCR,
+    /// the last WRR of the wafer, if any
+    pub wrr: Option<WRR>,
+    /// lot id from MIR
+    pub lot_id: Cn,
+    /// part type from MIR
+    pub part_typ: Cn,
+    /// pass/fail and name of hardware bins from HBR
+    pub hbin_defs: BTreeMap<U2, BinDef>,
+    /// pass/fail and name of software bins from SBR
+    pub sbin_defs: BTreeMap<U2, BinDef>,
+    dies: BTreeMap<(I2, I2), Die>,
+}
+
+/// Pass/fail and name of a bin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinDef {
+    pub bin_pf: C1,
+    pub bin_nam: Cn,
+}
+
+/// Build wafer maps from records
+///
+/// Dies of WIRs with the same non-empty wafer id are
+/// merged into one wafer map, so a retested wafer in the
+/// same file produces only one map, retested dies
+/// are resolved by the `RetestPolicy`. Every WIR with
+/// an empty wafer id starts a new wafer map.
+///
+/// PRRs outside of WIR/WRR are collected in a
+/// wafer map with empty wafer id per head, which is
+/// never merged with the wafers of WIRs.
+///
+/// # Example
+///
+/// ```
+/// use rust_stdf::{stdf_file::*, wafermap::*};
+///
+/// let mut builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+/// if let Ok(mut reader) = StdfReader::new("demo_file.stdf") {
+///     for rec in reader.get_record_iter().flatten() {
+///         builder.feed(&rec);
+///     }
+/// }
+/// for wafer in builder.finish() {
+///     println!("{}: {} dies, yield {:?}", wafer.wafer_id, wafer.die_count(), wafer.yield_rate());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct WaferMapBuilder {
+    policy: RetestPolicy,
+    wcr: Option<WCR>,
+    mir: Option<MIR>,
+    hbin_defs: BTreeMap<U2, BinDef>,
+    sbin_defs: BTreeMap<U2, BinDef>,
+    wafers: Vec<WaferMap>,
+    keys: WaferKeys,
+}
+
+/// Assign the indexes of wafers to WIRs and PRRs,
+/// shared by the builders of wafer maps and heatmaps,
+/// so that their outputs are in the same order
+#[derive(Debug, Default)]
+pub(crate) struct WaferKeys {
+    count: usize,
+    // non-empty wafer id -> index
+    wafer_index: HashMap<Cn, usize>,
+    // head number -> index of the wafer of PRRs outside of WIR/WRR
+    loose_wafers: HashMap<U1, usize>,
+    // head number -> index of the open wafer
+    open_wafers: HashMap<U1, usize>,
+}
+
+impl WaferMap {
+    fn new(wir: &WIR) -> Self {
+        WaferMap {
+            wafer_id: wir.wafer_id.clone(),
+            head_num: wir.head_num,
+            site_grp: wir.site_grp,
+            start_t: wir.start_t,
+            finish_t: 0,
+            wcr: WCR::default(),
+            wrr: None,
+            lot_id: String::new(),
+            part_typ: String::new(),
+            hbin_defs: BTreeMap::new(),
+            sbin_defs: BTreeMap::new(),
+            dies: BTreeMap::new(),
+        }
+    }
+
+    /// get the die at (x, y)
+    #[inline(always)]
+    pub fn get_die(&self, x_coord: I2, y_coord: I2) -> Option<&Die> {
+        self.dies.get(&(x_coord, y_coord))
+    }
+
+    /// iterate dies ordered by (x, y)
+    #[inline(always)]
+    pub fn dies(&self) -> impl Iterator<Item = &Die> {
+        self.dies.values()
+    }
+
+    #[inline(always)]
+    pub fn die_count(&self) -> usize {
+        self.dies.len()
+    }
+
+    /// count of the passed dies
+    #[inline(always)]
+    pub fn good_count(&self) -> usize {
+        self.dies.values().filter(|d| d.passed).count()
+    }
+
+    /// count of the dies that are tested more than once
+    #[inline(always)]
+    pub fn retest_count(&self) -> usize {
+        self.dies.values().filter(|d| d.test_cnt > 1).count()
+    }
+
+    /// ratio of the passed dies
+    pub fn yield_rate(&self) -> Option<R8> {
+        if self.dies.is_empty() {
+            None
+        } else {
+            Some(self.good_count() as R8 / self.dies.len() as R8)
+        }
+    }
+
+    /// (min_x, min_y, max_x, max_y) of the dies
+    pub fn bounds(&self) -> Option<(I2, I2, I2, I2)> {
+        self.dies.keys().fold(None, |acc, &(x, y)| match acc {
+            None => Some((x, y, x, y)),
+            Some((x0, y0, x1, y1)) => Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y))),
+        })
+    }
+
+    /// (column, row) of the coordinate in the view, rows from
+    /// top to bottom and columns from left to right, according
+    /// to `pos_x` and `pos_y` of WCR, x increases to the right
+    /// and y increases downward if they are not specified.
+    ///
+    /// the top-left die of the bounds is (0, 0), the coordinate
+    /// can be outside of the bounds, `None` if there are no dies.
+    pub fn grid_position(&self, x_coord: I2, y_coord: I2) -> Option<(i32, i32)> {
+        let bounds = self.bounds()?;
+        Some(self.position_in(bounds, x_coord, y_coord))
+    }
+
+    #[inline(always)]
+    fn position_in(&self, bounds: (I2, I2, I2, I2), x_coord: I2, y_coord: I2) -> (i32, i32) {
+        let (min_x, min_y, max_x, max_y) = bounds;
+        let col = if self.wcr.pos_x == 'L' {
+            max_x as i32 - x_coord as i32
+        } else {
+            x_coord as i32 - min_x as i32
+        };
+        let row = if self.wcr.pos_y == 'U' {
+            max_y as i32 - y_coord as i32
+        } else {
+            y_coord as i32 - min_y as i32
+        };
+        (col, row)
+    }
+
+    /// dies arranged in rows and columns as they
+    /// are viewed, see `grid_position` for the orientation.
+    ///
+    /// Empty if the wafer has no dies.
+    pub fn die_grid(&self) -> Vec<Vec<Option<&Die>>> {
+        let bounds = match self.bounds() {
+            Some(b) => b,
+            None => return vec![],
+        };
+        let (min_x, min_y, max_x, max_y) = bounds;
+        let cols = (max_x as i32 - min_x as i32 + 1) as usize;
+        let rows = (max_y as i32 - min_y as i32 + 1) as usize;
+        let mut grid = vec![vec![None; cols]; rows];
+        for die in self.dies.values() {
+            let (col, row) = self.position_in(bounds, die.x_coord, die.y_coord);
+            grid[row as usize][col as usize] = Some(die);
+        }
+        grid
+    }
+
+    /// die count of every hardware bin
+    pub fn hbin_counts(&self) -> BTreeMap<U2, usize> {
+        let mut counts = BTreeMap::new();
+        for die in self.dies.values() {
+            *counts.entry(die.hard_bin).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// die count of every software bin
+    pub fn sbin_counts(&self) -> BTreeMap<U2, usize> {
+        let mut counts = BTreeMap::new();
+        for die in self.dies.values() {
+            *counts.entry(die.soft_bin).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[inline(always)]
+    fn add_prr(&mut self, prr: &PRR, policy: RetestPolicy) {
+        // -32768 indicates the coordinate is invalid
+        if prr.x_coord == -32768 || prr.y_coord == -32768 {
+            return;
+        }
+        let new_die = Die {
+            x_coord: prr.x_coord,
+            y_coord: prr.y_coord,
+            head_num: prr.head_num,
+            site_num: prr.site_num,
+            hard_bin: prr.hard_bin,
+            soft_bin: prr.soft_bin,
+            part_flg: prr.part_flg,
+            part_id: prr.part_id.clone(),
+            test_t: prr.test_t,
+            passed: part_passed(prr),
+            test_cnt: 1,
+        };
+        match self.dies.get_mut(&(prr.x_coord, prr.y_coord)) {
+            Some(die) => {
+                let test_cnt = die.test_cnt + 1;
+                if policy == RetestPolicy::LastWins {
+                    *die = new_die;
+                }
+                die.test_cnt = test_cnt;
+            }
+            None => {
+                self.dies.insert((prr.x_coord, prr.y_coord), new_die);
+            }
+        }
+    }
+}
+
+impl WaferMapBuilder {
+    pub fn new(policy: RetestPolicy) -> Self {
+        WaferMapBuilder {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    /// feed MIR, WCR, WIR, WRR, PRR, HBR and SBR,
+    /// other records are ignored.
+    pub fn feed(&mut self, rec: &StdfRecord) {
+        match rec {
+            StdfRecord::MIR(mir_rec) => self.mir = Some(mir_rec.clone()),
+            StdfRecord::WCR(wcr_rec) => self.wcr = Some(wcr_rec.clone()),
+            StdfRecord::HBR(hbr_rec) => {
+                self.hbin_defs
+                    .entry(hbr_rec.hbin_num)
+                    .or_insert_with(|| BinDef {
+                        bin_pf: hbr_rec.hbin_pf,
+                        bin_nam: hbr_rec.hbin_nam.clone(),
+                    });
+            }
+            StdfRecord::SBR(sbr_rec) => {
+                self.sbin_defs
+                    .entry(sbr_rec.sbin_num)
+                    .or_insert_with(|| BinDef {
+                        bin_pf: sbr_rec.sbin_pf,
+                        bin_nam: sbr_rec.sbin_nam.clone(),
+                    });
+            }
+            StdfRecord::WIR(wir_rec) => {
+                let (_, new) = self.keys.open(wir_rec);
+                if new {
+                    self.wafers.push(WaferMap::new(wir_rec));
+                }
+            }
+            StdfRecord::WRR(wrr_rec) => {
+                if let Some(index) = self.keys.close(wrr_rec.head_num) {
+                    let wafer = &mut self.wafers[index];
+                    wafer.finish_t = wrr_rec.finish_t;
+                    wafer.wrr = Some(wrr_rec.clone());
+                }
+            }
+            StdfRecord::PRR(prr_rec) => {
+                // -32768 indicates the coordinate is invalid
+                if prr_rec.x_coord == -32768 || prr_rec.y_coord == -32768 {
+                    return;
+                }
+                let (index, new) = self.keys.locate(prr_rec.head_num);
+                if new {
+                    // PRR outside of WIR/WRR
+                    self.wafers.push(WaferMap::new(&WIR {
+                        head_num: prr_rec.head_num,
+                        ..Default::default()
+                    }));
+                }
+                self.wafers[index].add_prr(prr_rec, self.policy);
+            }
+            _ => {}
+        }
+    }
+
+    /// return wafer maps in the order of appearance
+    pub fn finish(self) -> Vec<WaferMap> {
+        let wcr = self.wcr.unwrap_or_default();
+        let mir = self.mir.unwrap_or_default();
+        self.wafers
+            .into_iter()
+            .map(|mut wafer| {
+                wafer.wcr = wcr.clone();
+                wafer.lot_id = mir.lot_id.clone();
+                wafer.part_typ = mir.part_typ.clone();
+                wafer.hbin_defs = self.hbin_defs.clone();
+                wafer.sbin_defs = self.sbin_defs.clone();
+                wafer
+            })
+            .collect()
+    }
+}
+
+impl WaferKeys {
+    /// open the wafer of WIR, return its index and whether
+    /// it's a new wafer, wafers are merged by wafer id
+    /// only if it's not empty
+    pub(crate) fn open(&mut self, wir: &WIR) -> (usize, bool) {
+        let (index, new) = match self.wafer_index.get(&wir.wafer_id) {
+            Some(&index) => (index, false),
+            None => {
+                let index = self.next_index();
+                if !wir.wafer_id.is_empty() {
+                    self.wafer_index.insert(wir.wafer_id.clone(), index);
+                }
+                (index, true)
+            }
+        };
+        self.open_wafers.insert(wir.head_num, index);
+        (index, new)
+    }
+
+    /// close the open wafer of the head, return its index
+    #[inline(always)]
+    pub(crate) fn close(&mut self, head_num: U1) -> Option<usize> {
+        self.open_wafers.remove(&head_num)
+    }
+
+    /// return the index of the wafer of a PRR and whether it's
+    /// a new wafer, which is the open wafer of the head, or
+    /// the wafer of PRRs outside of WIR/WRR of the head
+    pub(crate) fn locate(&mut self, head_num: U1) -> (usize, bool) {
+        if let Some(&index) = self.open_wafers.get(&head_num) {
+            return (index, false);
+        }
+        match self.loose_wafers.get(&head_num) {
+            Some(&index) => (index, false),
+            None => {
+                let index = self.next_index();
+                self.loose_wafers.insert(head_num, index);
+                (index, true)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn next_index(&mut self) -> usize {
+        self.count += 1;
+        self.count - 1
+    }
+}
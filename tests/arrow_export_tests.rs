@@ -0,0 +1,131 @@
+//
+// arrow_export_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "arrow")]
+
+mod common;
+
+use arrow::array::UInt16Array;
+#[cfg(feature = "gzip")]
+use common::{demo_path, demo_records};
+#[cfg(feature = "gzip")]
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+#[cfg(feature = "gzip")]
+use rust_stdf::stdf_record_type::*;
+use rust_stdf::{arrow_export::*, StdfRecord, MPR, PIR};
+#[cfg(feature = "gzip")]
+use std::fs::File;
+
+#[test]
+#[cfg(feature = "gzip")]
+fn arrow_batch_size_test() {
+    let records = demo_records();
+    let prr_cnt = records.iter().filter(|r| r.is_type(REC_PRR)).count();
+    let bin_cnt = records
+        .iter()
+        .filter(|r| r.is_type(REC_HBR | REC_SBR))
+        .count();
+
+    let mut builder = ArrowBatchBuilder::with_options(ArrowOptions { batch_size: 100 });
+    let mut batches = vec![];
+    for rec in records.iter() {
+        builder.feed(rec).unwrap();
+        batches.extend(builder.take_batches());
+    }
+    batches.extend(builder.finish().unwrap());
+
+    let mut row_counts = std::collections::HashMap::new();
+    for (table, batch) in batches.iter() {
+        assert!(batch.num_rows() <= 100);
+        assert_eq!(batch.schema(), table.schema());
+        *row_counts.entry(*table).or_insert(0) += batch.num_rows();
+    }
+    assert_eq!(row_counts[&StdfTable::Parts], prr_cnt);
+    assert_eq!(row_counts[&StdfTable::Bins], bin_cnt);
+    assert!(row_counts[&StdfTable::Results] > 100);
+    assert!(row_counts[&StdfTable::Tests] > 0);
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn write_parquet_test() {
+    let records = demo_records();
+    let prr_cnt = records.iter().filter(|r| r.is_type(REC_PRR)).count() as u64;
+    let dst_dir = std::env::temp_dir().join("rust_stdf_parquet_test");
+    let row_counts =
+        write_parquet(demo_path("lot2.stdf.gz"), &dst_dir, ArrowOptions::default()).unwrap();
+    assert_eq!(row_counts[&StdfTable::Parts], prr_cnt);
+
+    for table in StdfTable::ALL {
+        let file = File::open(dst_dir.join(format!("{}.parquet", table.name()))).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut rows = 0;
+        for batch in reader {
+            let batch = batch.unwrap();
+            assert_eq!(batch.schema(), table.schema());
+            rows += batch.num_rows() as u64;
+        }
+        assert_eq!(rows, row_counts[&table]);
+    }
+    let _ = std::fs::remove_dir_all(dst_dir);
+}
+
+#[test]
+fn arrow_mpr_pins_test() {
+    let mpr = |site_num: u8, rtn_indx: Option<Vec<u16>>| {
+        StdfRecord::MPR(MPR {
+            test_num: 10,
+            site_num,
+            rtn_icnt: 2,
+            rslt_cnt: 2,
+            rtn_rslt: vec![1.0, 2.0],
+            rtn_indx,
+            ..Default::default()
+        })
+    };
+    let pir = |site_num: u8| {
+        StdfRecord::PIR(PIR {
+            head_num: 0,
+            site_num,
+        })
+    };
+    let mut builder = ArrowBatchBuilder::new();
+    for rec in [
+        pir(1),
+        pir(2),
+        // every site has its own pins
+        mpr(1, Some(vec![1, 2])),
+        mpr(2, Some(vec![3, 4])),
+        // omitted, use the first MPR of the test
+        mpr(2, None),
+    ] {
+        builder.feed(&rec).unwrap();
+    }
+    let (_, results) = builder
+        .finish()
+        .unwrap()
+        .into_iter()
+        .find(|(table, _)| *table == StdfTable::Results)
+        .unwrap();
+    let pmr_indx = results
+        .column_by_name("pmr_indx")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<UInt16Array>()
+        .unwrap();
+    assert_eq!(
+        pmr_indx.iter().collect::<Vec<_>>(),
+        [1, 2, 3, 4, 1, 2].map(Some).to_vec()
+    );
+}
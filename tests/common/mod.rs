@@ -0,0 +1,32 @@
+//
+// mod.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+// shared by the test crates, each uses a part of it
+#![allow(dead_code)]
+
+#[cfg(feature = "gzip")]
+use rust_stdf::{stdf_file::StdfReader, StdfRecord};
+use std::path::PathBuf;
+
+/// path of a file in `demo_stdf`
+pub fn demo_path(name: &str) -> PathBuf {
+    let mut stdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    stdf_path.push("demo_stdf");
+    stdf_path.push(name);
+    stdf_path
+}
+
+/// all records of `lot2.stdf.gz`
+#[cfg(feature = "gzip")]
+pub fn demo_records() -> Vec<StdfRecord> {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    reader.get_record_iter().map(|r| r.unwrap()).collect()
+}
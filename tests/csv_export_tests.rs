@@ -0,0 +1,160 @@
+//
+// csv_export_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+#[cfg(feature = "gzip")]
+use rust_stdf::stdf_file::*;
+use rust_stdf::{csv_export::*, stdf_record_type::*, StdfRecord};
+#[cfg(feature = "gzip")]
+use std::collections::HashSet;
+
+#[test]
+#[cfg(feature = "gzip")]
+fn export_csv_file_test() {
+    let src = demo_path("lot2.stdf.gz");
+    let mut reader = StdfReader::new(&src).unwrap();
+    let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    let prr_cnt = records.iter().filter(|r| r.is_type(REC_PRR)).count();
+    // (test number, pin position)
+    let mut test_columns: HashSet<(u32, Option<usize>)> = HashSet::new();
+    for rec in records.iter() {
+        match rec {
+            StdfRecord::PTR(ptr_rec) => {
+                test_columns.insert((ptr_rec.test_num, None));
+            }
+            StdfRecord::MPR(mpr_rec) => {
+                for pos in 0..mpr_rec.rtn_rslt.len() {
+                    test_columns.insert((mpr_rec.test_num, Some(pos)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let dst = std::env::temp_dir().join("rust_stdf_csv_export_test.tsv");
+    let options = CsvOptions {
+        delimiter: '\t',
+        ..Default::default()
+    };
+    let row_count = export_csv_file(&src, &dst, options).unwrap();
+    assert_eq!(row_count, prr_cnt as u64);
+
+    let table = std::fs::read_to_string(&dst).unwrap();
+    let lines: Vec<&str> = table.lines().collect();
+    // 6 header rows and the row of column names
+    assert_eq!(lines.len(), 7 + prr_cnt);
+    let column_count = PART_COLUMNS.len() + test_columns.len();
+    // some test names of the demo file contain tabs,
+    // which are quoted in TEST_TXT row
+    for line in lines.iter().filter(|l| !l.starts_with("TEST_TXT")) {
+        assert_eq!(line.split('\t').count(), column_count);
+    }
+    assert!(lines[0].starts_with("TEST_NUM\t"));
+    assert!(lines[6].starts_with("LOT_ID\tWAFER_ID\tPART_ID"));
+    // column names are unique
+    let names: HashSet<&str> = lines[6].split('\t').collect();
+    assert_eq!(names.len(), column_count);
+    let _ = std::fs::remove_file(dst);
+}
+
+#[test]
+fn csv_exporter_test() {
+    let mut ptr = StdfRecord::new(REC_PTR);
+    if let StdfRecord::PTR(ref mut ptr_rec) = ptr {
+        ptr_rec.test_num = 1;
+        ptr_rec.test_txt = "VDD, nominal".to_string();
+        ptr_rec.result = 0.5;
+    }
+    let mut invalid_ptr = ptr.clone();
+    if let StdfRecord::PTR(ref mut ptr_rec) = invalid_ptr {
+        // bit 1: result is not valid
+        ptr_rec.test_flg = [0b0000_0010];
+    }
+    let mut mpr = StdfRecord::new(REC_MPR);
+    if let StdfRecord::MPR(ref mut mpr_rec) = mpr {
+        mpr_rec.test_num = 2;
+        mpr_rec.rtn_rslt = vec![1.0, 2.0];
+        mpr_rec.rtn_indx = Some(vec![10, 11]);
+    }
+    let mut pmr = StdfRecord::new(REC_PMR);
+    if let StdfRecord::PMR(ref mut pmr_rec) = pmr {
+        pmr_rec.pmr_indx = 10;
+        pmr_rec.log_nam = "DQ0".to_string();
+        // the site of MPR
+        pmr_rec.head_num = 0;
+        pmr_rec.site_num = 0;
+    }
+    let mut prr = StdfRecord::new(REC_PRR);
+    if let StdfRecord::PRR(ref mut prr_rec) = prr {
+        prr_rec.part_id = "1".to_string();
+    }
+    let recs = [
+        pmr,
+        StdfRecord::new(REC_PIR),
+        ptr,
+        mpr,
+        prr.clone(),
+        StdfRecord::new(REC_PIR),
+        invalid_ptr,
+        prr,
+    ];
+
+    let mut collector = TestColumnCollector::new();
+    recs.iter().for_each(|r| collector.feed(r));
+    let columns = collector.finish();
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[1].pin_nam, "DQ0");
+    // PMR 11 is not found
+    assert_eq!(columns[2].pin_nam, "11");
+
+    let mut exporter = CsvExporter::new(Vec::new(), columns, CsvOptions::default()).unwrap();
+    for rec in recs.iter() {
+        exporter.feed(rec).unwrap();
+    }
+    assert_eq!(exporter.get_row_count(), 2);
+    let csv = String::from_utf8(exporter.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert!(lines[1].ends_with(",\"VDD, nominal\",,"));
+    assert!(lines[2].ends_with(",,DQ0,11"));
+    assert!(lines[6].ends_with(",1,2.DQ0,2.11"));
+    assert!(lines[7].ends_with(",0.5,1,2"));
+    // invalid result and tests not executed are empty
+    assert!(lines[8].ends_with(",,,"));
+}
+
+#[test]
+fn csv_pin_column_name_test() {
+    let column = |pin_pos: Option<usize>, pin_nam: &str| TestColumn {
+        test_num: 3,
+        test_typ: if pin_pos.is_some() { 'M' } else { 'P' },
+        test_txt: String::new(),
+        pin_pos,
+        pin_nam: pin_nam.to_string(),
+        units: String::new(),
+        lo_limit: None,
+        hi_limit: None,
+    };
+    let columns = vec![
+        column(None, ""),
+        column(Some(0), "IO"),
+        column(Some(1), "IO"),
+        column(Some(2), ""),
+        column(Some(3), "VDD"),
+    ];
+    let exporter = CsvExporter::new(Vec::new(), columns, CsvOptions::default()).unwrap();
+    let csv = String::from_utf8(exporter.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[6].ends_with(",SOFT_BIN,TEST_T,3,3.IO_0,3.IO_1,3.2,3.VDD"));
+}
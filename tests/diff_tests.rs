@@ -0,0 +1,129 @@
+//
+// diff_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use common::{demo_path, demo_records};
+use rust_stdf::{diff::*, stdf_record_type::*, StdfRecord};
+
+fn diff(left: &[StdfRecord], right: &[StdfRecord], options: DiffOptions) -> Vec<Difference> {
+    let mut builder = DiffBuilder::with_options(options);
+    left.iter().for_each(|r| builder.feed_left(r));
+    right.iter().for_each(|r| builder.feed_right(r));
+    builder.finish()
+}
+
+#[test]
+fn diff_identical_test() {
+    let path = demo_path("lot2.stdf.gz");
+    let diffs = diff_files(&path, &path, DiffOptions::default()).unwrap();
+    assert!(diffs.is_empty());
+}
+
+#[test]
+fn diff_changes_test() {
+    let left = demo_records();
+    let mut right = left.clone();
+    let mut first_ptr = None;
+    let mut first_prr = None;
+    for (i, rec) in right.iter_mut().enumerate() {
+        match rec {
+            StdfRecord::MIR(mir_rec) => mir_rec.node_nam = "TESTER_B".to_string(),
+            StdfRecord::PTR(ptr_rec) if first_ptr.is_none() => {
+                first_ptr = Some((i, ptr_rec.test_num));
+                ptr_rec.result += 1.0;
+            }
+            StdfRecord::PRR(prr_rec) if first_prr.is_none() => {
+                first_prr = Some(i);
+                prr_rec.hard_bin += 100;
+            }
+            _ => {}
+        }
+    }
+    let (_, test_num) = first_ptr.unwrap();
+
+    let diffs = diff(&left, &right, DiffOptions::default());
+    assert_eq!(diffs.len(), 3);
+    assert_eq!(diffs[0].kind, DiffKind::HeaderChanged);
+    assert_eq!(diffs[0].field, "NODE_NAM");
+    assert_eq!(diffs[0].right.as_deref(), Some("TESTER_B"));
+    assert_eq!(diffs[1].kind, DiffKind::BinChanged);
+    assert_eq!(diffs[1].part.as_deref(), Some("#1"));
+    assert_eq!(diffs[2].kind, DiffKind::ResultChanged);
+    assert_eq!(diffs[2].test_num, Some(test_num));
+
+    // within tolerance, and header is not compared
+    let options = DiffOptions {
+        abs_tol: 1.5,
+        header: false,
+        ..Default::default()
+    };
+    let diffs = diff(&left, &right, options);
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].kind, DiffKind::BinChanged);
+}
+
+#[test]
+fn diff_missing_test() {
+    let mut left = demo_records();
+    let prr_cnt = left.iter().filter(|r| r.is_type(REC_PRR)).count();
+    // give every part a unique id
+    let mut index = 0;
+    for rec in left.iter_mut() {
+        if let StdfRecord::PRR(prr_rec) = rec {
+            index += 1;
+            prr_rec.part_id = format!("P{}", index);
+        }
+    }
+    // remove the first PRR and a test definition from the right
+    let first_prr = left.iter().position(|r| r.is_type(REC_PRR)).unwrap();
+    let test_num = left
+        .iter()
+        .find_map(|r| match r {
+            StdfRecord::PTR(ptr_rec) => Some(ptr_rec.test_num),
+            _ => None,
+        })
+        .unwrap();
+    let right: Vec<StdfRecord> = left
+        .iter()
+        .enumerate()
+        .filter(|(i, r)| {
+            *i != first_prr && !matches!(r, StdfRecord::PTR(p) if p.test_num == test_num)
+        })
+        .map(|(_, r)| r.clone())
+        .collect();
+
+    let options = DiffOptions {
+        align: AlignBy::PartId,
+        ..Default::default()
+    };
+    let diffs = diff(&left, &right, options);
+    assert_eq!(diffs[0].kind, DiffKind::TestMissing);
+    assert_eq!(diffs[0].test_num, Some(test_num));
+    assert!(diffs[0].right.is_none());
+    let missing: Vec<&Difference> = diffs
+        .iter()
+        .filter(|d| d.kind == DiffKind::PartMissing)
+        .collect();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].part.as_deref(), Some("P1"));
+    // the test is missing in all other parts
+    let result_missing = diffs
+        .iter()
+        .filter(|d| d.kind == DiffKind::ResultMissing)
+        .count();
+    assert!(result_missing > 0 && result_missing < prr_cnt);
+    assert!(diffs
+        .iter()
+        .all(|d| d.kind != DiffKind::BinChanged && d.kind != DiffKind::ResultChanged));
+}
@@ -0,0 +1,227 @@
+//
+// faildata_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{
+    faildata::*, stdf_file::*, stdf_record_type::*, ByteOrder, CompressType, KxUf, StdfRecord, FTR,
+    PTR, STR,
+};
+use std::io::Cursor;
+
+#[test]
+fn bitmap_decode_test() {
+    assert_eq!(
+        decode_bitmap(&vec![0b0000_0101, 0, 0b1000_0000]),
+        vec![0, 2, 23]
+    );
+    assert!(decode_bitmap(&vec![]).is_empty());
+
+    let ftr = FTR {
+        fail_pin: vec![0b0000_0010, 0b0000_0001],
+        spin_map: vec![0b1111_1111],
+        ..Default::default()
+    };
+    assert_eq!(ftr.fail_pins(), vec![1, 8]);
+    assert_eq!(ftr.enabled_pins(), (0..8).collect::<Vec<u16>>());
+
+    let str_rec = STR {
+        mask_map: vec![0b0001_0000],
+        fal_map: vec![0, 0b0000_0100],
+        ..Default::default()
+    };
+    assert_eq!(str_rec.masked_pins(), vec![4]);
+    assert_eq!(str_rec.fal_pins(), vec![10]);
+}
+
+fn str_record(cont_flg: u8, cyc_ofst: Vec<u8>, pmr_indx: Vec<u16>) -> STR {
+    STR {
+        cont_flg: [cont_flg],
+        test_num: 7,
+        head_num: 1,
+        site_num: 2,
+        cyc_base: 1000,
+        bit_base: 10,
+        totl_cnt: cyc_ofst.len() as u32,
+        cyc_cnt: cyc_ofst.len() as u16,
+        cyc_size: 1,
+        pmr_size: 2,
+        bit_size: 1,
+        exp_cnt: cyc_ofst.len() as u16,
+        cap_cnt: cyc_ofst.len() as u16,
+        bpos_cnt: cyc_ofst.len() as u16,
+        exp_data: vec![1; cyc_ofst.len()],
+        cap_data: vec![0; cyc_ofst.len()],
+        bit_pos: KxUf::F1(cyc_ofst.clone()),
+        cyc_ofst: KxUf::F1(cyc_ofst),
+        pmr_cnt: pmr_indx.len() as u16,
+        pmr_indx: KxUf::F2(pmr_indx),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn str_fails_test() {
+    let first = str_record(1, vec![1, 2], vec![5, 6]);
+    let fails = first.fails();
+    assert_eq!(fails.len(), 2);
+    assert_eq!(
+        fails[1],
+        StrFail {
+            cycle: Some(1002),
+            pmr_indx: Some(6),
+            expected: Some(1),
+            captured: Some(0),
+            new_data: None,
+            pattern: None,
+            chain: None,
+            bit_pos: Some(12),
+        }
+    );
+
+    let mut decoder = StrFailDecoder::new();
+    assert!(decoder.feed(&first).is_none());
+    // another test in between is not affected
+    let other = STR {
+        test_num: 8,
+        head_num: 1,
+        site_num: 2,
+        ..Default::default()
+    };
+    let other_fails = decoder.feed(&other).unwrap();
+    assert_eq!(other_fails.test_num, 8);
+    assert!(other_fails.fails.is_empty());
+
+    // the last record has more cycles than pins
+    let last = str_record(0, vec![3, 4], vec![7]);
+    let merged = decoder.feed(&last).unwrap();
+    assert_eq!(
+        (merged.test_num, merged.head_num, merged.site_num),
+        (7, 1, 2)
+    );
+    let cycles: Vec<Option<u64>> = merged.fails.iter().map(|f| f.cycle).collect();
+    assert_eq!(cycles, vec![Some(1001), Some(1002), Some(1003), Some(1004)]);
+    assert_eq!(merged.fails[3].pmr_indx, None);
+
+    assert!(decoder.feed(&str_record(1, vec![9], vec![9])).is_none());
+    let pending = decoder.finish();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].fails[0].cycle, Some(1009));
+}
+
+#[test]
+fn str_merge_iter_test() {
+    let mut first = str_record(1, vec![1, 2], vec![5, 6]);
+    first.totl_cnt = 4;
+    first.fal_map = vec![0b0000_0001];
+    let mut last = str_record(0, vec![1, 255], vec![7, 300]);
+    last.totl_cnt = 4;
+    last.cyc_base = 2000;
+    last.fal_map = vec![0b0000_0010, 0b0000_0001];
+
+    let order = ByteOrder::LittleEndian;
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    for rec in [
+        StdfRecord::new(REC_FAR),
+        StdfRecord::STR(first),
+        StdfRecord::PTR(PTR::default()),
+        StdfRecord::STR(last),
+    ] {
+        writer.write_record(&rec).unwrap();
+    }
+    let mut data = writer.finish().unwrap();
+    // an STR claims 2 PMR indexes but only 1 is written
+    let broken = StdfRecord::STR(STR {
+        test_num: 9,
+        head_num: 1,
+        site_num: 2,
+        // missing
+        totl_cnt: 4_294_967_295,
+        pmr_size: 2,
+        pmr_cnt: 1,
+        pmr_indx: KxUf::F2(vec![1]),
+        ..Default::default()
+    });
+    let mut broken_data = broken.write_to_bytes_with_header(&order).unwrap();
+    let broken_len = broken_data.len() as u32 - 4;
+    // PMR_CNT and PMR_INDX are followed by 10 empty array counts
+    let pmr_cnt_pos = broken_data.len() - 20 - 2 - 2;
+    broken_data[pmr_cnt_pos] = 2;
+    data.extend(broken_data);
+    let unterminated = StdfRecord::STR(str_record(1, vec![8], vec![8]));
+    data.extend(unterminated.write_to_bytes_with_header(&order).unwrap());
+
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let mut merged_iter = StrMergeIter::new(reader.get_rawdata_iter());
+    let merged: Vec<StdfRecord> = merged_iter.by_ref().flatten().collect();
+    assert_eq!(merged.len(), 5);
+    assert!(matches!(merged[1], StdfRecord::PTR(_)));
+
+    let StdfRecord::STR(str_rec) = &merged[2] else {
+        panic!("STR is expected")
+    };
+    assert_eq!(str_rec.cont_flg, [0]);
+    assert_eq!(
+        (str_rec.cyc_cnt, str_rec.pmr_cnt, str_rec.exp_cnt),
+        (4, 4, 4)
+    );
+    // offsets are rebased to the cycle base of the first record
+    assert_eq!(str_rec.cyc_ofst.to_u64_vec(), vec![1, 2, 1001, 1255]);
+    assert_eq!(str_rec.cyc_size, 8);
+    assert_eq!(str_rec.pmr_indx, KxUf::F2(vec![5, 6, 7, 300]));
+    assert_eq!(str_rec.fal_map, vec![0b0000_0011, 0b0000_0001]);
+    assert_eq!(str_rec.fails()[3].cycle, Some(2255));
+
+    // the unterminated sequence is yielded at the end
+    let StdfRecord::STR(str_rec) = &merged[4] else {
+        panic!("STR is expected")
+    };
+    assert_eq!(str_rec.cont_flg, [1]);
+
+    assert_eq!(
+        merged_iter.get_mismatches(),
+        &[StrCountMismatch {
+            test_num: 9,
+            head_num: 1,
+            site_num: 2,
+            field: "REC_LEN",
+            declared: broken_len + 2,
+            actual: broken_len,
+        }]
+    );
+}
+
+#[test]
+fn str_merge_bit_pos_test() {
+    let first = str_record(1, vec![1], vec![1]);
+    let mut last = str_record(0, vec![2], vec![2]);
+    last.bit_base = 20;
+    last.bit_size = 8;
+    last.bit_pos = KxUf::F8(vec![u64::MAX]);
+
+    let order = ByteOrder::LittleEndian;
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    for rec in [
+        StdfRecord::new(REC_FAR),
+        StdfRecord::STR(first),
+        StdfRecord::STR(last),
+    ] {
+        writer.write_record(&rec).unwrap();
+    }
+    let data = writer.finish().unwrap();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let merged: Vec<StdfRecord> = StrMergeIter::new(reader.get_rawdata_iter())
+        .flatten()
+        .collect();
+    let StdfRecord::STR(str_rec) = &merged[1] else {
+        panic!("STR is expected")
+    };
+    // positions are rebased to the bit base of the first record
+    assert_eq!(str_rec.bit_pos.to_u64_vec(), vec![1, u64::MAX - 10]);
+}
@@ -0,0 +1,89 @@
+//
+// flags_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{flags::*, FTR, MPR, PRR, PTR};
+
+#[test]
+fn test_flags_test() {
+    let flags = PtrTestFlags::from([0b1000_0001]);
+    assert!(flags.alarm());
+    assert!(flags.is_fail());
+    assert!(flags.is_valid());
+    assert_eq!(flags.passed(), Some(false));
+    assert_eq!(<[u8; 1]>::from(flags), [0b1000_0001]);
+
+    // fail bit is ignored without pass/fail indication
+    let flags = PtrTestFlags::new([0b1100_0000]);
+    assert!(!flags.is_fail());
+    assert_eq!(flags.passed(), None);
+
+    let flags = PtrTestFlags(0b0001_0000);
+    assert!(flags.not_executed());
+    assert!(!flags.is_valid());
+    assert_eq!(flags.passed(), None);
+
+    let ptr = PTR::default();
+    assert_eq!(ptr.passed(), Some(true));
+    let mpr = MPR {
+        test_flg: [0b1000_0000],
+        ..Default::default()
+    };
+    assert_eq!(mpr.passed(), Some(false));
+    let ftr = FTR {
+        test_flg: [0b0100_0000],
+        ..Default::default()
+    };
+    assert_eq!(ftr.passed(), None);
+
+    let parm = ParmFlags::from([0b1101_0000]);
+    assert!(parm.under_lo_limit());
+    assert!(!parm.over_hi_limit());
+    assert!(parm.lo_limit_inclusive() && parm.hi_limit_inclusive());
+}
+
+#[test]
+fn part_and_opt_flags_test() {
+    let part = PartFlags::from([0b0000_0110]);
+    assert!(part.retested());
+    assert!(part.supersedes_xy() && !part.supersedes_part_id());
+    assert!(part.abnormal_end());
+    assert_eq!(part.passed(), Some(true));
+
+    let prr = PRR {
+        part_flg: [0b0000_1000],
+        ..Default::default()
+    };
+    assert_eq!(prr.passed(), Some(false));
+    let prr = PRR {
+        part_flg: [0b0001_1000],
+        ..Default::default()
+    };
+    assert_eq!(prr.passed(), None);
+    assert!(!PartFlags::from(prr.part_flg).is_fail());
+
+    let opt = PtrOptFlags::from([0b1001_0001]);
+    assert!(opt.res_scal_invalid());
+    assert!(opt.lo_limit_invalid() && !opt.hi_limit_invalid());
+    assert!(opt.no_hi_limit() && !opt.no_lo_limit());
+
+    let opt = MprOptFlags::from([0b0000_0110]);
+    assert!(opt.start_incr_invalid() && opt.no_lo_spec());
+    assert!(!opt.no_hi_spec());
+
+    let opt = FtrOptFlags::from([0b0011_0000]);
+    assert!(opt.fail_ad_invalid() && opt.vect_off_invalid());
+    assert!(!opt.cycl_cnt_invalid());
+
+    let fmu = StrFmuFlags::from([0b0001_0101]);
+    assert!(fmu.mask_map_used() && fmu.fal_map_used() && fmu.pattern_changed());
+    assert!(!fmu.mask_map_unchanged());
+    assert_eq!(fmu.bits(), 0b0001_0101);
+}
@@ -0,0 +1,122 @@
+//
+// jsonl_export_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "jsonl")]
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+#[cfg(feature = "gzip")]
+use rust_stdf::stdf_file::*;
+use rust_stdf::{jsonl_export::*, stdf_record_type::*, StdfRecord, V1};
+use serde_json::json;
+#[cfg(feature = "gzip")]
+use serde_json::Value;
+
+#[cfg(feature = "gzip")]
+fn export(options: JsonlOptions) -> Vec<Value> {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    let mut output = Vec::new();
+    let count = to_jsonl_stream(&mut reader, &mut output, options).unwrap();
+    let lines: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len() as u64, count);
+    lines
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn jsonl_stream_test() {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    let raws: Vec<(u64, u64)> = reader
+        .get_rawdata_iter()
+        .map(|r| r.unwrap())
+        // offset of the header
+        .map(|r| (r.header.get_type(), r.offset - 4))
+        .collect();
+
+    let lines = export(JsonlOptions::default());
+    assert_eq!(lines.len(), raws.len());
+    for (line, (rec_type, offset)) in lines.iter().zip(raws.iter()) {
+        assert_eq!(line["rec"], get_rec_name_from_code(*rec_type));
+        assert_eq!(line["offset"], *offset);
+        assert!(line["fields"].is_object());
+    }
+    // FAR is at the start of the file
+    assert_eq!(lines[0]["offset"], 0);
+    // B1 flags are bit strings
+    let ptr = lines.iter().find(|l| l["rec"] == "PTR").unwrap();
+    let test_flg = ptr["fields"]["TEST_FLG"].as_str().unwrap();
+    assert_eq!(test_flg.len(), 8);
+    assert!(test_flg.chars().all(|c| c == '0' || c == '1'));
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn jsonl_filter_test() {
+    let options = JsonlOptions {
+        flag_format: FlagFormat::Hex,
+        rec_types: REC_PRR,
+    };
+    let lines = export(options);
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|l| l["rec"] == "PRR"));
+    let part_flg = lines[0]["fields"]["PART_FLG"].as_str().unwrap();
+    assert!(part_flg.starts_with("0x") && part_flg.len() == 4);
+}
+
+#[test]
+fn jsonl_typed_fields_test() {
+    let mut ftr = StdfRecord::new(REC_FTR);
+    if let StdfRecord::FTR(ref mut ftr_rec) = ftr {
+        ftr_rec.test_flg = [0b1000_0001];
+        ftr_rec.fail_pin = vec![0b0000_0101, 0b1000_0000];
+    }
+    let fields = record_to_json(&ftr, FlagFormat::Bits);
+    assert_eq!(fields["TEST_FLG"], "10000001");
+    assert_eq!(fields["FAIL_PIN"], json!([0, 2, 15]));
+    assert_eq!(fields["SPIN_MAP"], json!([]));
+
+    let mut gdr = StdfRecord::new(REC_GDR);
+    if let StdfRecord::GDR(ref mut gdr_rec) = gdr {
+        gdr_rec.gen_data = vec![
+            V1::B0,
+            V1::U2(300),
+            V1::Cn("abc".to_string()),
+            V1::Bn(vec![0x0F, 0xA0]),
+            V1::Dn(vec![0b10]),
+        ];
+    }
+    let fields = record_to_json(&gdr, FlagFormat::Bits);
+    assert_eq!(
+        fields["GEN_DATA"],
+        json!([
+            {"type": "B0", "value": null},
+            {"type": "U2", "value": 300},
+            {"type": "Cn", "value": "abc"},
+            {"type": "Bn", "value": "0FA0"},
+            {"type": "Dn", "value": [1]},
+        ])
+    );
+
+    // omitted optional flag of PTR
+    let mut ptr = StdfRecord::new(REC_PTR);
+    if let StdfRecord::PTR(ref mut ptr_rec) = ptr {
+        ptr_rec.opt_flag = None;
+    }
+    let fields = record_to_json(&ptr, FlagFormat::Hex);
+    assert!(fields["OPT_FLAG"].is_null());
+    assert_eq!(fields["PARM_FLG"], "0x00");
+}
@@ -0,0 +1,109 @@
+//
+// map_export_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{map_export::*, wafermap::*, StdfRecord, HBR, MIR, PRR, WCR, WIR, WRR};
+
+fn build_wafer(pos_x: char, pos_y: char) -> WaferMap {
+    let mut records = vec![
+        StdfRecord::MIR(MIR {
+            lot_id: "LOT<1>".to_string(),
+            part_typ: "DEV".to_string(),
+            ..Default::default()
+        }),
+        StdfRecord::WCR(WCR {
+            die_ht: 2.5,
+            die_wid: 3.0,
+            wf_units: 3,
+            wf_flat: 'D',
+            pos_x,
+            pos_y,
+            ..Default::default()
+        }),
+        StdfRecord::WIR(WIR {
+            head_num: 1,
+            wafer_id: "W01".to_string(),
+            ..Default::default()
+        }),
+    ];
+    // a 3 x 2 map, (2, 1) has no die
+    for (x, y, bin) in [(0, 0, 1), (1, 0, 1), (2, 0, 300), (0, 1, 2), (1, 1, 1)] {
+        records.push(StdfRecord::PRR(PRR {
+            head_num: 1,
+            part_flg: [if bin == 1 { 0 } else { 0b0000_1000 }],
+            hard_bin: bin,
+            soft_bin: bin,
+            x_coord: x,
+            y_coord: y,
+            ..Default::default()
+        }));
+    }
+    records.push(StdfRecord::WRR(WRR {
+        head_num: 1,
+        ..Default::default()
+    }));
+    records.push(StdfRecord::HBR(HBR {
+        head_num: 255,
+        hbin_num: 2,
+        hbin_pf: 'F',
+        hbin_nam: "OPEN & SHORT".to_string(),
+        ..Default::default()
+    }));
+
+    let mut builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    records.iter().for_each(|r| builder.feed(r));
+    builder.finish().remove(0)
+}
+
+#[test]
+fn sinf_export_test() {
+    let wafer = build_wafer('R', 'D');
+    // bin 300 requires 3 hex digits
+    let expected = "DEVICE:DEV\n\
+        LOT:LOT<1>\n\
+        WAFER:W01\n\
+        FNLOC:180\n\
+        ROWCT:2\n\
+        COLCT:3\n\
+        BCEQU:001\n\
+        REFPX:0\n\
+        REFPY:0\n\
+        DUTMS:mm\n\
+        XDIES:3\n\
+        YDIES:2.5\n\
+        RowData:001 001 12C\n\
+        RowData:002 001 ___\n";
+    assert_eq!(wafer.to_sinf(MapBinType::HardBin), expected);
+
+    // x increases to the left, y increases upward
+    let wafer = build_wafer('L', 'U');
+    let sinf = wafer.to_sinf(MapBinType::SoftBin);
+    assert!(sinf.contains("REFPX:2\nREFPY:1\n"));
+    assert!(sinf.contains("RowData:___ 001 002\nRowData:12C 001 001\n"));
+}
+
+#[test]
+fn e142_export_test() {
+    let wafer = build_wafer('R', 'D');
+    let xml = wafer.to_e142_xml(MapBinType::HardBin);
+    assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(xml.contains(r#"<Dimension X="3" Y="2"/>"#));
+    assert!(xml.contains(r#"<DeviceSize X="3" Y="2.5"/>"#));
+    assert!(xml.contains("<LotId>LOT&lt;1&gt;</LotId>"));
+    assert!(xml.contains(r#"<BinCodeMap BinType="HexaDecimal" NullBin="FFF">"#));
+    assert!(xml.contains(
+        r#"<BinDefinition BinCode="001" BinCount="3" BinQuality="Pass" BinDescription=""/>"#
+    ));
+    assert!(xml.contains(
+        r#"<BinDefinition BinCode="002" BinCount="1" BinQuality="Fail" BinDescription="OPEN &amp; SHORT"/>"#
+    ));
+    assert!(xml.contains("<BinCode>00100112C</BinCode>\n          <BinCode>002001FFF</BinCode>"));
+    assert!(xml.trim_end().ends_with("</MapData>"));
+}
@@ -0,0 +1,187 @@
+//
+// map_render_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{map_render::*, wafermap::*, StdfRecord, HBR, MIR, PIR, PRR, PTR, WCR, WIR, WRR};
+use std::collections::BTreeMap;
+
+fn build_records() -> Vec<StdfRecord> {
+    let mut records = vec![
+        StdfRecord::MIR(MIR {
+            lot_id: "LOT1".to_string(),
+            ..Default::default()
+        }),
+        StdfRecord::WCR(WCR {
+            wf_flat: 'D',
+            center_x: -32768,
+            center_y: -32768,
+            pos_x: 'R',
+            pos_y: 'D',
+            ..Default::default()
+        }),
+        StdfRecord::WIR(WIR {
+            head_num: 1,
+            wafer_id: "W01".to_string(),
+            ..Default::default()
+        }),
+    ];
+    for (i, (x, y, bin)) in [(0, 0, 1), (1, 0, 1), (2, 0, 2), (0, 1, 2), (1, 1, 1)]
+        .into_iter()
+        .enumerate()
+    {
+        records.push(StdfRecord::PIR(PIR {
+            head_num: 1,
+            site_num: 1,
+        }));
+        records.push(StdfRecord::PTR(PTR {
+            test_num: 100,
+            head_num: 1,
+            site_num: 1,
+            // the last die has an invalid result
+            test_flg: [if i == 4 { 0b0000_0010 } else { 0 }],
+            result: i as f32,
+            ..Default::default()
+        }));
+        records.push(StdfRecord::PRR(PRR {
+            head_num: 1,
+            site_num: 1,
+            part_flg: [if bin == 1 { 0 } else { 0b0000_1000 }],
+            hard_bin: bin,
+            soft_bin: bin,
+            x_coord: x,
+            y_coord: y,
+            ..Default::default()
+        }));
+    }
+    records.push(StdfRecord::WRR(WRR {
+        head_num: 1,
+        ..Default::default()
+    }));
+    records.push(StdfRecord::HBR(HBR {
+        head_num: 255,
+        hbin_num: 2,
+        hbin_pf: 'F',
+        hbin_nam: "OPEN<SHORT>".to_string(),
+        ..Default::default()
+    }));
+    records
+}
+
+fn build_wafer() -> WaferMap {
+    let mut builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    build_records().iter().for_each(|r| builder.feed(r));
+    builder.finish().remove(0)
+}
+
+#[test]
+fn heatmap_builder_test() {
+    let mut builder = HeatmapBuilder::new(100);
+    build_records().iter().for_each(|r| builder.feed(r));
+    let heatmaps = builder.finish();
+    assert_eq!(heatmaps.len(), 1);
+    let values = &heatmaps[0];
+    let expected = BTreeMap::from([((0, 0), 0.0), ((1, 0), 1.0), ((2, 0), 2.0), ((0, 1), 3.0)]);
+    assert_eq!(values, &expected);
+}
+
+#[test]
+fn heatmap_empty_wafer_id_test() {
+    let ptr = |result: f32| {
+        StdfRecord::PTR(PTR {
+            test_num: 100,
+            head_num: 1,
+            site_num: 1,
+            result,
+            ..Default::default()
+        })
+    };
+    let prr = StdfRecord::PRR(PRR {
+        head_num: 1,
+        site_num: 1,
+        x_coord: 0,
+        y_coord: 0,
+        ..Default::default()
+    });
+    let wir = StdfRecord::WIR(WIR {
+        head_num: 1,
+        ..Default::default()
+    });
+    let wrr = StdfRecord::WRR(WRR {
+        head_num: 1,
+        ..Default::default()
+    });
+    let records = [
+        wir.clone(),
+        ptr(1.0),
+        prr.clone(),
+        wrr.clone(),
+        // another wafer without wafer id
+        wir,
+        ptr(2.0),
+        prr.clone(),
+        wrr,
+        // outside of WIR/WRR
+        ptr(3.0),
+        prr,
+    ];
+    let mut builder = HeatmapBuilder::new(100);
+    let mut map_builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    for rec in records.iter() {
+        builder.feed(rec);
+        map_builder.feed(rec);
+    }
+    let heatmaps = builder.finish();
+    assert_eq!(heatmaps.len(), map_builder.finish().len());
+    let values: Vec<f64> = heatmaps.iter().map(|m| m[&(0, 0)]).collect();
+    assert_eq!(values, [1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn svg_render_test() {
+    let wafer = build_wafer();
+    let svg = wafer.to_svg(&RenderOptions::default());
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("<circle "));
+    assert!(svg.contains("<polygon "));
+    // 5 dies + 2 legend items + background
+    assert_eq!(svg.matches("<rect ").count(), 8);
+    assert!(svg.contains(">LOT1 / W01</text>"));
+    assert!(svg.contains(">1 (3)</text>"));
+    assert!(svg.contains(">2 OPEN&lt;SHORT&gt; (2)</text>"));
+
+    let wafer_heatmap = BTreeMap::from([((0, 0), 1.5), ((1, 1), 4.5)]);
+    let options = RenderOptions {
+        heatmap: Some(&wafer_heatmap),
+        legend: false,
+        ..Default::default()
+    };
+    let svg = wafer.to_svg(&options);
+    assert!(!svg.contains("linearGradient"));
+    // dies without value are gray
+    assert_eq!(svg.matches(r##"fill="#d3d3d3""##).count(), 3);
+}
+
+#[test]
+fn png_render_test() {
+    let wafer = build_wafer();
+    let options = RenderOptions::default();
+    let png = wafer.to_png(&options);
+    assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+    assert_eq!(&png[12..16], b"IHDR");
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+
+    // same size as the SVG
+    let svg = wafer.to_svg(&options);
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    assert!(svg.contains(&format!(r#"width="{}" height="{}""#, width, height)));
+    assert!(width > 0 && height > 0);
+}
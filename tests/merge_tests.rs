@@ -0,0 +1,99 @@
+//
+// merge_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+use rust_stdf::merge::*;
+#[cfg(feature = "gzip")]
+use rust_stdf::{
+    stdf_file::*,
+    stdf_record_type::*,
+    summary::OVERALL_HEAD,
+    validate::{validate_file, Severity},
+    ByteOrder, StdfRecord,
+};
+#[cfg(feature = "gzip")]
+use std::path::PathBuf;
+
+#[cfg(feature = "gzip")]
+fn count_parts(path: &PathBuf) -> u64 {
+    let mut reader = StdfReader::new(path).unwrap();
+    reader
+        .get_rawdata_iter()
+        .map(|r| r.unwrap())
+        .filter(|r| r.is_type(REC_PRR))
+        .count() as u64
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn merge_files_test() {
+    let srcs = [demo_path("lot2.stdf.gz"), demo_path("lot3.stdf.gz")];
+    let dst = std::env::temp_dir().join("rust_stdf_merge_test.stdf");
+    let options = MergeOptions {
+        byte_order: Some(ByteOrder::BigEndian),
+    };
+    let report = merge_files(&srcs, &dst, options).unwrap();
+    assert_eq!(report.file_count, 2);
+    assert_eq!(
+        report.part_count,
+        count_parts(&srcs[0]) + count_parts(&srcs[1])
+    );
+
+    let mut reader = StdfReader::new(&dst).unwrap();
+    assert_eq!(reader.get_byte_order(), ByteOrder::BigEndian);
+    let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len() as u64, report.record_count);
+    assert_eq!(records.iter().filter(|r| r.is_type(REC_FAR)).count(), 1);
+    assert_eq!(records.iter().filter(|r| r.is_type(REC_MIR)).count(), 1);
+    assert_eq!(records.iter().filter(|r| r.is_type(REC_MRR)).count(), 1);
+    assert!(records[records.len() - 1].is_type(REC_MRR));
+    if let StdfRecord::FAR(far_rec) = &records[0] {
+        // big endian
+        assert_eq!(far_rec.cpu_type, 1);
+    } else {
+        panic!("first record is not FAR");
+    }
+
+    // summary is recomputed from the parts of both files
+    let overall_pcr = records.iter().find_map(|r| match r {
+        StdfRecord::PCR(pcr_rec) if pcr_rec.head_num == OVERALL_HEAD => Some(pcr_rec),
+        _ => None,
+    });
+    assert_eq!(overall_pcr.unwrap().part_cnt as u64, report.part_count);
+    let overall_hbin_cnt: u64 = records
+        .iter()
+        .filter_map(|r| match r {
+            StdfRecord::HBR(hbr_rec) if hbr_rec.head_num == OVERALL_HEAD => {
+                Some(hbr_rec.hbin_cnt as u64)
+            }
+            _ => None,
+        })
+        .sum();
+    assert_eq!(overall_hbin_cnt, report.part_count);
+
+    let diagnostics = validate_file(&dst).unwrap();
+    let _ = std::fs::remove_file(&dst);
+    assert!(
+        diagnostics.iter().all(|d| d.severity != Severity::Error),
+        "{:?}",
+        diagnostics
+    );
+}
+
+#[test]
+fn merge_no_source_test() {
+    let srcs: [&str; 0] = [];
+    let dst = std::env::temp_dir().join("rust_stdf_merge_empty.stdf");
+    assert!(merge_files(&srcs, &dst, MergeOptions::default()).is_err());
+}
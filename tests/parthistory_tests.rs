@@ -0,0 +1,139 @@
+//
+// parthistory_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+#[cfg(feature = "gzip")]
+use rust_stdf::stdf_file::*;
+use rust_stdf::{parthistory::*, StdfRecord, FAR, PRR, WIR, WRR};
+
+fn prr(part_flg: u8, hard_bin: u16, xy: (i16, i16), part_id: &str) -> StdfRecord {
+    StdfRecord::PRR(PRR {
+        head_num: 1,
+        site_num: 1,
+        part_flg: [part_flg | if hard_bin == 1 { 0 } else { 0b0000_1000 }],
+        hard_bin,
+        x_coord: xy.0,
+        y_coord: xy.1,
+        part_id: part_id.to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn part_history_link_test() {
+    let wir = StdfRecord::WIR(WIR {
+        head_num: 1,
+        wafer_id: "W1".to_string(),
+        ..Default::default()
+    });
+    let wrr = StdfRecord::WRR(WRR {
+        head_num: 1,
+        ..Default::default()
+    });
+    let records = vec![
+        StdfRecord::FAR(FAR::default()),
+        wir.clone(),
+        prr(0, 1, (0, 0), "1"),
+        prr(0, 5, (1, 0), "2"),
+        prr(0, 3, (2, 0), "3"),
+        // retest of (1, 0), passed
+        prr(0b10, 1, (1, 0), "4"),
+        wrr.clone(),
+        // packaged parts, linked by part id
+        prr(0, 4, (-32768, -32768), "A"),
+        prr(0b01, 2, (-32768, -32768), "A"),
+        prr(0, 6, (-32768, -32768), ""),
+        // another file retests the wafer
+        StdfRecord::FAR(FAR::default()),
+        wir,
+        prr(0, 7, (2, 0), "1"),
+        prr(0, 2, (2, 0), "2"),
+        wrr,
+    ];
+    let mut builder = PartHistoryBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+    let histories = builder.finish();
+
+    assert_eq!(histories.part_count(), 5);
+    assert_eq!(histories.retested_count(), 3);
+    let die = &histories.parts[2];
+    assert_eq!(die.retest_count(), 2);
+    assert_eq!(die.attempts[2].file_index, 1);
+    assert_eq!(die.get(PartView::FirstPass).hard_bin, 3);
+    assert_eq!(die.get(PartView::LastPass).hard_bin, 2);
+    // all failed, the lowest bin
+    assert_eq!(die.get(PartView::BestBin).hard_bin, 2);
+    let part = &histories.parts[3];
+    assert_eq!(part.attempts.len(), 2);
+    assert_eq!(part.get(PartView::BestBin).hard_bin, 2);
+
+    assert_eq!(histories.good_count(PartView::FirstPass), 1);
+    assert_eq!(histories.good_count(PartView::LastPass), 2);
+    assert_eq!(histories.yield_rate(PartView::BestBin), Some(0.4));
+    let hbins = histories.hbin_counts(PartView::LastPass);
+    assert_eq!(hbins.get(&2), Some(&2));
+    assert_eq!(hbins.get(&5), None);
+    assert_eq!(
+        PartHistories::default().yield_rate(PartView::LastPass),
+        None
+    );
+}
+
+#[test]
+fn part_id_link_test() {
+    let records = [
+        StdfRecord::FAR(FAR::default()),
+        // the same PART_ID without bit 0 of PART_FLG
+        prr(0, 5, (-32768, -32768), "B"),
+        prr(0, 1, (-32768, -32768), "B"),
+        // another file tests the part again
+        StdfRecord::FAR(FAR::default()),
+        prr(0, 2, (-32768, -32768), "B"),
+    ];
+    for (link_part_id_across_files, part_count) in [(false, 3), (true, 2)] {
+        let mut builder = PartHistoryBuilder::with_options(PartHistoryOptions {
+            link_part_id_across_files,
+        });
+        records.iter().for_each(|r| builder.feed(r));
+        let histories = builder.finish();
+        assert_eq!(histories.part_count(), part_count);
+        assert_eq!(histories.parts[0].attempts.len(), 1);
+        assert_eq!(histories.parts[1].get(PartView::FirstPass).hard_bin, 1);
+        assert_eq!(
+            histories.parts[part_count - 1]
+                .get(PartView::LastPass)
+                .hard_bin,
+            2
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn demo_file_part_history_test() {
+    let stdf_path = demo_path("lot2.stdf.gz");
+
+    let mut builder = PartHistoryBuilder::new();
+    let mut reader = StdfReader::new(&stdf_path).unwrap();
+    for rec in reader.get_record_iter().map(|r| r.unwrap()) {
+        builder.feed(&rec);
+    }
+    let histories = builder.finish();
+    // same as the wafer map
+    assert_eq!(histories.part_count(), 1456);
+    assert_eq!(histories.retested_count(), 113);
+    assert_eq!(histories.good_count(PartView::LastPass), 1389);
+    assert!(histories.good_count(PartView::FirstPass) <= 1389);
+    assert!(histories.good_count(PartView::BestBin) >= 1389);
+}
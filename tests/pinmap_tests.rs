@@ -0,0 +1,147 @@
+//
+// pinmap_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{pinmap::*, KxUf, StdfRecord, FTR, MPR, PGR, PLR, PMR, STR};
+
+fn build_pin_map() -> PinMap {
+    let mut pin_map = PinMap::new();
+    for (pmr_indx, head_num, site_num, log_nam) in [
+        (1, 1, 1, "VDD"),
+        (2, 1, 1, "IO0"),
+        (1, 1, 2, "VDD_S2"),
+        // shared by all sites
+        (4, 1, 255, "VSS"),
+    ] {
+        pin_map.feed(&StdfRecord::PMR(PMR {
+            pmr_indx,
+            chan_nam: format!("CH{}", pmr_indx),
+            log_nam: log_nam.to_string(),
+            head_num,
+            site_num,
+            ..Default::default()
+        }));
+    }
+    pin_map.feed(&StdfRecord::PGR(PGR {
+        grp_indx: 32768,
+        grp_nam: "ALL".to_string(),
+        indx_cnt: 3,
+        pmr_indx: vec![1, 2, 3],
+    }));
+    pin_map.feed(&StdfRecord::PLR(PLR {
+        grp_cnt: 1,
+        grp_indx: vec![32768],
+        grp_mode: vec![0x20],
+        grp_radx: vec![16],
+        pgm_char: vec!["HL".to_string()],
+        ..Default::default()
+    }));
+    pin_map
+}
+
+#[test]
+fn pin_lookup_test() {
+    let pin_map = build_pin_map();
+    assert_eq!(pin_map.get_pin(1, 1, 1).unwrap().log_nam, "VDD");
+    assert_eq!(pin_map.get_pin(1, 2, 1).unwrap().log_nam, "VDD_S2");
+    // no PMR of site 2, PMR of site 1 is not used
+    assert!(pin_map.get_pin(1, 2, 2).is_none());
+    assert!(pin_map.get_pin(1, 1, 3).is_none());
+    assert_eq!(pin_map.get_pin(1, 2, 4).unwrap().log_nam, "VSS");
+    assert!(pin_map.get_pin(2, 1, 4).is_none());
+
+    assert_eq!(pin_map.get_group(32768).unwrap().grp_nam, "ALL");
+    let names: Vec<&str> = pin_map
+        .get_group_pins(1, 2, 32768)
+        .iter()
+        .map(|p| p.log_nam.as_str())
+        .collect();
+    assert_eq!(names, vec!["VDD_S2"]);
+    assert_eq!(pin_map.get_pin_groups(2)[0].grp_indx, 32768);
+    assert!(pin_map.get_pin_groups(4).is_empty());
+
+    let info = pin_map.get_pin_list(32768).unwrap();
+    assert_eq!((info.grp_mode, info.grp_radx), (0x20, 16));
+    assert_eq!(info.pgm_char, "HL");
+    assert_eq!(info.rtn_char, "");
+}
+
+#[test]
+fn pin_resolve_test() {
+    let mut pin_map = build_pin_map();
+    let first = MPR {
+        test_num: 10,
+        head_num: 1,
+        site_num: 1,
+        rtn_icnt: 2,
+        rslt_cnt: 2,
+        rtn_stat: vec![1, 0],
+        rtn_rslt: vec![1.5, 0.5],
+        rtn_indx: Some(vec![1, 2]),
+        ..Default::default()
+    };
+    // RTN_INDX is omitted in the following MPR
+    let second = MPR {
+        test_num: 10,
+        head_num: 1,
+        site_num: 2,
+        rslt_cnt: 2,
+        rtn_rslt: vec![2.5, 3.5],
+        ..Default::default()
+    };
+    pin_map.feed(&StdfRecord::MPR(first.clone()));
+    pin_map.feed(&StdfRecord::MPR(second.clone()));
+    let results = pin_map.resolve_mpr(&first);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].pin.unwrap().log_nam, "VDD");
+    assert_eq!(
+        (results[0].rtn_stat, results[0].rtn_rslt),
+        (Some(1), Some(1.5))
+    );
+    let results = pin_map.resolve_mpr(&second);
+    assert_eq!(results[0].pin.unwrap().log_nam, "VDD_S2");
+    assert_eq!(
+        (results[1].rtn_stat, results[1].rtn_rslt),
+        (None, Some(3.5))
+    );
+
+    let ftr = FTR {
+        head_num: 1,
+        site_num: 1,
+        rtn_icnt: 1,
+        pgm_icnt: 2,
+        rtn_indx: vec![2],
+        rtn_stat: vec![5],
+        pgm_indx: vec![1, 3],
+        pgm_stat: vec![1, 2],
+        ..Default::default()
+    };
+    let (rtn, pgm) = pin_map.resolve_ftr(&ftr);
+    assert_eq!(rtn.len(), 1);
+    assert_eq!(
+        (rtn[0].pin.unwrap().log_nam.as_str(), rtn[0].state),
+        ("IO0", 5)
+    );
+    assert_eq!(pgm[1].pmr_indx, 3);
+    assert!(pgm[1].pin.is_none());
+
+    let str_rec = STR {
+        head_num: 1,
+        site_num: 2,
+        pmr_cnt: 3,
+        pmr_indx: KxUf::F2(vec![2, 1, 7]),
+        ..Default::default()
+    };
+    let pins = pin_map.resolve_str(&str_rec);
+    // PMR 2 is only defined on site 1
+    assert!(pins[0].is_none());
+    assert_eq!(pins[1].unwrap().log_nam, "VDD_S2");
+    assert!(pins[2].is_none());
+}
@@ -0,0 +1,132 @@
+//
+// redact_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_records;
+use rust_stdf::{redact::*, stdf_record_type::*, StdfRecord};
+#[cfg(feature = "gzip")]
+use rust_stdf::{stdf_file::*, ByteOrder, CompressType};
+#[cfg(feature = "gzip")]
+use std::io::Cursor;
+
+#[cfg(feature = "gzip")]
+fn write_all(records: &[StdfRecord]) -> Vec<u8> {
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    for rec in records {
+        writer.write_record(rec).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn redact_stream_test() {
+    let mut source = demo_records();
+    // make sure the sensitive fields are present
+    for rec in source.iter_mut() {
+        match rec {
+            StdfRecord::MIR(mir_rec) => {
+                mir_rec.lot_id = "ACME_LOT_42".to_string();
+                mir_rec.facil_id = "ACME_FAB".to_string();
+            }
+            StdfRecord::PRR(prr_rec) => prr_rec.part_id = "DIE".to_string(),
+            _ => {}
+        }
+    }
+    let mut dtr = StdfRecord::new(REC_DTR);
+    if let StdfRecord::DTR(ref mut dtr_rec) = dtr {
+        dtr_rec.text_dat = "lot ACME_LOT_42 retest".to_string();
+    }
+    source.push(dtr);
+    let data = write_all(&source);
+
+    let mut redactor = Redactor::new();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    let report = redact_stream(&mut reader, &mut writer, &mut redactor).unwrap();
+    assert_eq!(report.read_count, source.len() as u64);
+    assert_eq!(report.write_count, source.len() as u64);
+
+    let data = writer.finish().unwrap();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let redacted: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(redacted.len(), source.len());
+    for (src, rec) in source.iter().zip(redacted.iter()) {
+        assert_eq!(src.get_type(), rec.get_type());
+        match (src, rec) {
+            (StdfRecord::MIR(_), StdfRecord::MIR(mir_rec)) => {
+                assert_eq!(mir_rec.lot_id, "LOT_1");
+                assert_eq!(mir_rec.facil_id, "FACILITY_1");
+            }
+            // same part id is redacted to the same value
+            (StdfRecord::PRR(src_rec), StdfRecord::PRR(prr_rec)) => {
+                assert_eq!(prr_rec.part_id, "PART_1");
+                assert_eq!(src_rec.hard_bin, prr_rec.hard_bin);
+                assert_eq!(src_rec.x_coord, prr_rec.x_coord);
+            }
+            (StdfRecord::DTR(_), StdfRecord::DTR(dtr_rec)) => {
+                assert!(!dtr_rec.text_dat.contains("ACME"));
+            }
+            // test names are kept by default
+            (StdfRecord::PTR(_), StdfRecord::PTR(_)) => assert_eq!(src, rec),
+            _ => {}
+        }
+    }
+    assert_eq!(redactor.lookup(FieldKind::Part, "DIE"), Some("PART_1"));
+    assert_eq!(redactor.lookup(FieldKind::Lot, "UNKNOWN"), None);
+}
+
+#[test]
+fn redact_hash_test() {
+    let options = RedactOptions {
+        mode: RedactMode::Hash,
+        salt: "salt".to_string(),
+        test_names: true,
+        ..Default::default()
+    };
+    let mut first = Redactor::with_options(options.clone());
+    let mut second = Redactor::with_options(options);
+    let hashed = first.redact_value(FieldKind::Test, "VDD_LEAKAGE");
+    assert_eq!(hashed.len(), 16);
+    assert_ne!(hashed, "VDD_LEAKAGE");
+    // consistent across redactors with the same salt
+    assert_eq!(hashed, second.redact_value(FieldKind::Test, "VDD_LEAKAGE"));
+    assert_eq!(first.redact_value(FieldKind::Test, ""), "");
+
+    let mut tsr = StdfRecord::new(REC_TSR);
+    if let StdfRecord::TSR(ref mut tsr_rec) = tsr {
+        tsr_rec.test_nam = "VDD_LEAKAGE".to_string();
+    }
+    first.redact(&mut tsr);
+    assert!(matches!(&tsr, StdfRecord::TSR(t) if t.test_nam == hashed));
+
+    let other_salt = RedactOptions {
+        mode: RedactMode::Hash,
+        salt: "pepper".to_string(),
+        ..Default::default()
+    };
+    let mut third = Redactor::with_options(other_salt);
+    assert_ne!(hashed, third.redact_value(FieldKind::Test, "VDD_LEAKAGE"));
+    // test names are not redacted by default
+    assert_eq!(third.rec_types() & REC_TSR, 0);
+}
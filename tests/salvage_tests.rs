@@ -0,0 +1,94 @@
+//
+// salvage_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use common::demo_path;
+use rust_stdf::{
+    salvage::*, stdf_file::*, stdf_record_type::*, summary::OVERALL_HEAD, CompressType, StdfRecord,
+};
+use std::io::Cursor;
+
+#[test]
+fn truncated_file_salvage_test() {
+    let stdf_path = demo_path("lot3.stdf.gz");
+
+    // dump the demo file uncompressed, and
+    // cut it in the middle of the last PIR/PRR
+    let mut reader = StdfReader::new(&stdf_path).unwrap();
+    let order = reader.get_byte_order();
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    let mut last_pir_end = 0;
+    let mut total_parts = 0;
+    for raw in reader.get_rawdata_iter() {
+        let raw = raw.unwrap();
+        writer.write_raw_data(&raw).unwrap();
+        if raw.is_type(REC_PIR) {
+            last_pir_end = raw.offset + raw.raw_data.len() as u64;
+            total_parts += 1;
+        }
+    }
+    let full_data = writer.finish().unwrap();
+    // cut 3 bytes after the first record following the last PIR
+    let cut_pos = (last_pir_end as usize + 4 + 3).min(full_data.len() - 1);
+    let truncated = full_data[..cut_pos].to_vec();
+
+    let mut reader = StdfReader::from(Cursor::new(truncated), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    let report = salvage_stream(&mut reader, &mut writer, SalvageOptions::default()).unwrap();
+    let repaired = writer.finish().unwrap();
+
+    assert!(report.read_error.is_some());
+    assert_eq!(
+        report.synthesized.last().map(|r| r.get_type()),
+        Some(REC_MRR)
+    );
+    // at least one part is not finished
+    let synthesized_prr: Vec<&StdfRecord> = report
+        .synthesized
+        .iter()
+        .filter(|r| r.is_type(REC_PRR))
+        .collect();
+    assert!(!synthesized_prr.is_empty());
+    for rec in synthesized_prr {
+        if let StdfRecord::PRR(prr_rec) = rec {
+            // abnormal end of testing
+            assert_eq!(prr_rec.part_flg[0] & 0b0000_0100, 0b0000_0100);
+            assert_eq!(prr_rec.hard_bin, 0);
+        }
+    }
+
+    // repaired file should be complete, and
+    // the overall PCR should count every PIR
+    let mut reader = StdfReader::from(Cursor::new(repaired), &CompressType::Uncompressed).unwrap();
+    let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(
+        records.len() as u64,
+        report.record_count + report.synthesized.len() as u64
+    );
+    assert!(records[0].is_type(REC_FAR));
+    assert!(records[records.len() - 1].is_type(REC_MRR));
+    let overall_pcr = records.iter().find_map(|r| match r {
+        StdfRecord::PCR(pcr_rec) if pcr_rec.head_num == OVERALL_HEAD => Some(pcr_rec),
+        _ => None,
+    });
+    match overall_pcr {
+        Some(pcr_rec) => assert_eq!(pcr_rec.part_cnt, total_parts),
+        None => panic!("overall PCR is not synthesized"),
+    }
+
+    // nothing to synthesize for a complete file
+    let mut salvage = Salvage::new();
+    records.iter().for_each(|r| salvage.feed(r));
+    assert!(salvage.finish().is_empty());
+}
@@ -0,0 +1,158 @@
+//
+// scandiag_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{scandiag::*, KxUf, StdfRecord, CDR, CNR, NMR, PIR, PMR, PRR, PSR, SSR, STR};
+
+fn build_diagnosis() -> ScanDiagnosis {
+    let records = vec![
+        StdfRecord::PMR(PMR {
+            pmr_indx: 2,
+            log_nam: "SO_2".to_string(),
+            ..Default::default()
+        }),
+        StdfRecord::NMR(NMR {
+            totm_cnt: 1,
+            locm_cnt: 1,
+            pmr_indx: vec![1],
+            atpg_nam: vec!["scan_out\"1\"".to_string()],
+            ..Default::default()
+        }),
+        StdfRecord::PSR(PSR {
+            cont_flg: [1],
+            psr_indx: 3,
+            locp_cnt: 1,
+            pat_bgn: vec![0],
+            pat_end: vec![99],
+            pat_file: vec!["stuck.stil".to_string()],
+            pat_lbl: vec!["stuck".to_string()],
+            ..Default::default()
+        }),
+        StdfRecord::PSR(PSR {
+            psr_indx: 3,
+            locp_cnt: 1,
+            pat_bgn: vec![100],
+            pat_end: vec![199],
+            pat_file: vec!["trans.stil".to_string()],
+            ..Default::default()
+        }),
+        StdfRecord::CDR(CDR {
+            cont_flg: [1],
+            cdr_indx: 0,
+            chn_nam: "chain0".to_string(),
+            chn_len: 4,
+            lst_cnt: 2,
+            cell_lst: vec!["c0".to_string(), "c1".to_string()],
+            ..Default::default()
+        }),
+        StdfRecord::CDR(CDR {
+            cdr_indx: 0,
+            lst_cnt: 2,
+            cell_lst: vec!["c2".to_string(), "c3".to_string()],
+            ..Default::default()
+        }),
+        StdfRecord::SSR(SSR {
+            ssr_nam: "scan_struct".to_string(),
+            chn_cnt: 1,
+            chn_list: vec![0],
+        }),
+        StdfRecord::CNR(CNR {
+            chn_num: 0,
+            bit_pos: 1,
+            cell_nam: "top/u1/reg".to_string(),
+        }),
+        StdfRecord::PIR(PIR {
+            head_num: 1,
+            site_num: 1,
+        }),
+        StdfRecord::STR(STR {
+            test_num: 10,
+            head_num: 1,
+            site_num: 1,
+            psr_ref: 3,
+            cyc_base: 50,
+            cyc_cnt: 2,
+            cyc_ofst: KxUf::F1(vec![10, 100]),
+            pmr_cnt: 2,
+            pmr_indx: KxUf::F1(vec![1, 2]),
+            chn_cnt: 2,
+            chn_num: KxUf::F1(vec![0, 0]),
+            bpos_cnt: 2,
+            bit_pos: KxUf::F1(vec![1, 3]),
+            exp_cnt: 2,
+            exp_data: vec![1, 0],
+            cap_cnt: 2,
+            cap_data: vec![0, 1],
+            ..Default::default()
+        }),
+        StdfRecord::PRR(PRR {
+            head_num: 1,
+            site_num: 1,
+            x_coord: 3,
+            y_coord: -2,
+            part_id: "P1".to_string(),
+            ..Default::default()
+        }),
+    ];
+    let mut builder = ScanDiagnosisBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+    builder.finish()
+}
+
+#[test]
+fn scan_fail_resolve_test() {
+    let diagnosis = build_diagnosis();
+    assert_eq!(diagnosis.fails.len(), 2);
+    let first = &diagnosis.fails[0];
+    assert_eq!(first.part_id, "P1");
+    assert_eq!((first.x_coord, first.y_coord), (3, -2));
+    assert_eq!(first.cycle, Some(60));
+    assert_eq!(first.pat_file.as_deref(), Some("stuck.stil"));
+    assert_eq!(first.pat_lbl.as_deref(), Some("stuck"));
+    assert_eq!(first.pin_nam.as_deref(), Some("scan_out\"1\""));
+    assert_eq!(first.chn_nam.as_deref(), Some("chain0"));
+    assert_eq!(first.ssr_nam.as_deref(), Some("scan_struct"));
+    // CNR takes precedence over CDR cell list
+    assert_eq!(first.cell_nam.as_deref(), Some("top/u1/reg"));
+
+    let second = &diagnosis.fails[1];
+    assert_eq!(second.cycle, Some(150));
+    // pattern of the continuation PSR
+    assert_eq!(second.pat_file.as_deref(), Some("trans.stil"));
+    assert_eq!(second.pat_lbl.as_deref(), Some(""));
+    // no NMR entry, from PMR
+    assert_eq!(second.pin_nam.as_deref(), Some("SO_2"));
+    // from the continuation CDR
+    assert_eq!(second.cell_nam.as_deref(), Some("c3"));
+    assert_eq!((second.expected, second.captured), (Some(0), Some(1)));
+}
+
+#[test]
+fn scan_fail_export_test() {
+    let diagnosis = build_diagnosis();
+    let table = diagnosis.to_table();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("PART_ID\tX_COORD\tY_COORD"));
+    assert_eq!(
+        lines[2],
+        "P1\t3\t-2\t1\t1\t10\ttrans.stil\t\t150\t2\tSO_2\t0\tchain0\tscan_struct\t3\tc3\t0\t1"
+    );
+
+    let json = diagnosis.to_json();
+    assert!(json.starts_with("[\n  {\"PART_ID\": \"P1\", \"X_COORD\": 3"));
+    assert!(json.contains("\"PIN_NAM\": \"scan_out\\\"1\\\"\""));
+    assert!(json.ends_with("}\n]"));
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed[1]["CELL_NAM"], "c3");
+    assert_eq!(parsed[0]["CYCLE"], 60);
+
+    assert_eq!(ScanDiagnosis::default().to_json(), "[]");
+}
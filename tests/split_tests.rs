@@ -0,0 +1,230 @@
+//
+// split_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use common::demo_path;
+use rust_stdf::{
+    split::*,
+    stdf_file::*,
+    stdf_record_type::*,
+    summary::OVERALL_HEAD,
+    validate::{validate_stream, Severity},
+    ByteOrder, CompressType, StdfRecord, WIR, WRR,
+};
+use std::collections::HashSet;
+use std::io::Cursor;
+
+fn split_demo(split_by: SplitBy) -> Vec<SplitOutput<Vec<u8>>> {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    let order = reader.get_byte_order();
+    split_stream(&mut reader, split_by, |_| {
+        StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order)
+    })
+    .unwrap()
+}
+
+/// check the output is a complete file,
+/// and return the records
+fn check_output(out: &SplitOutput<Vec<u8>>) -> Vec<StdfRecord> {
+    let mut reader =
+        StdfReader::from(Cursor::new(out.output.clone()), &CompressType::Uncompressed).unwrap();
+    let diagnostics = validate_stream(&mut reader);
+    assert!(
+        diagnostics.iter().all(|d| d.severity != Severity::Error),
+        "{:?}: {:?}",
+        out.key,
+        diagnostics
+    );
+
+    let mut reader =
+        StdfReader::from(Cursor::new(out.output.clone()), &CompressType::Uncompressed).unwrap();
+    let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    assert!(records[0].is_type(REC_FAR));
+    assert!(records[1].is_type(REC_MIR));
+    assert!(records[records.len() - 1].is_type(REC_MRR));
+    let prr_cnt = records.iter().filter(|r| r.is_type(REC_PRR)).count() as u64;
+    assert_eq!(prr_cnt, out.part_count);
+    let overall_pcr = records.iter().find_map(|r| match r {
+        StdfRecord::PCR(pcr_rec) if pcr_rec.head_num == OVERALL_HEAD => Some(pcr_rec),
+        _ => None,
+    });
+    assert_eq!(overall_pcr.unwrap().part_cnt as u64, out.part_count);
+    records
+}
+
+#[test]
+fn split_by_wafer_test() {
+    let outputs = split_demo(SplitBy::Wafer);
+    assert_eq!(
+        outputs.iter().map(|o| o.part_count).sum::<u64>(),
+        1569,
+        "every PRR should be in one of the outputs"
+    );
+    for out in outputs.iter() {
+        let records = check_output(out);
+        let wafer_ids: HashSet<String> = records
+            .iter()
+            .filter_map(|r| match r {
+                StdfRecord::WIR(wir_rec) => Some(wir_rec.wafer_id.clone()),
+                _ => None,
+            })
+            .collect();
+        match &out.key {
+            SplitKey::Wafer(wafer_id) if !wafer_id.is_empty() => {
+                assert_eq!(
+                    wafer_ids.into_iter().collect::<Vec<_>>(),
+                    vec![wafer_id.clone()]
+                )
+            }
+            key => assert_eq!(key, &SplitKey::Wafer(String::new())),
+        }
+    }
+    assert!(outputs
+        .iter()
+        .any(|o| o.key == SplitKey::Wafer("GAL-LOT-02".to_string())));
+}
+
+#[test]
+fn split_by_site_test() {
+    let outputs = split_demo(SplitBy::Site);
+    assert!(!outputs.is_empty());
+    assert_eq!(outputs.iter().map(|o| o.part_count).sum::<u64>(), 1569);
+    for out in outputs.iter() {
+        let records = check_output(out);
+        let SplitKey::Site(head_num, site_num) = out.key else {
+            panic!("unexpected key {:?}", out.key);
+        };
+        assert!(records.iter().all(|r| match r {
+            StdfRecord::PRR(prr_rec) =>
+                prr_rec.head_num == head_num && prr_rec.site_num == site_num,
+            _ => true,
+        }));
+    }
+}
+
+#[test]
+fn split_by_part_count_test() {
+    let outputs = split_demo(SplitBy::PartCount(500));
+    assert_eq!(outputs.len(), 4);
+    assert_eq!(
+        outputs.iter().map(|o| o.part_count).collect::<Vec<_>>(),
+        vec![500, 500, 500, 69]
+    );
+    for (i, out) in outputs.iter().enumerate() {
+        assert_eq!(out.key, SplitKey::Chunk(i));
+        check_output(out);
+    }
+}
+
+#[test]
+fn split_byte_order_test() {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    let order = match reader.get_byte_order() {
+        ByteOrder::LittleEndian => ByteOrder::BigEndian,
+        ByteOrder::BigEndian => ByteOrder::LittleEndian,
+    };
+    let outputs = split_stream(&mut reader, SplitBy::PartCount(1000), |_| {
+        StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order)
+    })
+    .unwrap();
+    for out in outputs.iter() {
+        let records = check_output(out);
+        match &records[0] {
+            StdfRecord::FAR(far_rec) => assert_eq!(far_rec.cpu_type, order.cpu_type()),
+            _ => panic!("first record is not FAR"),
+        }
+    }
+}
+
+#[test]
+fn split_file_test() {
+    let dst_dir = std::env::temp_dir().join("rust_stdf_split_test");
+    std::fs::create_dir_all(&dst_dir).unwrap();
+    let paths = split_file(
+        demo_path("lot2.stdf.gz"),
+        &dst_dir,
+        SplitBy::PartCount(1000),
+    )
+    .unwrap();
+    let names: Vec<String> = paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(names, vec!["lot2_part0.stdf", "lot2_part1.stdf"]);
+    let mut total = 0;
+    for path in paths.iter() {
+        let mut reader = StdfReader::new(path).unwrap();
+        total += reader
+            .get_rawdata_iter()
+            .map(|r| r.unwrap())
+            .filter(|r| r.is_type(REC_PRR))
+            .count();
+    }
+    let _ = std::fs::remove_dir_all(&dst_dir);
+    assert_eq!(total, 1569);
+}
+
+#[test]
+fn split_file_name_collision_test() {
+    let dst_dir = std::env::temp_dir().join("rust_stdf_split_collision_test");
+    std::fs::create_dir_all(&dst_dir).unwrap();
+    let src = dst_dir.join("lot3.stdf");
+    let mut writer = StdfWriter::new(&src, ByteOrder::LittleEndian).unwrap();
+    writer.write_record(&StdfRecord::new(REC_FAR)).unwrap();
+    writer.write_record(&StdfRecord::new(REC_MIR)).unwrap();
+    // the wafer ids are the same in file names,
+    // on case-insensitive file systems
+    for wafer_id in ["W/1", "W_1", "w/1"] {
+        for rec in [
+            StdfRecord::WIR(WIR {
+                wafer_id: wafer_id.to_string(),
+                ..Default::default()
+            }),
+            StdfRecord::new(REC_PIR),
+            StdfRecord::new(REC_PRR),
+            StdfRecord::WRR(WRR {
+                wafer_id: wafer_id.to_string(),
+                ..Default::default()
+            }),
+        ] {
+            writer.write_record(&rec).unwrap();
+        }
+    }
+    writer.write_record(&StdfRecord::new(REC_MRR)).unwrap();
+    writer.finish().unwrap();
+
+    let paths = split_file(&src, &dst_dir, SplitBy::Wafer).unwrap();
+    let names: Vec<String> = paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    let part_counts: Vec<usize> = paths
+        .iter()
+        .map(|path| {
+            let mut reader = StdfReader::new(path).unwrap();
+            reader
+                .get_rawdata_iter()
+                .map(|r| r.unwrap())
+                .filter(|r| r.is_type(REC_PRR))
+                .count()
+        })
+        .collect();
+    let _ = std::fs::remove_dir_all(&dst_dir);
+    assert_eq!(
+        names,
+        vec!["lot3_W_1.stdf", "lot3_W_1_2.stdf", "lot3_w_1_3.stdf"]
+    );
+    // no output is overwritten
+    assert_eq!(part_counts, vec![1, 1, 1]);
+}
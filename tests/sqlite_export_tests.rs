@@ -0,0 +1,158 @@
+//
+// sqlite_export_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+use rusqlite::Connection;
+use rust_stdf::{sqlite_export::*, StdfRecord, MPR, PIR};
+#[cfg(feature = "gzip")]
+use rust_stdf::{stdf_file::*, stdf_record_type::*};
+
+#[cfg(feature = "gzip")]
+fn count(conn: &Connection, sql: &str, file_id: i64) -> i64 {
+    conn.query_row(sql, [file_id], |row| row.get(0)).unwrap()
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn export_sqlite_test() {
+    let src = demo_path("lot2.stdf.gz");
+    let mut reader = StdfReader::new(&src).unwrap();
+    let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    let prr_cnt = records.iter().filter(|r| r.is_type(REC_PRR)).count() as i64;
+    let bin_cnt = records
+        .iter()
+        .filter(|r| r.is_type(REC_HBR | REC_SBR))
+        .count() as i64;
+    let pmr_cnt = records.iter().filter(|r| r.is_type(REC_PMR)).count() as i64;
+    let lot_id = records
+        .iter()
+        .find_map(|r| match r {
+            StdfRecord::MIR(mir_rec) => Some(mir_rec.lot_id.clone()),
+            _ => None,
+        })
+        .unwrap();
+
+    let db_path = std::env::temp_dir().join("rust_stdf_sqlite_test.db");
+    let _ = std::fs::remove_file(&db_path);
+    let first = export_sqlite(&src, &db_path).unwrap();
+    // another file is appended
+    let second = export_sqlite(&src, &db_path).unwrap();
+    assert_ne!(first, second);
+
+    let conn = Connection::open(&db_path).unwrap();
+    for file_id in [first, second] {
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM parts WHERE file_id = ?1",
+                file_id
+            ),
+            prr_cnt
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM bins WHERE file_id = ?1",
+                file_id
+            ),
+            bin_cnt
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM pins WHERE file_id = ?1",
+                file_id
+            ),
+            pmr_cnt
+        );
+    }
+    let stored_lot: String = conn
+        .query_row(
+            "SELECT lot_id FROM file_info WHERE file_id = ?1",
+            [first],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(stored_lot, lot_id);
+    // every result belongs to a part and a test
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM results r LEFT JOIN parts p
+            ON r.file_id = p.file_id AND r.part_index = p.part_index
+            WHERE r.file_id = ?1 AND p.part_index IS NULL",
+            first
+        ),
+        0
+    );
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM results r LEFT JOIN tests t
+            ON r.file_id = t.file_id AND r.test_num = t.test_num AND r.test_typ = t.test_typ
+            WHERE r.file_id = ?1 AND t.test_num IS NULL",
+            first
+        ),
+        0
+    );
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+}
+
+#[test]
+fn sqlite_mpr_pins_test() {
+    let mpr = |site_num: u8, rtn_indx: Option<Vec<u16>>| {
+        StdfRecord::MPR(MPR {
+            test_num: 10,
+            site_num,
+            rtn_icnt: 2,
+            rslt_cnt: 2,
+            rtn_rslt: vec![1.0, 2.0],
+            rtn_indx,
+            ..Default::default()
+        })
+    };
+    let pir = |site_num: u8| {
+        StdfRecord::PIR(PIR {
+            head_num: 0,
+            site_num,
+        })
+    };
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(SCHEMA).unwrap();
+    let mut loader = SqliteLoader::new(&conn, "two_sites.stdf").unwrap();
+    for rec in [
+        pir(1),
+        pir(2),
+        // every site has its own pins
+        mpr(1, Some(vec![1, 2])),
+        mpr(2, Some(vec![3, 4])),
+        // omitted, use the first MPR of the test
+        mpr(2, None),
+    ] {
+        loader.feed(&rec).unwrap();
+    }
+    let file_id = loader.finish();
+    let mut stmt = conn
+        .prepare("SELECT pmr_indx FROM results WHERE file_id = ?1 ORDER BY rowid")
+        .unwrap();
+    let pmr_indx: Vec<u16> = stmt
+        .query_map([file_id], |row| row.get(0))
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(pmr_indx, [1, 2, 3, 4, 1, 2]);
+}
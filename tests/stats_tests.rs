@@ -0,0 +1,145 @@
+//
+// stats_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rand::prelude::*;
+use rust_stdf::{stats::*, StdfRecord, PTR};
+
+#[test]
+fn ptr_stats_test() {
+    let mut builder = StatsBuilder::new();
+    // first PTR provides the limits in A, scaled to mA
+    builder.feed(&StdfRecord::PTR(PTR {
+        test_num: 1,
+        head_num: 1,
+        site_num: 0,
+        result: 0.001,
+        test_txt: "idd".to_string(),
+        opt_flag: Some([0b0000_1110]),
+        res_scal: Some(3),
+        llm_scal: Some(3),
+        hlm_scal: Some(3),
+        lo_limit: Some(0.0),
+        hi_limit: Some(0.01),
+        units: Some("A".to_string()),
+        ..Default::default()
+    }));
+    // the rest omit the optional fields
+    let mut values: Vec<f64> = (2..=1000).map(|i| i as f64).collect();
+    values.shuffle(&mut rand::thread_rng());
+    for (i, &v) in values.iter().enumerate() {
+        builder.feed(&StdfRecord::PTR(PTR {
+            test_num: 1,
+            head_num: 1,
+            site_num: (i % 2 + 1) as u8,
+            // bit 7: failed
+            test_flg: [if v > 10.0 { 0b1000_0000 } else { 0 }],
+            result: (v / 1000.0) as f32,
+            ..Default::default()
+        }));
+    }
+    // not executed, ignored
+    builder.feed(&StdfRecord::PTR(PTR {
+        test_num: 1,
+        head_num: 1,
+        site_num: 1,
+        test_flg: [0b0001_0000],
+        result: 1000.0,
+        ..Default::default()
+    }));
+    // invalid result, counted as an execution only
+    builder.feed(&StdfRecord::PTR(PTR {
+        test_num: 1,
+        head_num: 1,
+        site_num: 1,
+        test_flg: [0b0000_0010],
+        result: 1000.0,
+        ..Default::default()
+    }));
+
+    let stats = builder.get_test_stats().get(&1).unwrap();
+    assert_eq!(stats.test_txt, "idd");
+    assert_eq!(stats.units, "A");
+    assert_eq!(stats.exec_cnt, 1001);
+    assert_eq!(stats.count, 1000);
+    assert_eq!(stats.fail_cnt, 990);
+    assert!((stats.lo_limit.unwrap() - 0.0).abs() < 1e-9);
+    assert!((stats.hi_limit.unwrap() - 10.0).abs() < 1e-5);
+    // values are 1..=1000 mA
+    assert!((stats.min - 1.0).abs() < 1e-4);
+    assert!((stats.max - 1000.0).abs() < 1e-3);
+    assert!((stats.mean().unwrap() - 500.5).abs() < 1e-3);
+    let expected_sd = (1000.0 * 1001.0 / 12.0_f64).sqrt();
+    assert!((stats.stdev().unwrap() - expected_sd).abs() < 1e-3);
+    // P² estimation
+    assert!((stats.median().unwrap() - 500.5).abs() < 20.0);
+    let quantiles = stats.quantiles();
+    assert_eq!(quantiles.len(), 2);
+    assert!((quantiles[0].1.unwrap() - 250.75).abs() < 20.0);
+    assert!((quantiles[1].1.unwrap() - 750.25).abs() < 20.0);
+    // capability
+    let cp = (10.0 - 0.0) / (6.0 * expected_sd);
+    assert!((stats.cp().unwrap() - cp).abs() < 1e-6);
+    let cpk = (10.0 - 500.5) / (3.0 * expected_sd);
+    assert!((stats.cpk().unwrap() - cpk).abs() < 1e-4);
+    assert!((stats.yield_rate().unwrap() - 11.0 / 1001.0).abs() < 1e-9);
+
+    // per site breakdown
+    let site_stats = builder.get_site_stats();
+    assert_eq!(site_stats.len(), 3);
+    let total: u64 = site_stats.values().map(|s| s.count).sum();
+    assert_eq!(total, 1000);
+    assert_eq!(site_stats.get(&(1, 2, 1)).unwrap().count, 499);
+}
+
+#[test]
+fn small_sample_stats_test() {
+    let options = StatsOptions {
+        apply_scaling: false,
+        quantiles: vec![0.0, 1.0],
+    };
+    let mut builder = StatsBuilder::with_options(options);
+    for v in [3.0, 1.0, 2.0] {
+        builder.feed(&StdfRecord::PTR(PTR {
+            test_num: 7,
+            result: v,
+            opt_flag: Some([0b1100_1110]),
+            res_scal: Some(3),
+            ..Default::default()
+        }));
+    }
+    let stats = builder.get_test_stats().get(&7).unwrap();
+    assert_eq!(stats.median(), Some(2.0));
+    assert_eq!(stats.quantiles(), vec![(0.0, Some(1.0)), (1.0, Some(3.0))]);
+    assert_eq!(stats.stdev(), Some(1.0));
+    // no limits
+    assert_eq!(stats.lo_limit, None);
+    assert_eq!(stats.cp(), None);
+    assert_eq!(stats.cpk(), None);
+}
+
+#[test]
+fn out_of_range_quantile_stats_test() {
+    let options = StatsOptions {
+        apply_scaling: false,
+        quantiles: vec![-0.5, 1.5],
+    };
+    let mut builder = StatsBuilder::with_options(options);
+    for v in [3.0, 1.0] {
+        builder.feed(&StdfRecord::PTR(PTR {
+            test_num: 7,
+            result: v,
+            ..Default::default()
+        }));
+    }
+    let stats = builder.get_test_stats().get(&7).unwrap();
+    // clamped to [0, 1]
+    assert_eq!(stats.quantiles(), vec![(0.0, Some(1.0)), (1.0, Some(3.0))]);
+}
@@ -17,6 +17,13 @@ use std::{
     path::PathBuf,
 };
 
+#[cfg(any(feature = "gzip", feature = "bzip", feature = "zipfile"))]
+use rust_stdf::ByteOrder;
+#[cfg(any(feature = "gzip", feature = "bzip"))]
+use rust_stdf::CompressType;
+#[cfg(any(feature = "gzip", feature = "bzip"))]
+use std::io::Cursor;
+
 fn get_test_stdf_files() -> Vec<PathBuf> {
     let mut test_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     test_folder.push("demo_stdf");
@@ -108,3 +115,69 @@ fn supported_stdf_file_test() {
         }
     }
 }
+
+#[test]
+#[cfg(any(feature = "gzip", feature = "bzip"))]
+fn stdf_writer_roundtrip_test() {
+    let stdf_file_list = get_test_stdf_files();
+    assert_ne!(stdf_file_list.len(), 0);
+
+    for file in stdf_file_list.iter() {
+        let mut reader =
+            StdfReader::new(file).unwrap_or_else(|_| panic!("error when open {}", file.display()));
+        let src_order = reader.get_byte_order();
+        let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+
+        // serialize every record and parse it again
+        for rec in records.iter() {
+            let data = rec.write_to_bytes(&src_order);
+            let mut parsed = StdfRecord::new(rec.get_type());
+            parsed.read_from_bytes(&data, &src_order);
+            assert_eq!(rec, &parsed);
+        }
+
+        // dump to the other byte order, compressed or not
+        let dst_order = match src_order {
+            ByteOrder::LittleEndian => ByteOrder::BigEndian,
+            ByteOrder::BigEndian => ByteOrder::LittleEndian,
+        };
+        let compress_list = [
+            CompressType::Uncompressed,
+            #[cfg(feature = "gzip")]
+            CompressType::GzipCompressed,
+            #[cfg(feature = "bzip")]
+            CompressType::BzipCompressed,
+        ];
+
+        for compress in compress_list.iter() {
+            let mut writer = StdfWriter::from(Vec::new(), compress, dst_order).unwrap();
+            for rec in records.iter() {
+                writer.write_record(rec).unwrap();
+            }
+            let data = writer.finish().unwrap();
+
+            let mut new_reader = StdfReader::from(Cursor::new(data), compress).unwrap();
+            assert_eq!(new_reader.get_byte_order(), dst_order);
+            let new_records: Vec<StdfRecord> =
+                new_reader.get_record_iter().map(|r| r.unwrap()).collect();
+            // CPU_TYPE of FAR follows the writer
+            match &new_records[0] {
+                StdfRecord::FAR(far_rec) => assert_eq!(far_rec.cpu_type, dst_order.cpu_type()),
+                _ => panic!("first record is not FAR"),
+            }
+            assert_eq!(records[1..], new_records[1..]);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "zipfile")]
+fn stdf_writer_zip_test() {
+    let mut path = std::env::temp_dir();
+    path.push("rust_stdf_writer_zip_test.zip");
+    fs::write(&path, b"existing").unwrap();
+    // zip is rejected without touching the existing file
+    assert!(StdfWriter::new(&path, ByteOrder::LittleEndian).is_err());
+    assert_eq!(fs::read(&path).unwrap(), b"existing");
+    fs::remove_file(&path).unwrap();
+}
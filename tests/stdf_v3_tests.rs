@@ -0,0 +1,265 @@
+//
+// stdf_v3_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "v3")]
+
+use rust_stdf::{stdf_file::*, stdf_v3::*, ByteOrder, CompressType, StdfRecord};
+use std::io::Cursor;
+
+fn record(typ: u8, sub: u8, data: &[u8]) -> Vec<u8> {
+    let mut rec = (data.len() as u16).to_le_bytes().to_vec();
+    rec.extend([typ, sub]);
+    rec.extend(data);
+    rec
+}
+
+fn cn(s: &str) -> Vec<u8> {
+    let mut data = vec![s.len() as u8];
+    data.extend(s.as_bytes());
+    data
+}
+
+/// a little endian V3 file with 1 parametric test,
+/// 1 functional test and 1 part
+fn v3_file() -> Vec<u8> {
+    let mut file = record(0, 10, &[2, 3]);
+    // MIR
+    let mut mir = vec![2, 3, b'P', 1];
+    mir.extend(b"ABC");
+    mir.extend([b' ', b' ', b' ']);
+    mir.extend(100u32.to_le_bytes());
+    mir.extend(200u32.to_le_bytes());
+    for s in ["LOT1", "PART", "JOB", "OP", "NODE", "TSTR", "EXEC", "SUPR"] {
+        mir.extend(cn(s));
+    }
+    for s in ["HANDLER", "SBLOT", "REV", "PROC", "CARD"] {
+        mir.extend(cn(s));
+    }
+    file.extend(record(1, 10, &mir));
+    // PDR
+    let mut pdr = 1u32.to_le_bytes().to_vec();
+    pdr.extend([0, 0b0000_0010, 0]);
+    pdr.extend(b"V      ");
+    pdr.extend([0; 8]);
+    pdr.extend(0.5f32.to_le_bytes());
+    pdr.extend(1.5f32.to_le_bytes());
+    pdr.extend(cn("VOH"));
+    pdr.extend(cn("SEQ"));
+    file.extend(record(10, 10, &pdr));
+    // FDR
+    let mut fdr = 2u32.to_le_bytes().to_vec();
+    fdr.push(0);
+    fdr.extend(cn("FUNC"));
+    fdr.extend(cn("SEQ"));
+    file.extend(record(10, 20, &fdr));
+    // PIR
+    let mut pir = vec![1, 0];
+    pir.extend(3i16.to_le_bytes());
+    pir.extend(4i16.to_le_bytes());
+    pir.extend(cn("1"));
+    file.extend(record(5, 10, &pir));
+    // PTR without descriptive fields
+    let mut ptr = 1u32.to_le_bytes().to_vec();
+    ptr.extend([1, 0, 0, 0]);
+    ptr.extend(1.2f32.to_le_bytes());
+    file.extend(record(15, 10, &ptr));
+    // FTR
+    let mut ftr = 2u32.to_le_bytes().to_vec();
+    ftr.extend([1, 0, 0b1000_0000, 0, 0, 3]);
+    ftr.extend(10u32.to_le_bytes());
+    ftr.extend(20u32.to_le_bytes());
+    ftr.extend(5u16.to_le_bytes());
+    ftr.extend(0u16.to_le_bytes());
+    ftr.extend(1u32.to_le_bytes());
+    ftr.extend([1, 0b0000_0100, 0, 0, 0]);
+    ftr.extend([0, 0, 0]);
+    file.extend(record(15, 20, &ftr));
+    // PRR
+    let mut prr = vec![1, 0];
+    prr.extend(2u16.to_le_bytes());
+    prr.extend(1u16.to_le_bytes());
+    prr.extend(1u16.to_le_bytes());
+    prr.extend([0, 0]);
+    prr.extend(3i16.to_le_bytes());
+    prr.extend(4i16.to_le_bytes());
+    prr.extend(cn("1"));
+    file.extend(record(5, 20, &prr));
+    // STS with invalid alarm count
+    let mut sts = vec![1, 0];
+    sts.extend(1u32.to_le_bytes());
+    sts.extend(1i32.to_le_bytes());
+    sts.extend(0i32.to_le_bytes());
+    sts.extend((-1i32).to_le_bytes());
+    sts.extend([0b1100_1111, 0]);
+    sts.extend([0; 24]);
+    sts.extend(cn("VOH"));
+    sts.extend(cn("SEQ"));
+    sts.extend(cn(""));
+    file.extend(record(25, 30, &sts));
+    // SHB
+    let mut shb = vec![1, 0];
+    shb.extend(1u16.to_le_bytes());
+    shb.extend(1u32.to_le_bytes());
+    shb.extend(cn("PASS"));
+    file.extend(record(25, 10, &shb));
+    // HBR
+    let mut hbr = 1u16.to_le_bytes().to_vec();
+    hbr.extend(1u32.to_le_bytes());
+    hbr.extend(cn("PASS"));
+    file.extend(record(1, 40, &hbr));
+    // MRR
+    let mut mrr = 300u32.to_le_bytes().to_vec();
+    for cnt in [1u32, 0, 0, 1, 0] {
+        mrr.extend(cnt.to_le_bytes());
+    }
+    mrr.push(b' ');
+    mrr.extend(cn(""));
+    mrr.extend(cn(""));
+    file.extend(record(1, 20, &mrr));
+    file
+}
+
+#[test]
+fn v3_record_parse_test() {
+    let mut reader = StdfReader::from(Cursor::new(v3_file()), &CompressType::Uncompressed).unwrap();
+    assert_eq!(reader.get_stdf_version(), 3);
+    let recs: Vec<StdfV3Record> = reader
+        .get_rawdata_iter()
+        .map(|r| StdfV3Record::from(&r.unwrap()))
+        .collect();
+    assert_eq!(recs.len(), 12);
+
+    let StdfV3Record::MIR(mir) = &recs[1] else {
+        panic!("MIR expected, got {:?}", recs[1]);
+    };
+    assert_eq!(mir.test_cod, "ABC");
+    assert_eq!(mir.start_t, 200);
+    assert_eq!(mir.supr_nam, "SUPR");
+    assert_eq!(mir.hand_id, "HANDLER");
+    assert_eq!(mir.prb_card, "CARD");
+
+    let StdfV3Record::PDR(pdr) = &recs[2] else {
+        panic!("PDR expected, got {:?}", recs[2]);
+    };
+    assert_eq!(pdr.units, "V");
+    assert_eq!(pdr.hi_limit, 1.5);
+    assert_eq!(pdr.test_nam, "VOH");
+
+    // truncated PTR
+    let StdfV3Record::PTR(ptr) = &recs[5] else {
+        panic!("PTR expected, got {:?}", recs[5]);
+    };
+    assert_eq!(ptr.result, 1.2);
+    assert_eq!(ptr.opt_flag, None);
+    assert_eq!(ptr.test_nam, None);
+
+    let StdfV3Record::STS(sts) = &recs[8] else {
+        panic!("STS expected, got {:?}", recs[8]);
+    };
+    assert_eq!(sts.alrm_cnt, -1);
+    assert!(matches!(&recs[0], StdfV3Record::V4(StdfRecord::FAR(_))));
+}
+
+#[test]
+fn v3_record_iter_test() {
+    let mut reader = StdfReader::from(Cursor::new(v3_file()), &CompressType::Uncompressed).unwrap();
+    assert_eq!(reader.get_stdf_version(), 3);
+    // V3 records are not parsed in V4 layout
+    let results: Vec<_> = reader.get_record_iter().collect();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn v3_upconvert_test() {
+    let mut reader = StdfReader::from(Cursor::new(v3_file()), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    let count = upconvert_stream(&mut reader, &mut writer).unwrap();
+    let data = writer.finish().unwrap();
+
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    assert_eq!(reader.get_stdf_version(), 4);
+    let recs: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+    // PDR and FDR are dropped, MIR adds SDR, MRR adds PCR
+    assert_eq!(recs.len() as u64, count);
+    assert_eq!(recs.len(), 12);
+
+    let StdfRecord::SDR(sdr) = &recs[2] else {
+        panic!("SDR expected, got {:?}", recs[2]);
+    };
+    assert_eq!(sdr.hand_id, "HANDLER");
+    assert_eq!(sdr.card_id, "CARD");
+
+    let StdfRecord::PTR(ptr) = &recs[4] else {
+        panic!("PTR expected, got {:?}", recs[4]);
+    };
+    assert_eq!(ptr.test_txt, "VOH");
+    assert_eq!(ptr.units, Some("V".to_string()));
+    assert_eq!(ptr.lo_limit, Some(0.5));
+    assert_eq!(ptr.hi_limit, Some(1.5));
+    assert_eq!(ptr.opt_flag, Some([0b0000_1110]));
+
+    let StdfRecord::FTR(ftr) = &recs[5] else {
+        panic!("FTR expected, got {:?}", recs[5]);
+    };
+    assert_eq!(ftr.test_txt, "FUNC");
+    assert_eq!(ftr.rel_vadr, 10);
+    assert_eq!(ftr.rept_cnt, 5);
+    assert_eq!(ftr.time_set, "3");
+
+    let StdfRecord::PRR(prr) = &recs[6] else {
+        panic!("PRR expected, got {:?}", recs[6]);
+    };
+    assert_eq!((prr.x_coord, prr.y_coord), (3, 4));
+
+    let StdfRecord::TSR(tsr) = &recs[7] else {
+        panic!("TSR expected, got {:?}", recs[7]);
+    };
+    assert_eq!(tsr.test_typ, 'P');
+    assert_eq!(tsr.alrm_cnt, 4294967295);
+    assert_eq!(tsr.opt_flag, [0b0000_0111]);
+
+    assert!(matches!(&recs[8], StdfRecord::HBR(h) if h.head_num == 1 && h.hbin_cnt == 1));
+    assert!(matches!(&recs[9], StdfRecord::HBR(h) if h.head_num == 255));
+    assert!(matches!(&recs[10], StdfRecord::PCR(p) if p.head_num == 255 && p.good_cnt == 1));
+    assert!(matches!(&recs[11], StdfRecord::MRR(m) if m.finish_t == 300));
+}
+
+#[test]
+fn v3_upconvert_rejects_v4_test() {
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    writer
+        .write_record(&StdfRecord::FAR(rust_stdf::FAR {
+            cpu_type: 2,
+            stdf_ver: 4,
+        }))
+        .unwrap();
+    let data = writer.finish().unwrap();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    let err = upconvert_stream(&mut reader, &mut writer).unwrap_err();
+    assert_eq!(err.code, 1);
+}
@@ -0,0 +1,237 @@
+//
+// summary_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{stdf_record_type::*, summary::*, StdfRecord, HBR, PCR, PIR, PRR, PTR, SBR, TSR};
+
+fn part(site_num: u8, hard_bin: u16, failed: bool, result: f32) -> Vec<StdfRecord> {
+    vec![
+        StdfRecord::PIR(PIR {
+            head_num: 1,
+            site_num,
+        }),
+        StdfRecord::PTR(PTR {
+            test_num: 100,
+            head_num: 1,
+            site_num,
+            test_flg: [if failed { 0b1000_0000 } else { 0 }],
+            result,
+            test_txt: "vdd".to_string(),
+            ..Default::default()
+        }),
+        StdfRecord::PRR(PRR {
+            head_num: 1,
+            site_num,
+            part_flg: [if failed { 0b0000_1000 } else { 0 }],
+            num_test: 1,
+            hard_bin,
+            soft_bin: hard_bin,
+            ..Default::default()
+        }),
+    ]
+}
+
+#[test]
+fn summary_cross_check_test() {
+    let mut records = vec![];
+    records.extend(part(1, 1, false, 1.0));
+    records.extend(part(2, 1, false, 2.0));
+    records.extend(part(1, 5, true, 3.0));
+    // summaries written by the tester,
+    // only the overall HBR of bin 5 is wrong
+    records.push(StdfRecord::PCR(PCR {
+        head_num: 255,
+        site_num: 255,
+        part_cnt: 3,
+        rtst_cnt: 0,
+        good_cnt: 2,
+        ..Default::default()
+    }));
+    records.push(StdfRecord::HBR(HBR {
+        head_num: 255,
+        site_num: 1,
+        hbin_num: 1,
+        hbin_cnt: 2,
+        hbin_pf: 'P',
+        hbin_nam: "PASS".to_string(),
+    }));
+    records.push(StdfRecord::HBR(HBR {
+        head_num: 255,
+        site_num: 1,
+        hbin_num: 5,
+        hbin_cnt: 2,
+        hbin_pf: 'F',
+        hbin_nam: "FAIL".to_string(),
+    }));
+    records.push(StdfRecord::TSR(TSR {
+        head_num: 255,
+        test_typ: 'P',
+        test_num: 100,
+        exec_cnt: 3,
+        fail_cnt: 1,
+        alrm_cnt: 0,
+        opt_flag: [0b0000_0100],
+        test_min: 1.0,
+        test_max: 3.0,
+        tst_sums: 6.0,
+        tst_sqrs: 14.0,
+        ..Default::default()
+    }));
+
+    let mut builder = SummaryBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+
+    let overall = builder.get_overall_part_count();
+    assert_eq!(overall.part_cnt, 3);
+    assert_eq!(overall.good_cnt, 2);
+    assert_eq!(builder.get_part_counts().len(), 2);
+
+    let mismatches = builder.cross_check();
+    assert_eq!(
+        mismatches,
+        vec![SummaryMismatch {
+            rec_type: REC_HBR,
+            head_num: OVERALL_HEAD,
+            site_num: 0,
+            number: 5,
+            field: "hbin_cnt",
+            in_file: Some(2.0),
+            computed: 1.0,
+        }]
+    );
+
+    // generated records carry the names from the file
+    let hbrs = builder.hbr_records();
+    let overall_bin5 = hbrs
+        .iter()
+        .find(|h| h.head_num == OVERALL_HEAD && h.hbin_num == 5)
+        .unwrap();
+    assert_eq!(overall_bin5.hbin_cnt, 1);
+    assert_eq!(overall_bin5.hbin_nam, "FAIL");
+    let tsrs = builder.tsr_records();
+    assert_eq!(tsrs.len(), 3);
+    assert_eq!(tsrs[2].exec_cnt, 3);
+    assert_eq!(tsrs[2].tst_sqrs, 14.0);
+
+    // a per-site HBR makes the missing bins of that site reported
+    builder.feed(&StdfRecord::HBR(HBR {
+        head_num: 1,
+        site_num: 2,
+        hbin_num: 1,
+        hbin_cnt: 1,
+        hbin_pf: 'P',
+        hbin_nam: "PASS".to_string(),
+    }));
+    assert_eq!(builder.cross_check().len(), 1);
+    builder.feed(&StdfRecord::HBR(HBR {
+        head_num: 1,
+        site_num: 1,
+        hbin_num: 1,
+        hbin_cnt: 1,
+        hbin_pf: 'P',
+        hbin_nam: "PASS".to_string(),
+    }));
+    let mismatches = builder.cross_check();
+    assert_eq!(mismatches.len(), 2);
+    assert_eq!(mismatches[1].head_num, 1);
+    assert_eq!(mismatches[1].site_num, 1);
+    assert_eq!(mismatches[1].number, 5);
+    assert_eq!(mismatches[1].in_file, None);
+}
+
+#[test]
+fn summary_missing_pcr_test() {
+    let mut records = vec![];
+    records.extend(part(1, 1, false, 1.0));
+    records.extend(part(2, 5, true, 2.0));
+    // the tester wrote the PCR of site 1 only
+    records.push(StdfRecord::PCR(PCR {
+        head_num: 1,
+        site_num: 1,
+        part_cnt: 1,
+        rtst_cnt: 0,
+        good_cnt: 1,
+        ..Default::default()
+    }));
+
+    let mut builder = SummaryBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+    assert_eq!(
+        builder.cross_check(),
+        vec![SummaryMismatch {
+            rec_type: REC_PCR,
+            head_num: 1,
+            site_num: 2,
+            number: 0,
+            field: "part_cnt",
+            in_file: None,
+            computed: 1.0,
+        }]
+    );
+}
+
+#[test]
+fn bin_summary_test() {
+    let mut records = vec![];
+    records.extend(part(1, 1, false, 1.0));
+    records.extend(part(2, 1, false, 2.0));
+    records.extend(part(1, 5, true, 3.0));
+
+    // no HBR/SBR, counted from PRR
+    let mut builder = SummaryBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+    let bins = builder.bin_summary();
+    assert_eq!(bins.hbin_source(), BinSource::PartResults);
+    assert_eq!(bins.get_sites(), vec![(1, 1), (1, 2)]);
+    let bin1 = bins.get_hbin(1).unwrap();
+    assert_eq!((bin1.bin_cnt, bin1.is_pass()), (2, true));
+    assert!(bins.get_hbin(5).unwrap().is_fail());
+    assert_eq!(bins.get_site_hbins(1, 2).unwrap().len(), 1);
+
+    // per-site HBRs without overall records, SBRs
+    // with overall records only
+    for (site_num, hbin_num, hbin_cnt) in [(1, 1, 1), (1, 5, 1), (2, 1, 1)] {
+        records.push(StdfRecord::HBR(HBR {
+            head_num: 1,
+            site_num,
+            hbin_num,
+            hbin_cnt,
+            hbin_pf: ' ',
+            hbin_nam: if hbin_num == 5 { "OS" } else { "" }.to_string(),
+        }));
+    }
+    records.push(StdfRecord::SBR(SBR {
+        head_num: 255,
+        site_num: 0,
+        sbin_num: 1,
+        sbin_cnt: 9,
+        sbin_pf: 'F',
+        sbin_nam: "SOFT".to_string(),
+    }));
+    let mut builder = SummaryBuilder::new();
+    records.iter().for_each(|r| builder.feed(r));
+    let bins = builder.bin_summary();
+    assert_eq!(bins.hbin_source(), BinSource::SummaryRecords);
+    assert_eq!(bins.get_overall_hbins().len(), 2);
+    let bin5 = bins.get_hbin(5).unwrap();
+    assert_eq!(bin5.bin_cnt, 1);
+    assert_eq!(bin5.bin_nam, "OS");
+    // pass/fail is not in HBR, guessed from PRR
+    assert_eq!(bin5.bin_pf, 'F');
+    assert_eq!(bins.get_hbin(1).unwrap().bin_cnt, 2);
+
+    assert_eq!(bins.sbin_source(), BinSource::SummaryRecords);
+    let sbin1 = bins.get_sbin(1).unwrap();
+    assert_eq!((sbin1.bin_cnt, sbin1.bin_pf), (9, 'F'));
+    assert_eq!(sbin1.bin_nam, "SOFT");
+    // no per-site SBR, counted from PRR
+    assert_eq!(bins.get_site_sbins(1, 1).unwrap()[&1].bin_cnt, 1);
+    assert!(bins.get_site_sbins(1, 3).is_none());
+}
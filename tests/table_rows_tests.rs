@@ -0,0 +1,124 @@
+//
+// table_rows_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+use rust_stdf::{table_rows::*, StdfRecord, HBR, PIR, PRR, PTR, WIR, WRR};
+
+fn normalize(records: &[StdfRecord]) -> Vec<TableRow> {
+    let mut normalizer = RowNormalizer::new();
+    let mut rows = vec![];
+    for rec in records {
+        normalizer.feed(rec, &mut rows);
+    }
+    rows
+}
+
+#[test]
+fn table_rows_part_test() {
+    let ptr = |site_num: u8, test_flg: u8| {
+        StdfRecord::PTR(PTR {
+            test_num: 1,
+            site_num,
+            test_flg: [test_flg],
+            result: 0.5,
+            ..Default::default()
+        })
+    };
+    let rows = normalize(&[
+        StdfRecord::WIR(WIR {
+            wafer_id: "W01".to_string(),
+            start_t: 100,
+            ..Default::default()
+        }),
+        StdfRecord::PIR(PIR {
+            head_num: 0,
+            site_num: 1,
+        }),
+        ptr(1, 0),
+        // not executed
+        ptr(1, 0b0001_0000),
+        // not valid
+        ptr(1, 0b0000_0010),
+        // without PIR
+        ptr(2, 0),
+        StdfRecord::PRR(PRR {
+            site_num: 1,
+            x_coord: -32768,
+            y_coord: 3,
+            soft_bin: 65535,
+            ..Default::default()
+        }),
+        StdfRecord::HBR(HBR {
+            hbin_pf: ' ',
+            ..Default::default()
+        }),
+        StdfRecord::WRR(WRR {
+            wafer_id: "W01".to_string(),
+            rtst_cnt: u32::MAX,
+            good_cnt: 10,
+            ..Default::default()
+        }),
+    ]);
+
+    let tests: Vec<&TestRow> = rows
+        .iter()
+        .filter_map(|r| match r {
+            TableRow::Test(t) => Some(t),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(tests.len(), 1);
+
+    let results: Vec<&ResultRow> = rows
+        .iter()
+        .filter_map(|r| match r {
+            TableRow::Result(r) => Some(r),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        results
+            .iter()
+            .map(|r| (r.part_index, r.result))
+            .collect::<Vec<_>>(),
+        [(0, Some(0.5)), (0, None), (1, Some(0.5))]
+    );
+
+    let part = rows
+        .iter()
+        .find_map(|r| match r {
+            TableRow::Part(p) => Some(p),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(part.part_index, 0);
+    assert_eq!(part.wafer_id.as_deref(), Some("W01"));
+    assert_eq!((part.x_coord, part.y_coord), (None, Some(3)));
+    assert_eq!(part.soft_bin, None);
+
+    let bin = rows
+        .iter()
+        .find_map(|r| match r {
+            TableRow::Bin(b) => Some(b),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!((bin.bin_typ, bin.bin_pf), ('H', None));
+
+    let wafer = rows
+        .iter()
+        .find_map(|r| match r {
+            TableRow::Wafer(w) => Some(w),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(wafer.start_t, Some(100));
+    assert_eq!((wafer.rtst_cnt, wafer.good_cnt), (None, Some(10)));
+}
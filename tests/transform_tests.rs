@@ -0,0 +1,170 @@
+//
+// transform_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use common::demo_path;
+use rust_stdf::{
+    stdf_file::*, stdf_record_type::*, transform::*, ByteOrder, CompressType, StdfRecord,
+};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+fn demo_records(order: ByteOrder) -> Vec<u8> {
+    let mut reader = StdfReader::new(demo_path("lot2.stdf.gz")).unwrap();
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    for raw in reader.get_rawdata_iter() {
+        writer.write_raw_data(&raw.unwrap()).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+fn read_all(data: Vec<u8>) -> Vec<StdfRecord> {
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    reader.get_record_iter().map(|r| r.unwrap()).collect()
+}
+
+#[test]
+fn transform_stream_test() {
+    let data = demo_records(ByteOrder::LittleEndian);
+    let source = read_all(data.clone());
+    let ptr_cnt = source.iter().filter(|r| r.is_type(REC_PTR)).count();
+    let prr_cnt = source.iter().filter(|r| r.is_type(REC_PRR)).count();
+    let dropped_cnt = source
+        .iter()
+        .filter(|r| r.is_type(REC_FTR | REC_DTR | REC_GDR))
+        .count();
+    let first_test = source
+        .iter()
+        .find_map(|r| match r {
+            StdfRecord::PTR(ptr_rec) => Some(ptr_rec.test_num),
+            _ => None,
+        })
+        .unwrap();
+
+    let mut transform = Transform::new()
+        .drop_types(REC_FTR | REC_DTR | REC_GDR)
+        // a longer test name changes the record length
+        .modify(REC_PTR, |rec| {
+            if let StdfRecord::PTR(ptr_rec) = rec {
+                if ptr_rec.test_num == first_test {
+                    ptr_rec.test_txt = "RENAMED_TEST_WITH_A_LONGER_NAME".to_string();
+                }
+            }
+        })
+        // insert a DTR after every PRR, it is not
+        // dropped since the drop step is before
+        .step(REC_PRR, |rec| {
+            let mut dtr = StdfRecord::new(REC_DTR);
+            if let StdfRecord::DTR(ref mut dtr_rec) = dtr {
+                dtr_rec.text_dat = "part done".to_string();
+            }
+            Action::Replace(vec![rec.clone(), dtr])
+        });
+
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::LittleEndian,
+    )
+    .unwrap();
+    let report = transform.run_stream(&mut reader, &mut writer).unwrap();
+    let output = read_all(writer.finish().unwrap());
+
+    assert_eq!(report.read_count, source.len() as u64);
+    assert_eq!(report.write_count, output.len() as u64);
+    assert_eq!(output.len(), source.len() - dropped_cnt + prr_cnt);
+    assert!(output.iter().all(|r| !r.is_type(REC_FTR | REC_GDR)));
+    assert_eq!(
+        output.iter().filter(|r| r.is_type(REC_PTR)).count(),
+        ptr_cnt
+    );
+    assert_eq!(
+        output.iter().filter(|r| r.is_type(REC_DTR)).count(),
+        prr_cnt
+    );
+    for (i, rec) in output.iter().enumerate() {
+        if rec.is_type(REC_PRR) {
+            assert!(output[i + 1].is_type(REC_DTR));
+        }
+        if let StdfRecord::PTR(ptr_rec) = rec {
+            assert_eq!(
+                ptr_rec.test_num == first_test,
+                ptr_rec.test_txt == "RENAMED_TEST_WITH_A_LONGER_NAME"
+            );
+        }
+    }
+}
+
+#[test]
+fn transform_byte_order_test() {
+    let data = demo_records(ByteOrder::LittleEndian);
+    let source = read_all(data.clone());
+
+    let mut transform = Transform::new();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let mut writer = StdfWriter::from(
+        Vec::new(),
+        &CompressType::Uncompressed,
+        ByteOrder::BigEndian,
+    )
+    .unwrap();
+    transform.run_stream(&mut reader, &mut writer).unwrap();
+    let data = writer.finish().unwrap();
+
+    let reader = StdfReader::from(Cursor::new(data.clone()), &CompressType::Uncompressed).unwrap();
+    assert_eq!(reader.get_byte_order(), ByteOrder::BigEndian);
+    let output = read_all(data);
+    assert_eq!(output.len(), source.len());
+    match &output[0] {
+        StdfRecord::FAR(far_rec) => assert_eq!(far_rec.cpu_type, 1),
+        _ => panic!("first record is not FAR"),
+    }
+    assert_eq!(output[1..], source[1..]);
+}
+
+#[test]
+fn convert_byte_order_test() {
+    let stdf_path = demo_path("lot3.stdf.gz");
+    let be_path = std::env::temp_dir().join("rust_stdf_convert_be.stdf");
+    let le_path = std::env::temp_dir().join("rust_stdf_convert_le.stdf.gz");
+
+    let report = convert_byte_order(&stdf_path, &be_path, ByteOrder::BigEndian).unwrap();
+    assert_eq!(report.read_count, report.write_count);
+    convert_byte_order(&be_path, &le_path, ByteOrder::LittleEndian).unwrap();
+
+    let read_file = |path: &PathBuf| {
+        let mut reader = StdfReader::new(path).unwrap();
+        let order = reader.get_byte_order();
+        let records: Vec<StdfRecord> = reader.get_record_iter().map(|r| r.unwrap()).collect();
+        (order, records)
+    };
+    let (_, source) = read_file(&stdf_path);
+    let (be_order, be_records) = read_file(&be_path);
+    let (le_order, le_records) = read_file(&le_path);
+    let _ = std::fs::remove_file(&be_path);
+    let _ = std::fs::remove_file(&le_path);
+
+    assert_eq!(be_order, ByteOrder::BigEndian);
+    assert_eq!(le_order, ByteOrder::LittleEndian);
+    match (&be_records[0], &le_records[0]) {
+        (StdfRecord::FAR(be_far), StdfRecord::FAR(le_far)) => {
+            assert_eq!(be_far.cpu_type, 1);
+            assert_eq!(le_far.cpu_type, 2);
+        }
+        _ => panic!("first record is not FAR"),
+    }
+    assert_eq!(be_records[1..], source[1..]);
+    assert_eq!(le_records[1..], source[1..]);
+}
@@ -0,0 +1,170 @@
+//
+// validate_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+use rust_stdf::{
+    stdf_file::*, stdf_record_type::*, validate::*, ByteOrder, CompressType, StdfRecord, HBR, MPR,
+    PIR, PRR, PTR, SBR, WRR,
+};
+use std::io::Cursor;
+
+#[test]
+#[cfg(feature = "gzip")]
+fn demo_file_validate_test() {
+    for name in ["lot2.stdf.gz", "lot3.stdf.gz"] {
+        let stdf_path = demo_path(name);
+        let diagnostics = validate_file(&stdf_path).unwrap();
+        assert!(diagnostics.is_empty(), "{}: {:?}", name, diagnostics);
+    }
+}
+
+#[test]
+fn violation_validate_test() {
+    let order = ByteOrder::LittleEndian;
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    let mut far = StdfRecord::new(REC_FAR);
+    if let StdfRecord::FAR(ref mut far_rec) = far {
+        far_rec.cpu_type = 2;
+        far_rec.stdf_ver = 4;
+    }
+    let records = vec![
+        far,
+        // PIR before MIR
+        StdfRecord::PIR(PIR {
+            head_num: 1,
+            site_num: 1,
+        }),
+        StdfRecord::new(REC_MIR),
+        // PIR of site 1 is not closed
+        StdfRecord::PIR(PIR {
+            head_num: 1,
+            site_num: 1,
+        }),
+        // failed without pass/fail indication
+        StdfRecord::PTR(PTR {
+            head_num: 1,
+            site_num: 1,
+            test_flg: [0b1100_0000],
+            ..Default::default()
+        }),
+        // rtn_icnt != rslt_cnt
+        StdfRecord::MPR(MPR {
+            head_num: 1,
+            site_num: 1,
+            rtn_icnt: 2,
+            rslt_cnt: 1,
+            rtn_stat: vec![0, 0],
+            rtn_rslt: vec![0.0],
+            ..Default::default()
+        }),
+        StdfRecord::PRR(PRR {
+            head_num: 1,
+            site_num: 1,
+            ..Default::default()
+        }),
+        // PRR of site 2 has no PIR
+        StdfRecord::PRR(PRR {
+            head_num: 1,
+            site_num: 2,
+            ..Default::default()
+        }),
+        // WRR without WIR
+        StdfRecord::WRR(WRR {
+            head_num: 1,
+            ..Default::default()
+        }),
+        StdfRecord::SBR(SBR {
+            sbin_num: 1,
+            ..Default::default()
+        }),
+        StdfRecord::SBR(SBR {
+            sbin_num: 1,
+            ..Default::default()
+        }),
+        // MRR is absent
+    ];
+    for rec in records.iter() {
+        writer.write_record(rec).unwrap();
+    }
+    let mut data = writer.finish().unwrap();
+    // an MPR claims 3 results but only 1 is written
+    let mut mpr = StdfRecord::new(REC_MPR);
+    if let StdfRecord::MPR(ref mut mpr_rec) = mpr {
+        mpr_rec.rslt_cnt = 1;
+        mpr_rec.rtn_rslt = vec![1.0];
+    }
+    let mut mpr_data = mpr.write_to_bytes_with_header(&order).unwrap();
+    // rslt_cnt is at offset 4 (header) + 10
+    mpr_data[14] = 3;
+    data.extend(mpr_data);
+
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let diagnostics = validate_stream(&mut reader);
+    let kinds: Vec<DiagnosticKind> = diagnostics.iter().map(|d| d.kind).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            DiagnosticKind::MirMisplaced,
+            DiagnosticKind::PirWithoutPrr,
+            DiagnosticKind::FlagInconsistent,
+            DiagnosticKind::CountMismatch,
+            DiagnosticKind::PrrWithoutPir,
+            DiagnosticKind::WrrWithoutWir,
+            DiagnosticKind::DuplicatedBin,
+            DiagnosticKind::CountMismatch,
+            DiagnosticKind::MissingMrr,
+        ]
+    );
+    // offset points to the record header
+    assert_eq!(diagnostics[0].offset, 4 + 2);
+    assert!(diagnostics[0].rec_type == REC_PIR);
+    assert_eq!(diagnostics[2].severity, Severity::Error);
+    assert_eq!(diagnostics[3].severity, Severity::Warning);
+}
+
+#[test]
+fn overall_bin_validate_test() {
+    let order = ByteOrder::LittleEndian;
+    let mut writer = StdfWriter::from(Vec::new(), &CompressType::Uncompressed, order).unwrap();
+    let hbr = |head_num: u8, site_num: u8| {
+        StdfRecord::HBR(HBR {
+            head_num,
+            site_num,
+            hbin_num: 1,
+            ..Default::default()
+        })
+    };
+    for rec in [
+        StdfRecord::new(REC_FAR),
+        StdfRecord::new(REC_MIR),
+        // bins of different sites
+        hbr(1, 1),
+        hbr(1, 2),
+        // site is ignored for all sites
+        hbr(255, 1),
+        hbr(255, 2),
+        StdfRecord::new(REC_MRR),
+    ] {
+        writer.write_record(&rec).unwrap();
+    }
+    let data = writer.finish().unwrap();
+    let mut reader = StdfReader::from(Cursor::new(data), &CompressType::Uncompressed).unwrap();
+    let duplicated: Vec<Diagnostic> = validate_stream(&mut reader)
+        .into_iter()
+        .filter(|d| d.kind == DiagnosticKind::DuplicatedBin)
+        .collect();
+    assert_eq!(duplicated.len(), 1);
+    assert!(duplicated[0].message.contains("head 255"));
+}
@@ -0,0 +1,138 @@
+//
+// wafermap_tests.rs
+// Author: noonchen - chennoon233@foxmail.com
+// Created Date: October 18th 2026
+// -----
+// Last Modified: Sun Oct 18 2026
+// Modified By: noonchen
+// -----
+// Copyright (c) 2026 noonchen
+//
+
+mod common;
+
+#[cfg(feature = "gzip")]
+use common::demo_path;
+#[cfg(feature = "gzip")]
+use rust_stdf::stdf_file::*;
+use rust_stdf::{wafermap::*, StdfRecord, PRR, WIR, WRR};
+
+fn prr(x_coord: i16, y_coord: i16, hard_bin: u16) -> StdfRecord {
+    StdfRecord::PRR(PRR {
+        head_num: 1,
+        site_num: 1,
+        // bin 1 passed, others failed
+        part_flg: [if hard_bin == 1 { 0 } else { 0b0000_1000 }],
+        hard_bin,
+        soft_bin: hard_bin,
+        x_coord,
+        y_coord,
+        ..Default::default()
+    })
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn demo_file_wafermap_test() {
+    let stdf_path = demo_path("lot2.stdf.gz");
+
+    let mut last_builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    let mut first_builder = WaferMapBuilder::new(RetestPolicy::FirstWins);
+    let mut reader = StdfReader::new(&stdf_path).unwrap();
+    for rec in reader.get_record_iter().map(|r| r.unwrap()) {
+        last_builder.feed(&rec);
+        first_builder.feed(&rec);
+    }
+    let last_maps = last_builder.finish();
+    let first_maps = first_builder.finish();
+    assert_eq!(last_maps.len(), 1);
+    assert_eq!(first_maps.len(), 1);
+
+    let wafer = &last_maps[0];
+    assert_eq!(wafer.wafer_id, "GAL-LOT-02");
+    assert_eq!(wafer.wcr.wf_flat, 'D');
+    assert_eq!(wafer.die_count(), 1456);
+    assert_eq!(wafer.retest_count(), 113);
+    assert_eq!(wafer.bounds(), Some((4, -45, 45, -3)));
+    // good count is equal to the count of bin 1 in HBR
+    assert_eq!(wafer.good_count(), 1389);
+    assert_eq!(wafer.hbin_counts().get(&1), Some(&1389));
+    assert!(wafer.wrr.is_some());
+    // retested dies are failed in the first test
+    assert!(first_maps[0].good_count() < wafer.good_count());
+}
+
+#[test]
+fn retest_wafer_merge_test() {
+    let wir = StdfRecord::WIR(WIR {
+        head_num: 1,
+        wafer_id: "W01".to_string(),
+        ..Default::default()
+    });
+    let wrr = StdfRecord::WRR(WRR {
+        head_num: 1,
+        wafer_id: "W01".to_string(),
+        ..Default::default()
+    });
+    let records = [
+        wir.clone(),
+        prr(0, 0, 1),
+        prr(1, 0, 5),
+        // invalid coordinate
+        prr(-32768, -32768, 1),
+        wrr.clone(),
+        // the same wafer is retested later
+        wir,
+        prr(1, 0, 1),
+        wrr,
+    ];
+    for (policy, bin, yield_rate) in [
+        (RetestPolicy::LastWins, 1, 1.0),
+        (RetestPolicy::FirstWins, 5, 0.5),
+    ] {
+        let mut builder = WaferMapBuilder::new(policy);
+        records.iter().for_each(|r| builder.feed(r));
+        let maps = builder.finish();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].die_count(), 2);
+        let die = maps[0].get_die(1, 0).unwrap();
+        assert_eq!(die.hard_bin, bin);
+        assert_eq!(die.test_cnt, 2);
+        assert_eq!(maps[0].yield_rate(), Some(yield_rate));
+    }
+}
+
+#[test]
+fn empty_wafer_id_test() {
+    let wir = StdfRecord::WIR(WIR {
+        head_num: 1,
+        ..Default::default()
+    });
+    let wrr = StdfRecord::WRR(WRR {
+        head_num: 1,
+        ..Default::default()
+    });
+    let records = [
+        wir.clone(),
+        prr(0, 0, 1),
+        wrr.clone(),
+        // another wafer without wafer id
+        wir,
+        prr(0, 0, 5),
+        wrr,
+        // outside of WIR/WRR
+        prr(0, 0, 7),
+        prr(1, 0, 7),
+    ];
+    let mut builder = WaferMapBuilder::new(RetestPolicy::LastWins);
+    records.iter().for_each(|r| builder.feed(r));
+    let maps = builder.finish();
+    assert_eq!(maps.len(), 3);
+    let bins: Vec<u16> = maps
+        .iter()
+        .map(|m| m.get_die(0, 0).unwrap().hard_bin)
+        .collect();
+    assert_eq!(bins, [1, 5, 7]);
+    assert_eq!(maps[2].die_count(), 2);
+    assert!(maps[0].wrr.is_some() && maps[2].wrr.is_none());
+}